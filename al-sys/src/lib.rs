@@ -48,17 +48,44 @@ macro_rules! al_api {
 			use self::rent::RentSymbols;
 
 
+			#[cfg(target_os = "windows")]
+			const DEFAULT_LIBRARIES: &[&str] = &["soft_oal.dll", "OpenAL32.dll"];
+			#[cfg(target_os = "windows")]
+			const SEARCH_PATH_VAR: &str = "PATH";
+
+			#[cfg(any(target_os = "macos", target_os = "ios"))]
+			const DEFAULT_LIBRARIES: &[&str] = &["libopenal.dylib", "libopenal.1.dylib", "OpenAL.framework/OpenAL", "/System/Library/Frameworks/OpenAL.framework/OpenAL"];
+			#[cfg(any(target_os = "macos", target_os = "ios"))]
+			const SEARCH_PATH_VAR: &str = "DYLD_LIBRARY_PATH";
+
+			#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "ios")))]
+			const DEFAULT_LIBRARIES: &[&str] = &["libopenal.so", "libopenal.so.1"];
+			#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "ios")))]
+			const SEARCH_PATH_VAR: &str = "LD_LIBRARY_PATH";
+
+
 			pub struct AlApi(rent::RentSymbols);
 
 
 			impl AlApi {
+				/// Try each of the platform's default library names in turn.
+				/// If none can be loaded, the error lists every name tried, why it failed,
+				/// and the environment variable the dynamic loader searches.
 				pub fn load_default() -> io::Result<AlApi> {
-					AlApi::from_lib(libloading::Library::new("libopenal.so")
-						.or_else(|_| libloading::Library::new("libopenal.dylib"))
-						.or_else(|_| libloading::Library::new("OpenAL.framework/OpenAL"))
-						.or_else(|_| libloading::Library::new("soft_oal.dll"))
-						.or_else(|_| libloading::Library::new("OpenAL32.dll"))
-					?)
+					let mut failures = Vec::new();
+					for name in DEFAULT_LIBRARIES {
+						match libloading::Library::new(name) {
+							Ok(lib) => return AlApi::from_lib(lib),
+							Err(e) => failures.push(format!("  {}: {}", name, e)),
+						}
+					}
+
+					Err(io::Error::new(io::ErrorKind::NotFound, format!(
+						"Could not load an OpenAL implementation. Tried:\n{}\nSearch path {}={}",
+						failures.join("\n"),
+						SEARCH_PATH_VAR,
+						::std::env::var(SEARCH_PATH_VAR).unwrap_or_default(),
+					)))
 				}
 
 
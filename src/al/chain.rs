@@ -0,0 +1,298 @@
+use std::cmp;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+
+use ::{AltoError, AltoResult};
+use sys;
+use al::*;
+
+
+/// Number of buffers `ChainSource::update` keeps queued: the one playing and the one after it.
+const CHAIN_AHEAD: usize = 2;
+
+
+/// A streaming source that plays an ordered chain of shared buffers, such as an intro followed by a
+/// looping body, without gaps between them.
+///
+/// The chain is queued a couple of buffers at a time, and `update` must be called at least once per
+/// buffer duration to unqueue the buffers that have played and queue the next ones, wrapping around to the
+/// loop point at the end of the chain. A source that underran because `update` was called too late is
+/// restarted from the next buffer, leaving a gap.
+pub struct ChainSource {
+	src: StreamingSource,
+	chain: Vec<Arc<Buffer>>,
+	loop_point: Option<usize>,
+	next: Option<usize>,
+	queued: VecDeque<Arc<Buffer>>,
+}
+
+
+impl StreamingSource {
+	/// Turn this source into a `ChainSource` playing `bufs` in order. After the last buffer, playback
+	/// continues from the buffer at index `loop_point`, or ends if it is `None`.
+	/// Fails with `AltoError::InvalidOperation` if buffers are still queued on this source.
+	pub fn into_chain(self, bufs: Vec<Arc<Buffer>>, loop_point: Option<usize>) -> AltoResult<ChainSource> {
+		if !self.bufs.is_empty() {
+			return Err(AltoError::InvalidOperation);
+		}
+
+		let mut chain = ChainSource{src: self, chain: Vec::new(), loop_point: None, next: None, queued: VecDeque::new()};
+		chain.set_chain(bufs, loop_point)?;
+		Ok(chain)
+	}
+}
+
+
+impl ChainSource {
+	/// The buffers of the current chain, in playback order.
+	pub fn chain(&self) -> &[Arc<Buffer>] { &self.chain }
+	/// Index in the chain from which playback loops, if any.
+	pub fn loop_point(&self) -> Option<usize> { self.loop_point }
+
+
+	/// Replace the chain and its loop point. Buffers that are already queued still play, so on a playing
+	/// source the new chain starts from its first buffer at the end of the buffer after the one currently
+	/// playing, which is at most two buffer boundaries away. To switch loop bodies exactly at the end of a
+	/// loop, call this while the last buffer of the current body is playing, as reported by `playing`.
+	/// Fails with `AltoError::InvalidValue` if `loop_point` is out of range, and with
+	/// `AltoError::WrongDevice` if a buffer belongs to another device.
+	pub fn set_chain(&mut self, bufs: Vec<Arc<Buffer>>, loop_point: Option<usize>) -> AltoResult<()> {
+		if loop_point.is_some_and(|l| l >= bufs.len()) {
+			return Err(AltoError::InvalidValue);
+		}
+		for buf in &bufs {
+			self.src.src.ctx.check_buffer(buf)?;
+		}
+
+		self.next = if bufs.is_empty() { None } else { Some(0) };
+		self.chain = bufs;
+		self.loop_point = loop_point;
+		self.update()
+	}
+
+
+	/// The queued buffer currently playing, or next to play if the source is not playing.
+	pub fn playing(&self) -> AltoResult<Option<&Arc<Buffer>>> {
+		let processed = self.processed()?;
+		Ok(self.queued.get(processed))
+	}
+
+
+	/// Unqueue the buffers that have played and queue the next buffers of the chain, restarting playback
+	/// if the source underran while it was meant to be playing. A chain without a loop point ends once
+	/// all of its buffers have been queued, after which the source stops naturally.
+	/// This shadows `Source::update`, which can still be called as `Source::update(&mut chain)`.
+	pub fn update(&mut self) -> AltoResult<()> {
+		let processed = self.processed()?;
+		if processed > 0 {
+			self.unqueue(processed)?;
+		}
+
+		while self.queued.len() < CHAIN_AHEAD {
+			let index = match self.next {
+				Some(index) => index,
+				None => break,
+			};
+			let buf = self.chain[index].clone();
+			{
+				let _lock = self.src.src.ctx.make_current(true);
+				unsafe { self.src.src.ctx.0.dev.0.alto.0.api.alSourceQueueBuffers(self.src.src.src, 1, &buf.buf); }
+				self.src.src.ctx.get_error()?;
+			}
+			self.queued.push_back(buf);
+			self.next = if index + 1 < self.chain.len() { Some(index + 1) } else { self.loop_point };
+		}
+
+		if self.src.playing && processed > 0 && !self.queued.is_empty() && self.src.state() == SourceState::Stopped {
+			self.src.src.play();
+		}
+		Ok(())
+	}
+
+
+	/// Stop playback, unqueue every buffer and return the underlying source with an empty queue.
+	pub fn into_inner(mut self) -> AltoResult<StreamingSource> {
+		self.src.stop();
+		let len = self.queued.len();
+		self.unqueue(len)?;
+		Ok(self.src)
+	}
+
+
+	fn processed(&self) -> AltoResult<usize> {
+		let _lock = self.src.src.ctx.make_current(true);
+		let mut processed = 0;
+		unsafe { self.src.src.ctx.0.dev.0.alto.0.api.alGetSourcei(self.src.src.src, sys::AL_BUFFERS_PROCESSED, &mut processed); }
+		self.src.src.ctx.get_error()?;
+		Ok(cmp::min(processed.max(0) as usize, self.queued.len()))
+	}
+
+
+	fn unqueue(&mut self, len: usize) -> AltoResult<()> {
+		if self.src.scratch.len() < len {
+			self.src.scratch.resize(len, 0);
+		}
+		{
+			let _lock = self.src.src.ctx.make_current(true);
+			unsafe { self.src.src.ctx.0.dev.0.alto.0.api.alSourceUnqueueBuffers(self.src.src.src, len as sys::ALsizei, self.src.scratch.as_mut_ptr()); }
+			self.src.src.ctx.get_error()?;
+		}
+		self.src.played += self.queued.range(..len).map(|b| frames_duration(b.len as i64, b.frequency())).sum::<Duration>();
+		self.queued.drain(..len);
+		Ok(())
+	}
+}
+
+
+unsafe impl Source for ChainSource {
+	#[inline] fn context(&self) -> &Context { self.src.context() }
+	#[inline] fn as_raw(&self) -> sys::ALuint { self.src.as_raw() }
+	#[inline] fn handle(&self) -> SourceHandle { self.src.handle() }
+	#[inline] fn update(&mut self) -> SourceUpdate<'_> { self.src.update() }
+
+	#[inline] fn state(&self) -> SourceState { self.src.state() }
+	#[inline] fn source_type(&self) -> AltoResult<SourceType> { self.src.source_type() }
+	#[inline] fn play(&mut self) { self.src.play() }
+	#[inline] fn wait_until(&self, state: SourceState, timeout: Duration, poll: Duration) -> AltoResult<bool> { self.src.wait_until(state, timeout, poll) }
+	#[inline] fn wait_until_stopped(&self, timeout: Duration) -> AltoResult<bool> { self.src.wait_until_stopped(timeout) }
+	#[inline] fn soft_play_at_time(&mut self, time: i64) -> AltoResult<()> { self.src.soft_play_at_time(time) }
+	#[inline] fn pause(&mut self) { self.src.pause() }
+	#[inline] fn stop(&mut self) { self.src.stop() }
+	#[inline] fn stop_with_fade(&mut self, duration: Duration) -> AltoResult<()> { self.src.stop_with_fade(duration) }
+	#[inline] fn rewind(&mut self) { self.src.rewind() }
+	#[inline] fn buffer_raw(&self) -> AltoResult<Option<sys::ALuint>> { self.src.buffer_raw() }
+
+	#[inline] fn relative(&self) -> bool { self.src.relative() }
+	#[inline] fn set_relative(&mut self, value: bool) { self.src.set_relative(value) }
+
+	#[inline] fn gain(&self) -> f32 { self.src.gain() }
+	#[inline] fn set_gain(&mut self, value: f32) -> AltoResult<()> { self.src.set_gain(value) }
+	#[inline] fn gain_step(&mut self, target: f32, dt: f32, rate: f32) -> AltoResult<bool> { self.src.gain_step(target, dt, rate) }
+	#[inline] fn fade_gain_to(&mut self, target: f32, duration: Duration) -> AltoResult<FadeHandle> { self.src.fade_gain_to(target, duration) }
+	#[inline] fn is_muted(&self) -> bool { self.src.is_muted() }
+	#[inline] fn set_muted(&mut self, value: bool) -> AltoResult<()> { self.src.set_muted(value) }
+
+	#[inline] fn min_gain(&self) -> f32 { self.src.min_gain() }
+	#[inline] fn set_min_gain(&mut self, value: f32) -> AltoResult<()> { self.src.set_min_gain(value) }
+
+	#[inline] fn max_gain(&self) -> f32 { self.src.max_gain() }
+	#[inline] fn set_max_gain(&mut self, value: f32) -> AltoResult<()> { self.src.set_max_gain(value) }
+
+	#[inline] fn reference_distance(&self) -> f32 { self.src.reference_distance() }
+	#[inline] fn set_reference_distance(&mut self, value: f32) -> AltoResult<()> { self.src.set_reference_distance(value) }
+
+	#[inline] fn rolloff_factor(&self) -> f32 { self.src.rolloff_factor() }
+	#[inline] fn set_rolloff_factor(&mut self, value: f32) -> AltoResult<()> { self.src.set_rolloff_factor(value) }
+
+	#[inline] fn max_distance(&self) -> f32 { self.src.max_distance() }
+	#[inline] fn set_max_distance(&mut self, value: f32) -> AltoResult<()> { self.src.set_max_distance(value) }
+
+	#[inline] fn attenuation(&self) -> Attenuation { self.src.attenuation() }
+	#[inline] fn set_attenuation(&mut self, value: Attenuation) -> AltoResult<()> { self.src.set_attenuation(value) }
+
+	#[inline] fn pitch(&self) -> f32 { self.src.pitch() }
+	#[inline] fn set_pitch(&mut self, value: f32) -> AltoResult<()> { self.src.set_pitch(value) }
+
+	#[inline] fn position<V: From<[f32; 3]>>(&self) -> V { self.src.position() }
+	#[inline] fn set_position<V: AsAlVec3>(&mut self, value: V) -> AltoResult<()> { self.src.set_position(value) }
+
+	#[inline] fn velocity<V: From<[f32; 3]>>(&self) -> V { self.src.velocity() }
+	#[inline] fn set_velocity<V: AsAlVec3>(&mut self, value: V) -> AltoResult<()> { self.src.set_velocity(value) }
+	#[inline] fn update_position_with_velocity<V: AsAlVec3>(&mut self, pos: V, dt: f32) -> AltoResult<()> { self.src.update_position_with_velocity(pos, dt) }
+	#[inline] fn set_head_locked<V: AsAlVec3>(&mut self, offset: V) -> AltoResult<()> { self.src.set_head_locked(offset) }
+	#[inline] fn detach_to_world<V: AsAlVec3>(&mut self, listener_pos: V, listener_orientation: (V, V)) -> AltoResult<()> { self.src.detach_to_world(listener_pos, listener_orientation) }
+
+	#[inline] fn direction<V: From<[f32; 3]>>(&self) -> V { self.src.direction() }
+	#[inline] fn set_direction<V: AsAlVec3>(&mut self, value: V) -> AltoResult<()> { self.src.set_direction(value) }
+
+	#[inline] fn cone_inner_angle(&self) -> f32 { self.src.cone_inner_angle() }
+	#[inline] fn set_cone_inner_angle(&mut self, value: f32) -> AltoResult<()> { self.src.set_cone_inner_angle(value) }
+
+	#[inline] fn cone_outer_angle(&self) -> f32 { self.src.cone_outer_angle() }
+	#[inline] fn set_cone_outer_angle(&mut self, value: f32) -> AltoResult<()> { self.src.set_cone_outer_angle(value) }
+
+	#[inline] fn cone_outer_gain(&self) -> f32 { self.src.cone_outer_gain() }
+	#[inline] fn set_cone_outer_gain(&mut self, value: f32) -> AltoResult<()> { self.src.set_cone_outer_gain(value) }
+	#[inline] fn cone(&self) -> (Angle, Angle, f32) { self.src.cone() }
+	#[inline] fn set_cone(&mut self, inner: Angle, outer: Angle, outer_gain: f32) -> AltoResult<()> { self.src.set_cone(inner, outer, outer_gain) }
+
+	#[inline] fn sec_offset(&self) -> f32 { self.src.sec_offset() }
+	#[inline] fn set_sec_offset(&mut self, value: f32) -> AltoResult<()> { self.src.set_sec_offset(value) }
+
+	#[inline] fn sample_offset(&self) -> sys::ALint { self.src.sample_offset() }
+	#[inline] fn set_sample_offset(&mut self, value: sys::ALint) -> AltoResult<()> { self.src.set_sample_offset(value) }
+
+	#[inline] fn byte_offset(&self) -> sys::ALint { self.src.byte_offset() }
+	#[inline] fn set_byte_offset(&mut self, value: sys::ALint) -> AltoResult<()> { self.src.set_byte_offset(value) }
+	fn seek(&mut self, pos: SeekPos) -> AltoResult<()> {
+		let bytes = self.queued.iter().map(|b| b.size()).sum();
+		let samples = self.queued.iter().map(|b| b.len).sum();
+		let freq = self.queued.front().map(|b| b.frequency()).unwrap_or(0);
+		self.src.src.seek(pos, bytes, samples, freq)
+	}
+	#[inline] fn tell(&self) -> SeekPos { self.src.tell() }
+	fn playback_position(&self) -> AltoResult<Duration> {
+		Ok(queue_position(self.src.played, self.src.src.frame_offset()?, self.queued.iter().map(|b| &**b)))
+	}
+	#[inline] fn snapshot(&self) -> AltoResult<SourceSnapshot> { self.src.snapshot() }
+	#[inline] fn apply_snapshot(&mut self, value: &SourceSnapshot) -> AltoResult<()> { self.src.apply_snapshot(value) }
+
+	#[inline] fn soft_sec_offset_latency(&self) -> AltoResult<(f64, f64)> { self.src.soft_sec_offset_latency() }
+	#[inline] fn soft_position_f64<V: From<[f64; 3]>>(&self) -> AltoResult<V> { self.src.soft_position_f64() }
+	#[inline] fn set_soft_position_f64<V: Into<[f64; 3]>>(&mut self, value: V) -> AltoResult<()> { self.src.set_soft_position_f64(value) }
+	#[inline] fn soft_velocity_f64<V: From<[f64; 3]>>(&self) -> AltoResult<V> { self.src.soft_velocity_f64() }
+	#[inline] fn set_soft_velocity_f64<V: Into<[f64; 3]>>(&mut self, value: V) -> AltoResult<()> { self.src.set_soft_velocity_f64(value) }
+
+	#[inline] fn soft_sample_frac_offset_latency(&self) -> AltoResult<(i64, Duration)> { self.src.soft_sample_frac_offset_latency() }
+	#[inline] fn soft_sample_fixed_offset_latency(&self) -> AltoResult<(i64, Duration)> { self.src.soft_sample_fixed_offset_latency() }
+
+	#[inline] fn soft_sec_length(&self) -> AltoResult<f32> { self.src.soft_sec_length() }
+
+	#[inline] fn soft_sample_length(&self) -> AltoResult<sys::ALint> { self.src.soft_sample_length() }
+
+	#[inline] fn soft_byte_length(&self) -> AltoResult<sys::ALint> { self.src.soft_byte_length() }
+
+	#[inline] fn soft_direct_channels(&self) -> bool { self.src.soft_direct_channels() }
+	#[inline] fn set_soft_direct_channels(&mut self, value: bool) -> AltoResult<()> { self.src.set_soft_direct_channels(value) }
+
+	#[inline] fn soft_direct_channels_mode(&self) -> AltoResult<SoftDirectChannelsMode> { self.src.soft_direct_channels_mode() }
+	#[inline] fn set_soft_direct_channels_mode(&mut self, value: SoftDirectChannelsMode) -> AltoResult<()> { self.src.set_soft_direct_channels_mode(value) }
+
+	#[inline] fn distance_model(&self) -> DistanceModel { self.src.distance_model() }
+	#[inline] fn set_distance_model(&mut self, value: DistanceModel) -> AltoResult<()> { self.src.set_distance_model(value) }
+
+	#[inline] fn soft_spatialization(&self) -> SoftSourceSpatialization { self.src.soft_spatialization() }
+	#[inline] fn set_soft_spatialization(&mut self, value: SoftSourceSpatialization) -> AltoResult<()> { self.src.set_soft_spatialization(value) }
+
+	#[inline] fn soft_resampler(&self) -> AltoResult<sys::ALint> { self.src.soft_resampler() }
+	#[inline] fn set_soft_resampler(&mut self, value: sys::ALint) -> AltoResult<()> { self.src.set_soft_resampler(value) }
+
+	#[inline] fn stereo_angles<V: From<[f32; 2]>>(&self) -> AltoResult<V> { self.src.stereo_angles() }
+	#[inline] fn set_stereo_angles<V: Into<[f32; 2]>>(&mut self, value: V) -> AltoResult<()> { self.src.set_stereo_angles(value) }
+	#[inline] fn pan(&self) -> AltoResult<f32> { self.src.pan() }
+	#[inline] fn set_pan(&mut self, value: f32) -> AltoResult<()> { self.src.set_pan(value) }
+
+	#[inline] fn radius(&self) -> f32 { self.src.radius() }
+	#[inline] fn set_radius(&self, value: f32) -> AltoResult<()> { self.src.set_radius(value) }
+
+	#[inline] fn set_direct_filter<F: Filter>(&mut self, value: &F) -> AltoResult<()> { self.src.set_direct_filter(value) }
+	#[inline] fn clear_direct_filter(&mut self) { self.src.clear_direct_filter() }
+
+	#[inline] fn max_auxiliary_sends(&self) -> AltoResult<sys::ALint> { self.src.max_auxiliary_sends() }
+	#[inline] fn set_aux_send(&mut self, send: sys::ALint, slot: &mut AuxEffectSlot) -> AltoResult<()> { self.src.set_aux_send(send, slot) }
+	#[inline] fn set_aux_send_filter<F: Filter>(&mut self, send: sys::ALint, slot: &mut AuxEffectSlot, filter: &F) -> AltoResult<()> { self.src.set_aux_send_filter(send, slot, filter) }
+	#[inline] fn clear_aux_send(&mut self, send: sys::ALint) { self.src.clear_aux_send(send) }
+
+	#[inline] fn air_absorption_factor(&self) -> f32 { self.src.air_absorption_factor() }
+	#[inline] fn set_air_absorption_factor(&mut self, value: f32) -> AltoResult<()> { self.src.set_air_absorption_factor(value) }
+
+	#[inline] fn room_rolloff_factor(&self) -> f32 { self.src.room_rolloff_factor() }
+	#[inline] fn set_room_rolloff_factor(&mut self, value: f32) -> AltoResult<()> { self.src.set_room_rolloff_factor(value) }
+
+	#[inline] fn cone_outer_gainhf(&self) -> f32 { self.src.cone_outer_gainhf() }
+	#[inline] fn set_cone_outer_gainhf(&mut self, value: f32) -> AltoResult<()> { self.src.set_cone_outer_gainhf(value) }
+
+	#[inline] fn direct_filter_gainhf_auto(&self) -> bool { self.src.direct_filter_gainhf_auto() }
+	#[inline] fn set_direct_filter_gainhf_auto(&mut self, value: bool) -> AltoResult<()> { self.src.set_direct_filter_gainhf_auto(value) }
+}
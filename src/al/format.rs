@@ -1,4 +1,5 @@
 use std::ops::{Deref, DerefMut};
+use std::slice;
 
 use ::{AltoError, AltoResult};
 use sys;
@@ -185,15 +186,46 @@ pub enum SoftMsadpcmFormat {
 /// Implemented by structs that represent a frame of audio samples.
 /// A sample frame is a grouping of audio samples from each channel
 /// of an output format.
+/// Implementors must be `repr(C)` structs of exactly `CHANNELS` samples.
 pub unsafe trait SampleFrame: Copy + 'static {
 	/// Underlying sample type.
 	type Sample: Copy;
 
 
-	/// Length of the frame in samples.
-	fn len() -> usize;
+	/// Number of channels, and so of samples, in the frame.
+	const CHANNELS: usize;
+
+
+	/// Length of the frame in samples. Same as `CHANNELS`.
+	#[inline] fn len() -> usize { Self::CHANNELS }
+	/// Size of the frame in bytes, as used to size buffers and ring buffers for this frame type.
+	#[inline] fn bytes_per_frame() -> usize { mem::size_of::<Self>() }
 	/// The exact format described by this struct.
+	/// `Format::into_raw` resolves it to the token passed to `alBufferData()`.
 	fn format() -> Format;
+
+
+	/// View a slice of interleaved samples, as produced by most decoders, as a slice of frames.
+	/// Fails with `InvalidValue` if the length is not a whole number of frames.
+	fn from_interleaved(samples: &[Self::Sample]) -> AltoResult<&[Self]> {
+		if samples.len() % Self::len() != 0 {
+			return Err(AltoError::InvalidValue);
+		}
+
+		Ok(unsafe { slice::from_raw_parts(samples.as_ptr() as *const Self, samples.len() / Self::len()) })
+	}
+	/// Mutable version of `from_interleaved`.
+	fn from_interleaved_mut(samples: &mut [Self::Sample]) -> AltoResult<&mut [Self]> {
+		if samples.len() % Self::len() != 0 {
+			return Err(AltoError::InvalidValue);
+		}
+
+		Ok(unsafe { slice::from_raw_parts_mut(samples.as_mut_ptr() as *mut Self, samples.len() / Self::len()) })
+	}
+	/// View a slice of frames as interleaved samples.
+	fn as_interleaved(frames: &[Self]) -> &[Self::Sample] {
+		unsafe { slice::from_raw_parts(frames.as_ptr() as *const Self::Sample, frames.len() * Self::len()) }
+	}
 }
 
 
@@ -241,7 +273,8 @@ pub struct Stereo<S: Copy> {
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 #[repr(C)]
 pub struct McRear<S: Copy> {
-	pub rear: S,
+	pub rear_left: S,
+	pub rear_right: S,
 }
 
 
@@ -313,7 +346,43 @@ pub struct BFormat3D<S: Copy> {
 }
 
 
+// Every frame is a `repr(C)` struct of a single sample type, so it has no padding
+// and is plain data whenever its samples are.
+#[cfg(feature = "bytemuck")]
+macro_rules! impl_pod {
+	($($frame:ident,)*) => {
+		$(
+			unsafe impl<S: Copy + ::bytemuck::Zeroable> ::bytemuck::Zeroable for $frame<S> { }
+			unsafe impl<S: ::bytemuck::Pod> ::bytemuck::Pod for $frame<S> { }
+		)*
+	};
+}
+#[cfg(feature = "bytemuck")]
+impl_pod! {
+	Mono,
+	Stereo,
+	McRear,
+	McQuad,
+	Mc51Chn,
+	Mc61Chn,
+	Mc71Chn,
+	BFormat2D,
+	BFormat3D,
+}
+#[cfg(feature = "bytemuck")]
+unsafe impl ::bytemuck::Zeroable for ALawSample { }
+#[cfg(feature = "bytemuck")]
+unsafe impl ::bytemuck::Pod for ALawSample { }
+#[cfg(feature = "bytemuck")]
+unsafe impl ::bytemuck::Zeroable for MuLawSample { }
+#[cfg(feature = "bytemuck")]
+unsafe impl ::bytemuck::Pod for MuLawSample { }
+
+
 impl Format {
+	/// The `AL_FORMAT_*` token for this format, as passed to `alBufferData()`.
+	/// Formats defined by extensions can only be resolved through a context that supports them,
+	/// and fail with `ExtensionNotPresent` otherwise.
 	pub fn into_raw(self, ctx: Option<&Context>) -> AltoResult<sys::ALint> {
 		match self {
 			Format::Standard(f) => Ok(f.into_raw()),
@@ -469,37 +538,37 @@ impl SoftMsadpcmFormat {
 unsafe impl SampleFrame for Mono<u8> {
 	type Sample = u8;
 
-	#[inline] fn len() -> usize { 1 }
+	const CHANNELS: usize = 1;
 	#[inline] fn format() -> Format { Format::Standard(StandardFormat::MonoU8) }
 }
 unsafe impl SampleFrame for Mono<i16> {
 	type Sample = i16;
 
-	#[inline] fn len() -> usize { 1 }
+	const CHANNELS: usize = 1;
 	#[inline] fn format() -> Format { Format::Standard(StandardFormat::MonoI16) }
 }
 unsafe impl SampleFrame for Mono<f32> {
 	type Sample = f32;
 
-	#[inline] fn len() -> usize { 1 }
+	const CHANNELS: usize = 1;
 	#[inline] fn format() -> Format { Format::ExtFloat32(ExtFloat32Format::Mono) }
 }
 unsafe impl SampleFrame for Mono<f64> {
 	type Sample = f64;
 
-	#[inline] fn len() -> usize { 1 }
+	const CHANNELS: usize = 1;
 	#[inline] fn format() -> Format { Format::ExtDouble(ExtDoubleFormat::Mono) }
 }
 unsafe impl SampleFrame for Mono<ALawSample> {
 	type Sample = ALawSample;
 
-	#[inline] fn len() -> usize { 1 }
+	const CHANNELS: usize = 1;
 	#[inline] fn format() -> Format { Format::ExtALaw(ExtALawFormat::Mono) }
 }
 unsafe impl SampleFrame for Mono<MuLawSample> {
 	type Sample = MuLawSample;
 
-	#[inline] fn len() -> usize { 1 }
+	const CHANNELS: usize = 1;
 	#[inline] fn format() -> Format { Format::ExtMuLaw(ExtMuLawFormat::Mono) }
 }
 
@@ -507,37 +576,37 @@ unsafe impl SampleFrame for Mono<MuLawSample> {
 unsafe impl SampleFrame for Stereo<u8> {
 	type Sample = u8;
 
-	#[inline] fn len() -> usize { 2 }
+	const CHANNELS: usize = 2;
 	#[inline] fn format() -> Format { Format::Standard(StandardFormat::StereoU8) }
 }
 unsafe impl SampleFrame for Stereo<i16> {
 	type Sample = i16;
 
-	#[inline] fn len() -> usize { 2 }
+	const CHANNELS: usize = 2;
 	#[inline] fn format() -> Format { Format::Standard(StandardFormat::StereoI16) }
 }
 unsafe impl SampleFrame for Stereo<f32> {
 	type Sample = f32;
 
-	#[inline] fn len() -> usize { 2 }
+	const CHANNELS: usize = 2;
 	#[inline] fn format() -> Format { Format::ExtFloat32(ExtFloat32Format::Stereo) }
 }
 unsafe impl SampleFrame for Stereo<f64> {
 	type Sample = f64;
 
-	#[inline] fn len() -> usize { 2 }
+	const CHANNELS: usize = 2;
 	#[inline] fn format() -> Format { Format::ExtDouble(ExtDoubleFormat::Stereo) }
 }
 unsafe impl SampleFrame for Stereo<ALawSample> {
 	type Sample = ALawSample;
 
-	#[inline] fn len() -> usize { 2 }
+	const CHANNELS: usize = 2;
 	#[inline] fn format() -> Format { Format::ExtALaw(ExtALawFormat::Stereo) }
 }
 unsafe impl SampleFrame for Stereo<MuLawSample> {
 	type Sample = MuLawSample;
 
-	#[inline] fn len() -> usize { 2 }
+	const CHANNELS: usize = 2;
 	#[inline] fn format() -> Format { Format::ExtMuLaw(ExtMuLawFormat::Stereo) }
 }
 
@@ -545,25 +614,25 @@ unsafe impl SampleFrame for Stereo<MuLawSample> {
 unsafe impl SampleFrame for McRear<u8> {
 	type Sample = u8;
 
-	#[inline] fn len() -> usize { 1 }
+	const CHANNELS: usize = 2;
 	#[inline] fn format() -> Format { Format::ExtMcFormats(ExtMcFormat::RearU8) }
 }
 unsafe impl SampleFrame for McRear<i16> {
 	type Sample = i16;
 
-	#[inline] fn len() -> usize { 1 }
+	const CHANNELS: usize = 2;
 	#[inline] fn format() -> Format { Format::ExtMcFormats(ExtMcFormat::RearI16)  }
 }
 unsafe impl SampleFrame for McRear<f32> {
 	type Sample = f32;
 
-	#[inline] fn len() -> usize { 1 }
+	const CHANNELS: usize = 2;
 	#[inline] fn format() -> Format { Format::ExtMcFormats(ExtMcFormat::RearF32) }
 }
 unsafe impl SampleFrame for McRear<MuLawSample> {
 	type Sample = MuLawSample;
 
-	#[inline] fn len() -> usize { 1 }
+	const CHANNELS: usize = 2;
 	#[inline] fn format() -> Format { Format::ExtMuLawMcFormats(ExtMuLawMcFormat::Rear) }
 }
 
@@ -571,25 +640,25 @@ unsafe impl SampleFrame for McRear<MuLawSample> {
 unsafe impl SampleFrame for McQuad<u8> {
 	type Sample = u8;
 
-	#[inline] fn len() -> usize { 4 }
+	const CHANNELS: usize = 4;
 	#[inline] fn format() -> Format { Format::ExtMcFormats(ExtMcFormat::QuadU8) }
 }
 unsafe impl SampleFrame for McQuad<i16> {
 	type Sample = i16;
 
-	#[inline] fn len() -> usize { 4 }
+	const CHANNELS: usize = 4;
 	#[inline] fn format() -> Format { Format::ExtMcFormats(ExtMcFormat::QuadI16)  }
 }
 unsafe impl SampleFrame for McQuad<f32> {
 	type Sample = f32;
 
-	#[inline] fn len() -> usize { 4 }
+	const CHANNELS: usize = 4;
 	#[inline] fn format() -> Format { Format::ExtMcFormats(ExtMcFormat::QuadF32) }
 }
 unsafe impl SampleFrame for McQuad<MuLawSample> {
 	type Sample = MuLawSample;
 
-	#[inline] fn len() -> usize { 4 }
+	const CHANNELS: usize = 4;
 	#[inline] fn format() -> Format { Format::ExtMuLawMcFormats(ExtMuLawMcFormat::Quad) }
 }
 
@@ -597,25 +666,25 @@ unsafe impl SampleFrame for McQuad<MuLawSample> {
 unsafe impl SampleFrame for Mc51Chn<u8> {
 	type Sample = u8;
 
-	#[inline] fn len() -> usize { 6 }
+	const CHANNELS: usize = 6;
 	#[inline] fn format() -> Format { Format::ExtMcFormats(ExtMcFormat::Mc51ChnU8) }
 }
 unsafe impl SampleFrame for Mc51Chn<i16> {
 	type Sample = i16;
 
-	#[inline] fn len() -> usize { 6 }
+	const CHANNELS: usize = 6;
 	#[inline] fn format() -> Format { Format::ExtMcFormats(ExtMcFormat::Mc51ChnI16)  }
 }
 unsafe impl SampleFrame for Mc51Chn<f32> {
 	type Sample = f32;
 
-	#[inline] fn len() -> usize { 6 }
+	const CHANNELS: usize = 6;
 	#[inline] fn format() -> Format { Format::ExtMcFormats(ExtMcFormat::Mc51ChnF32) }
 }
 unsafe impl SampleFrame for Mc51Chn<MuLawSample> {
 	type Sample = MuLawSample;
 
-	#[inline] fn len() -> usize { 6 }
+	const CHANNELS: usize = 6;
 	#[inline] fn format() -> Format { Format::ExtMuLawMcFormats(ExtMuLawMcFormat::Mc51Chn) }
 }
 
@@ -623,25 +692,25 @@ unsafe impl SampleFrame for Mc51Chn<MuLawSample> {
 unsafe impl SampleFrame for Mc61Chn<u8> {
 	type Sample = u8;
 
-	#[inline] fn len() -> usize { 7 }
+	const CHANNELS: usize = 7;
 	#[inline] fn format() -> Format { Format::ExtMcFormats(ExtMcFormat::Mc61ChnU8) }
 }
 unsafe impl SampleFrame for Mc61Chn<i16> {
 	type Sample = i16;
 
-	#[inline] fn len() -> usize { 7 }
+	const CHANNELS: usize = 7;
 	#[inline] fn format() -> Format { Format::ExtMcFormats(ExtMcFormat::Mc61ChnI16)  }
 }
 unsafe impl SampleFrame for Mc61Chn<f32> {
 	type Sample = f32;
 
-	#[inline] fn len() -> usize { 7 }
+	const CHANNELS: usize = 7;
 	#[inline] fn format() -> Format { Format::ExtMcFormats(ExtMcFormat::Mc61ChnF32) }
 }
 unsafe impl SampleFrame for Mc61Chn<MuLawSample> {
 	type Sample = MuLawSample;
 
-	#[inline] fn len() -> usize { 7 }
+	const CHANNELS: usize = 7;
 	#[inline] fn format() -> Format { Format::ExtMuLawMcFormats(ExtMuLawMcFormat::Mc61Chn) }
 }
 
@@ -649,25 +718,25 @@ unsafe impl SampleFrame for Mc61Chn<MuLawSample> {
 unsafe impl SampleFrame for Mc71Chn<u8> {
 	type Sample = u8;
 
-	#[inline] fn len() -> usize { 8 }
+	const CHANNELS: usize = 8;
 	#[inline] fn format() -> Format { Format::ExtMcFormats(ExtMcFormat::Mc71ChnU8) }
 }
 unsafe impl SampleFrame for Mc71Chn<i16> {
 	type Sample = i16;
 
-	#[inline] fn len() -> usize { 8 }
+	const CHANNELS: usize = 8;
 	#[inline] fn format() -> Format { Format::ExtMcFormats(ExtMcFormat::Mc71ChnI16)  }
 }
 unsafe impl SampleFrame for Mc71Chn<f32> {
 	type Sample = f32;
 
-	#[inline] fn len() -> usize { 8 }
+	const CHANNELS: usize = 8;
 	#[inline] fn format() -> Format { Format::ExtMcFormats(ExtMcFormat::Mc71ChnF32) }
 }
 unsafe impl SampleFrame for Mc71Chn<MuLawSample> {
 	type Sample = MuLawSample;
 
-	#[inline] fn len() -> usize { 8 }
+	const CHANNELS: usize = 8;
 	#[inline] fn format() -> Format { Format::ExtMuLawMcFormats(ExtMuLawMcFormat::Mc71Chn) }
 }
 
@@ -675,25 +744,25 @@ unsafe impl SampleFrame for Mc71Chn<MuLawSample> {
 unsafe impl SampleFrame for BFormat2D<u8> {
 	type Sample = u8;
 
-	#[inline] fn len() -> usize { 3 }
+	const CHANNELS: usize = 3;
 	#[inline] fn format() -> Format { Format::ExtBFormat(ExtBFormat::B2DU8) }
 }
 unsafe impl SampleFrame for BFormat2D<i16> {
 	type Sample = i16;
 
-	#[inline] fn len() -> usize { 3 }
+	const CHANNELS: usize = 3;
 	#[inline] fn format() -> Format { Format::ExtBFormat(ExtBFormat::B2DI16) }
 }
 unsafe impl SampleFrame for BFormat2D<f32> {
 	type Sample = f32;
 
-	#[inline] fn len() -> usize { 3 }
+	const CHANNELS: usize = 3;
 	#[inline] fn format() -> Format { Format::ExtBFormat(ExtBFormat::B2DF32) }
 }
 unsafe impl SampleFrame for BFormat2D<MuLawSample> {
 	type Sample = MuLawSample;
 
-	#[inline] fn len() -> usize { 3 }
+	const CHANNELS: usize = 3;
 	#[inline] fn format() -> Format { Format::ExtMuLawBFormat(ExtMuLawBFormat::B2D) }
 }
 
@@ -701,25 +770,25 @@ unsafe impl SampleFrame for BFormat2D<MuLawSample> {
 unsafe impl SampleFrame for BFormat3D<u8> {
 	type Sample = u8;
 
-	#[inline] fn len() -> usize { 4 }
+	const CHANNELS: usize = 4;
 	#[inline] fn format() -> Format { Format::ExtBFormat(ExtBFormat::B3DU8) }
 }
 unsafe impl SampleFrame for BFormat3D<i16> {
 	type Sample = i16;
 
-	#[inline] fn len() -> usize { 4 }
+	const CHANNELS: usize = 4;
 	#[inline] fn format() -> Format { Format::ExtBFormat(ExtBFormat::B3DI16) }
 }
 unsafe impl SampleFrame for BFormat3D<f32> {
 	type Sample = f32;
 
-	#[inline] fn len() -> usize { 4 }
+	const CHANNELS: usize = 4;
 	#[inline] fn format() -> Format { Format::ExtBFormat(ExtBFormat::B3DF32) }
 }
 unsafe impl SampleFrame for BFormat3D<MuLawSample> {
 	type Sample = MuLawSample;
 
-	#[inline] fn len() -> usize { 4 }
+	const CHANNELS: usize = 4;
 	#[inline] fn format() -> Format { Format::ExtMuLawBFormat(ExtMuLawBFormat::B3D) }
 }
 
@@ -0,0 +1,152 @@
+use std::sync::{Arc, Weak};
+use std::sync::mpsc::{self, Receiver, Sender};
+
+use parking_lot::Mutex;
+
+use ::{AltoError, AltoResult};
+use al::*;
+
+
+/// A cheap, cloneable control handle for a source owned by another thread, as returned by `Source::handle`.
+/// Its methods only validate their arguments and enqueue a command; nothing happens to the source until
+/// the thread that owns the context calls `Context::apply_pending`. Commands sent through one handle are
+/// applied in the order they were sent.
+///
+/// The handle keeps neither the source nor its context alive. Commands for a source that has since been
+/// dropped, or sent after the context is gone, are discarded silently.
+#[derive(Clone)]
+pub struct SourceHandle {
+	src: Weak<SourceInner>,
+	cmds: Sender<PendingCommand>,
+}
+
+
+/// The per-context queue that `SourceHandle`s send into and `Context::apply_pending` drains.
+pub(crate) struct HandleQueue {
+	tx: Mutex<Sender<PendingCommand>>,
+	rx: Mutex<Receiver<PendingCommand>>,
+}
+
+
+struct PendingCommand {
+	src: Weak<SourceInner>,
+	cmd: HandleCommand,
+}
+
+
+enum HandleCommand {
+	Play,
+	Pause,
+	Stop,
+	Gain(f32),
+	Pitch(f32),
+	Position([f32; 3]),
+	Velocity([f32; 3]),
+}
+
+
+impl SourceHandle {
+	pub(crate) fn new(src: &Arc<SourceInner>) -> SourceHandle {
+		SourceHandle{src: Arc::downgrade(src), cmds: src.ctx.0.handles.tx.lock().clone()}
+	}
+
+
+	/// `alSourcePlay()`
+	/// As with `Context::play_all`, a streaming source does not record that it was played this way.
+	pub fn play(&self) { self.send(HandleCommand::Play) }
+	/// `alSourcePause()`
+	pub fn pause(&self) { self.send(HandleCommand::Pause) }
+	/// `alSourceStop()`
+	/// As with `Context::stop_all`, a streaming source stopped this way still counts as intended to play.
+	pub fn stop(&self) { self.send(HandleCommand::Stop) }
+
+
+	/// `alSourcef(AL_GAIN)`
+	pub fn set_gain(&self, value: f32) -> AltoResult<()> {
+		if !value.is_finite() || value < 0.0 {
+			return Err(AltoError::InvalidValue);
+		}
+		self.send(HandleCommand::Gain(value));
+		Ok(())
+	}
+	/// `alSourcef(AL_PITCH)`
+	pub fn set_pitch(&self, value: f32) -> AltoResult<()> {
+		if !value.is_finite() || value <= 0.0 {
+			return Err(AltoError::InvalidValue);
+		}
+		self.send(HandleCommand::Pitch(value));
+		Ok(())
+	}
+	/// `alSourcefv(AL_POSITION)`
+	pub fn set_position<V: AsAlVec3>(&self, value: V) -> AltoResult<()> {
+		let value = finite3(value.as_al_vec3())?;
+		self.send(HandleCommand::Position(value));
+		Ok(())
+	}
+	/// `alSourcefv(AL_VELOCITY)`
+	pub fn set_velocity<V: AsAlVec3>(&self, value: V) -> AltoResult<()> {
+		let value = finite3(value.as_al_vec3())?;
+		self.send(HandleCommand::Velocity(value));
+		Ok(())
+	}
+
+
+	/// Whether the source is still alive. It may be dropped at any moment after this returns `true`.
+	pub fn is_alive(&self) -> bool { self.src.upgrade().is_some() }
+
+
+	fn send(&self, cmd: HandleCommand) {
+		let _ = self.cmds.send(PendingCommand{src: self.src.clone(), cmd});
+	}
+}
+
+
+impl HandleQueue {
+	pub(crate) fn new() -> HandleQueue {
+		let (tx, rx) = mpsc::channel();
+		HandleQueue{tx: Mutex::new(tx), rx: Mutex::new(rx)}
+	}
+}
+
+
+impl Context {
+	/// Apply every command sent through a `SourceHandle` of this context since the last call, in one
+	/// deferred batch, and return how many were applied. Commands for sources that have been dropped
+	/// are discarded without being counted, and errors raised by individual commands are ignored.
+	pub fn apply_pending(&self) -> usize {
+		// Drain first, so no source is touched with the queue locked.
+		let pending: Vec<_> = self.0.handles.rx.lock().try_iter().collect();
+		if pending.is_empty() {
+			return 0;
+		}
+
+		let _defer = self.defer_updates();
+		let mut applied = 0;
+		for PendingCommand{src, cmd} in pending {
+			let src = match src.upgrade() {
+				Some(src) => src,
+				None => continue,
+			};
+			let _ = match cmd {
+				HandleCommand::Play => { src.play(); Ok(()) },
+				HandleCommand::Pause => { src.pause(); Ok(()) },
+				HandleCommand::Stop => { src.stop(); Ok(()) },
+				HandleCommand::Gain(value) => src.set_gain(value),
+				HandleCommand::Pitch(value) => src.set_pitch(value),
+				HandleCommand::Position(value) => src.set_position(value),
+				HandleCommand::Velocity(value) => src.set_velocity(value),
+			};
+			applied += 1;
+		}
+		applied
+	}
+}
+
+
+fn finite3(value: [f32; 3]) -> AltoResult<[f32; 3]> {
+	if value.iter().all(|c| c.is_finite()) {
+		Ok(value)
+	} else {
+		Err(AltoError::InvalidValue)
+	}
+}
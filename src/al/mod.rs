@@ -1,13 +1,20 @@
 use std::ops::Deref;
+use std::any::Any;
 use std::iter;
-use std::sync::Arc;
-use std::sync::atomic::{AtomicUsize, Ordering};
-use std::collections::VecDeque;
+use std::sync::{Arc, Weak};
+use std::sync::atomic::{AtomicBool, AtomicI32, AtomicUsize, Ordering};
+use std::collections::{HashMap, VecDeque};
+use std::collections::vec_deque;
 use std::mem;
 use std::ptr;
+use std::slice;
 use std::hash::{Hash, Hasher};
 use std::ffi::{CString, CStr};
-use parking_lot::{Mutex, MutexGuard};
+use std::cmp;
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_6};
+use std::thread;
+use std::time::{Duration, Instant};
+use parking_lot::{Condvar, Mutex, MutexGuard};
 
 use ::{AltoError, AltoResult};
 use sys;
@@ -20,12 +27,69 @@ mod format;
 pub use self::format::*;
 
 
+mod voice;
+pub use self::voice::*;
+
+
+mod stream;
+pub use self::stream::*;
+
+
+mod chain;
+pub use self::chain::*;
+
+
+mod oneshot;
+pub use self::oneshot::*;
+
+
+mod handle;
+pub use self::handle::*;
+
+
+mod update;
+pub use self::update::*;
+
+
+const SPEED_OF_SOUND_METERS: f32 = 343.3;
+
+
+/// The span of `gain_from_slider` in decibels below unity gain.
+const SLIDER_RANGE_DB: f32 = 60.0;
+
+
+static NEXT_SOURCE_GROUP: AtomicUsize = AtomicUsize::new(0);
+static NEXT_FADE: AtomicUsize = AtomicUsize::new(0);
+
+
+// Serializes use of the process-wide current context when `ALC_EXT_thread_local_context` is unavailable.
+// It is not reentrant, and is always acquired in this order relative to alto's other locks: a context's
+// `fades`, then a source's `muted`, then the context's `mixes`, `listener_pos`, `one_shots` or a source's
+// `last_pos`, then the context's `sources`, then this lock, then a source's `sends`. No other context's lock
+// is taken while it is held, and no alto object whose destructor takes it may be dropped while it is held. A
+// context's event handler table is never held while taking any other lock.
 lazy_static! {
 	#[doc(hidden)]
     static ref ALTO_CTX_LOCK: Mutex<()> = Mutex::new(());
 }
 
 
+/// A 3D vector accepted by the position, velocity, direction and orientation setters.
+///
+/// Implemented for every `Clone` type that converts into `[f32; 3]`, which includes `[f32; 3]`,
+/// `(f32, f32, f32)`, `mint::Vector3<f32>` and `mint::Point3<f32>` (tested under the `mint` feature), and
+/// the vector types of `cgmath` and `nalgebra`.
+pub trait AsAlVec3 {
+	/// The components of the vector.
+	fn as_al_vec3(&self) -> [f32; 3];
+}
+
+
+impl<V: Into<[f32; 3]> + Clone> AsAlVec3 for V {
+	#[inline] fn as_al_vec3(&self) -> [f32; 3] { self.clone().into() }
+}
+
+
 /// The gain curve of sources as a function of distance to the listener.
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 pub enum DistanceModel {
@@ -47,8 +111,25 @@ pub enum DistanceModel {
 }
 
 
+/// Distance parameters of a source, as named presets for common kinds of sound or as raw values.
+/// Preset distances assume the default of one unit per meter.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Attenuation {
+	/// A small object such as a prop or footstep, audible up close only. Reference 0.5, rolloff 1.5, max 15.
+	SmallProp,
+	/// A speaking character. Reference 1, rolloff 1, max 40.
+	Voice,
+	/// A loud nearby source such as a vehicle or machinery. Reference 4, rolloff 1, max 200.
+	Vehicle,
+	/// A very loud far-off source such as artillery or thunder. Reference 20, rolloff 0.5, max 2000.
+	Distant,
+	/// `AL_REFERENCE_DISTANCE`, `AL_ROLLOFF_FACTOR` and `AL_MAX_DISTANCE` as given.
+	Custom{reference: f32, rolloff: f32, max: f32},
+}
+
+
 /// The spatialization mode of a source.
-/// Requires `ALC_SOFT_source_spatialization`
+/// Requires `AL_SOFT_source_spatialize`
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 pub enum SoftSourceSpatialization {
 	/// `AL_FALSE`
@@ -61,7 +142,58 @@ pub enum SoftSourceSpatialization {
 }
 
 
+/// How a source with direct channels enabled handles buffer channels that have no matching output channel.
+/// Requires `AL_SOFT_direct_channels`
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum SoftDirectChannelsMode {
+	/// `AL_FALSE`
+	Off,
+	/// `AL_DROP_UNMATCHED_SOFT`
+	DropUnmatched,
+	/// `AL_REMIX_UNMATCHED_SOFT`
+	/// Requires `AL_SOFT_direct_channels_remix`
+	RemixUnmatched,
+}
+
+
+/// How eagerly a context checks for errors after setting per-frame properties.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum ErrorChecking {
+	/// Call `alGetError()` after every property change. This is the default.
+	Always,
+	/// Check errors only in builds with debug assertions enabled.
+	OnDebug,
+	/// Never check errors after float/vector property changes.
+	/// Creation and deletion of objects are still checked.
+	Never,
+}
+
+
+/// The world scale of a context as derived from its speed of sound and EFX meters per unit.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum UnitsPerMeter {
+	/// Both properties agree on this many units per meter.
+	Coherent(f32),
+	/// The properties have been set inconsistently.
+	Diverged {
+		/// Units per meter implied by `AL_SPEED_OF_SOUND`.
+		speed_of_sound: f32,
+		/// Units per meter implied by `AL_METERS_PER_UNIT`.
+		meters_per_unit: f32,
+	},
+}
+
+
+/// An angle, such as that of a sound cone, that can be given in either degrees or radians.
+/// OpenAL itself takes cone angles in degrees.
+#[derive(Copy, Clone, PartialEq, PartialOrd, Debug)]
+pub struct Angle(f32);
+
+
 /// A listener context.
+/// Handles to a context are reference counted, and every object created from it holds one.
+/// The underlying context is only destroyed once the last handle is dropped, at which point it is
+/// released from the global and calling thread's current context slots before `alcDestroyContext()`.
 pub struct Context(pub(crate) Arc<ContextInner>);
 
 
@@ -70,6 +202,80 @@ pub(crate) struct ContextInner {
 	pub ctx: *mut sys::ALCcontext,
 	pub exts: ext::AlCache,
 	defer_rc: Arc<AtomicUsize>,
+	error_checking: AtomicUsize,
+	mixes: Mutex<HashMap<sys::ALuint, SourceMix>>,
+	listener_pos: Mutex<Option<[f32; 3]>>,
+	events: EventRouter,
+	fades: Mutex<HashMap<sys::ALuint, Fade>>,
+	/// The source at which `Context::reap_within` resumes finalizing fades.
+	fade_cursor: AtomicUsize,
+	fade_timer: Mutex<Option<Arc<AtomicBool>>>,
+	one_shots: Mutex<OneShotPool>,
+	handles: HandleQueue,
+	/// Every live source wrapper of this context, counted per name since a raw source can be adopted more than once.
+	sources: Mutex<HashMap<sys::ALuint, usize>>,
+}
+
+
+/// A context reference held by the fade timer thread, which must not keep the context alive.
+struct WeakContext(Weak<ContextInner>);
+
+
+/// Dispatches the `AL_SOFT_events` callback of a context, which occupies its single event slot.
+/// Source state changes wake the waiters of `wait_until` and `drain`, and buffer completions are
+/// passed to the handler registered for the source they occurred on.
+struct EventRouter {
+	installed: AtomicBool,
+	/// The `AL_EVENT_TYPE_BUFFER_COMPLETED_SOFT` value once those events are enabled, and 0 before.
+	buffer_completed: AtomicI32,
+	generation: Mutex<u64>,
+	cond: Condvar,
+	buffer_handlers: Mutex<HashMap<sys::ALuint, BufferHandler>>,
+}
+
+
+type BufferHandler = Box<dyn FnMut(usize) + Send>;
+
+
+/// Gain and pitch of a source that belongs to a `SourceGroup`.
+struct SourceMix {
+	group: usize,
+	base_gain: f32,
+	base_pitch: f32,
+	gain: f32,
+	pitch: f32,
+}
+
+
+/// A gain fade in progress on a source.
+struct Fade {
+	id: usize,
+	from: f32,
+	to: f32,
+	elapsed: Duration,
+	duration: Duration,
+	/// Gain to restore once the source has been stopped at the end of the fade.
+	restore: Option<f32>,
+}
+
+
+/// Keeps a gain fade started by `Source::fade_gain_to` running.
+/// Dropping the handle cancels the fade, leaving the gain at its current value.
+#[must_use = "dropping a FadeHandle cancels the fade; keep it or call `detach`"]
+pub struct FadeHandle {
+	ctx: Context,
+	src: sys::ALuint,
+	id: usize,
+	detached: bool,
+}
+
+
+/// Restores the previously current context when `Context::with_current` returns or unwinds.
+struct RestoreCurrent<'a> {
+	alto: &'a Alto,
+	prev: *mut sys::ALCcontext,
+	thread: bool,
+	_lock: Option<MutexGuard<'static, ()>>,
 }
 
 
@@ -80,7 +286,58 @@ pub struct DeferLock<'c> {
 }
 
 
+/// A mixing bus that scales the gain and pitch of a set of sources.
+/// Each member keeps its own base gain and pitch, which the `Source` getters and setters operate on,
+/// while the values applied to OpenAL are the products of those and the group's multipliers.
+/// Members are tracked by name, so a group does not keep its sources alive.
+/// Removing a source or dropping the group restores the base values.
+pub struct SourceGroup {
+	ctx: Context,
+	id: usize,
+	gain: f32,
+	pitch: f32,
+	members: Vec<sys::ALuint>,
+}
+
+
+/// A buffer whose data is pulled from a callback while it plays, as created by `Context::new_callback_buffer`.
+/// It can be attached to one `StaticSource` at a time with `StaticSource::set_callback_buffer`, and keeps
+/// the callback alive until the buffer itself is dropped. Dropping it stops and detaches the source it is
+/// attached to, even one adopted without ownership, and leaks the callback if the buffer still cannot be deleted.
+pub struct CallbackBuffer {
+	buf: Buffer,
+	callback: Box<dyn Any + Send>,
+	owner: Mutex<Option<CallbackOwner>>,
+}
+
+
+/// The source a `CallbackBuffer` was last attached to, kept by name as well so it can be detached
+/// after its wrapper is gone.
+struct CallbackOwner {
+	src: Weak<SourceInner>,
+	ctx: Context,
+	id: sys::ALuint,
+}
+
+
+type BufferCallback<F> = Box<dyn FnMut(&mut [F]) -> usize + Send>;
+
+
+/// Derives listener and source velocities from successive positions for Doppler shift.
+/// Each update sets the position and its derived velocity together.
+pub struct DopplerTracker {
+	ctx: Context,
+	teleport_distance: f32,
+	listener: Option<[f32; 3]>,
+	sources: HashMap<sys::ALuint, [f32; 3]>,
+}
+
+
 /// An audio buffer of any format.
+///
+/// Buffers belong to the device rather than to the context they were created from, so a buffer may be
+/// attached to or queued on sources of any context of the same device. Sources themselves are private
+/// to their context. `Context::check_buffer` verifies that a buffer is usable from a given context.
 pub struct Buffer {
 	ctx: Context,
 	buf: sys::ALuint, 
@@ -89,23 +346,62 @@ pub struct Buffer {
 
 
 /// Capabilities common to both static and streaming sources.
+///
+/// This trait is not object safe, since several of its setters are generic. To store sources of
+/// both kinds together, use `AnySource`, or `dyn SourceObject` when only batch operations and
+/// downcasting are needed.
 pub unsafe trait Source {
 	/// The context from which this source was created.
 	fn context(&self) -> &Context;
 	/// Raw handle as provided by OpenAL.
+	/// A source created elsewhere can be wrapped with `Context::adopt_raw_source`.
 	fn as_raw(&self) -> sys::ALuint;
+	/// A `Send` handle through which other threads can control this source, with their commands
+	/// applied by `Context::apply_pending` on the thread that owns the context.
+	fn handle(&self) -> SourceHandle;
+	/// Start a batch of position, velocity, gain and pitch changes that `SourceUpdate::commit` applies
+	/// together, so that a mix update never sees, for example, a new position with a stale velocity.
+	fn update(&mut self) -> SourceUpdate<'_>;
 
 	/// `alGetSourcei(AL_SOURCE_STATE)`
 	fn state(&self) -> SourceState;
+	/// `alGetSourcei(AL_SOURCE_TYPE)`
+	/// What OpenAL considers the source to be, which follows from how its buffers were last attached
+	/// rather than from the alto type wrapping it.
+	fn source_type(&self) -> AltoResult<SourceType>;
 	/// `alSourcePlay()`
 	fn play(&mut self);
+	/// Block until the source reaches `state`, returning `false` if `timeout` elapses first.
+	/// With `AL_SOFT_events` the thread sleeps until a source of the context changes state, and `poll` only bounds each wait.
+	/// Otherwise the state is checked every `poll`, waking early when the queued audio is due to finish.
+	fn wait_until(&self, state: SourceState, timeout: Duration, poll: Duration) -> AltoResult<bool>;
+	/// Block until the source stops, checking at least every 10ms.
+	fn wait_until_stopped(&self, timeout: Duration) -> AltoResult<bool>;
+	/// `alSourcePlayAtTimeSOFT()`
+	/// Requires `AL_SOFT_source_start_delay`
+	/// Start playback when the device clock reaches `time` nanoseconds, as reported by `DeviceObject::soft_clock`.
+	/// A time in the past starts playback immediately.
+	fn soft_play_at_time(&mut self, time: i64) -> AltoResult<()>;
 	/// `alSourcePause()`
 	fn pause(&mut self);
 	/// `alSourceStop()`
 	fn stop(&mut self);
+	/// Fade the gain out over `duration` like `fade_gain_to`, then stop the source and restore its original gain
+	/// so it can be reused. A source that is not playing is stopped immediately.
+	/// The fade ends early if the source stops on its own, and playing the source again before it ends
+	/// cancels the pending stop and restores the gain.
+	fn stop_with_fade(&mut self, duration: Duration) -> AltoResult<()>;
 	/// `alSourceRewind()`
 	fn rewind(&mut self);
 
+	/// `alGetSourcei(AL_BUFFER)`
+	/// Raw name of the buffer OpenAL is currently playing on this source, or `None` if there is none.
+	/// For a static source this is the buffer attached with `set_buffer`, and for a streaming source
+	/// it is the queued buffer being played.
+	/// The name only identifies the buffer, to be compared with `Buffer::as_raw`. Buffers are kept alive by
+	/// the source using them, which hands out references through `StaticSource::buffer` or by unqueuing.
+	fn buffer_raw(&self) -> AltoResult<Option<sys::ALuint>>;
+
 	/// `alGetSourcei(AL_SOURCE_RELATIVE)`
 	fn relative(&self) -> bool;
 	/// `alSourcei(AL_SOURCE_RELATIVE)`
@@ -115,6 +411,21 @@ pub unsafe trait Source {
 	fn gain(&self) -> f32;
 	/// `alSourcef(AL_GAIN)`
 	fn set_gain(&mut self, f32) -> AltoResult<()>;
+	/// `alSourcef(AL_GAIN)`
+	/// Move the gain towards `target` by at most `rate` per second over `dt` seconds, and return whether
+	/// the target has been reached. Call once per frame to ramp the gain without the clicks of an abrupt change.
+	fn gain_step(&mut self, target: f32, dt: f32, rate: f32) -> AltoResult<bool>;
+	/// Fade the gain linearly to `target` over `duration`, settling exactly on the target.
+	/// The fade advances as the context's fades are driven by `Context::tick` or `Context::set_fade_timer`.
+	/// Starting a fade replaces any fade already running on this source.
+	fn fade_gain_to(&mut self, target: f32, duration: Duration) -> AltoResult<FadeHandle>;
+	/// Whether the source was muted with `set_muted`.
+	fn is_muted(&self) -> bool;
+	/// `alSourcef(AL_GAIN)`
+	/// Mute the source by setting its gain to zero and stashing the previous gain, or unmute it by restoring
+	/// the stashed gain. While muted, `gain` reports and `set_gain` replaces the stashed gain, so the latest
+	/// value takes effect on unmute. Gain fades write the gain directly and should not run on a muted source.
+	fn set_muted(&mut self, value: bool) -> AltoResult<()>;
 
 	/// `alGetSourcef(AL_MIN_GAIN)`
 	fn min_gain(&self) -> f32;
@@ -141,6 +452,14 @@ pub unsafe trait Source {
 	/// `alSourcef(AL_MAX_DISTANCE)`
 	fn set_max_distance(&mut self, f32) -> AltoResult<()>;
 
+	/// `alGetSourcef(AL_REFERENCE_DISTANCE)`, `alGetSourcef(AL_ROLLOFF_FACTOR)`, `alGetSourcef(AL_MAX_DISTANCE)`
+	/// The current distance parameters as `Attenuation::Custom`, which `set_attenuation` accepts back.
+	fn attenuation(&self) -> Attenuation;
+	/// `alSourcef(AL_REFERENCE_DISTANCE)`, `alSourcef(AL_ROLLOFF_FACTOR)`, `alSourcef(AL_MAX_DISTANCE)`
+	/// Apply all three distance parameters in one deferred batch. Fails with `AltoError::InvalidValue`, leaving
+	/// the source unchanged, if any value is negative or not finite, or the reference distance exceeds the max distance.
+	fn set_attenuation(&mut self, value: Attenuation) -> AltoResult<()>;
+
 	/// `alGetSourcef(AL_PITCH)`
 	fn pitch(&self) -> f32;
 	/// `alSourcef(AL_PITCH)`
@@ -149,26 +468,45 @@ pub unsafe trait Source {
 	/// `alGetSourcefv(AL_POSITION)`
 	fn position<V: From<[f32; 3]>>(&self) -> V;
 	/// `alSourcefv(AL_POSITION)`
-	fn set_position<V: Into<[f32; 3]>>(&mut self, V) -> AltoResult<()>;
+	fn set_position<V: AsAlVec3>(&mut self, V) -> AltoResult<()>;
 
 	/// `alGetSourcefv(AL_VELOCITY)`
 	fn velocity<V: From<[f32; 3]>>(&self) -> V;
 	/// `alSourcefv(AL_VELOCITY)`
-	fn set_velocity<V: Into<[f32; 3]>>(&mut self, V) -> AltoResult<()>;
+	fn set_velocity<V: AsAlVec3>(&mut self, V) -> AltoResult<()>;
+	/// `alSourcefv(AL_POSITION)` and `alSourcefv(AL_VELOCITY)`
+	/// Set the position along with a velocity derived from the position passed to the previous call
+	/// and the elapsed time in seconds. The first call sets zero velocity. The previous position is
+	/// kept behind a lock in the source, so calls from different threads are serialized.
+	fn update_position_with_velocity<V: AsAlVec3>(&mut self, pos: V, dt: f32) -> AltoResult<()>;
+
+	/// `alSourcei(AL_SOURCE_RELATIVE)`, `alSourcefv(AL_POSITION)` and `alSourcefv(AL_VELOCITY)`
+	/// Make the source listener-relative at `offset`, so that it moves with the listener.
+	fn set_head_locked<V: AsAlVec3>(&mut self, offset: V) -> AltoResult<()>;
+	/// `alSourcei(AL_SOURCE_RELATIVE)`, `alSourcefv(AL_POSITION)` and `alSourcefv(AL_VELOCITY)`
+	/// Turn a listener-relative source into a world space one without it moving audibly.
+	/// The position and velocity are transformed out of the listener's frame, as given by `listener_pos` and
+	/// `listener_orientation`, and the context's listener velocity is added so the Doppler shift is kept.
+	/// Does nothing if the source is not listener-relative.
+	fn detach_to_world<V: AsAlVec3>(&mut self, listener_pos: V, listener_orientation: (V, V)) -> AltoResult<()>;
 
 	/// `alGetSourcefv(AL_DIRECTION)`
 	fn direction<V: From<[f32; 3]>>(&self) -> V;
 	/// `alSourcefv(AL_DIRECTION)`
-	fn set_direction<V: Into<[f32; 3]>>(&mut self, V) -> AltoResult<()>;
+	fn set_direction<V: AsAlVec3>(&mut self, V) -> AltoResult<()>;
 
 	/// `alGetSourcef(AL_CONE_INNER_ANGLE)`
+	/// In degrees. Prefer `cone`, which is typed.
 	fn cone_inner_angle(&self) -> f32;
 	/// `alSourcef(AL_CONE_INNER_ANGLE)`
+	/// In degrees. Prefer `set_cone`, which is typed and validates the whole cone.
 	fn set_cone_inner_angle(&mut self, f32) -> AltoResult<()>;
 
 	/// `alGetSourcef(AL_CONE_OUTER_ANGLE)`
+	/// In degrees. Prefer `cone`, which is typed.
 	fn cone_outer_angle(&self) -> f32;
 	/// `alSourcef(AL_CONE_OUTER_ANGLE)`
+	/// In degrees. Prefer `set_cone`, which is typed and validates the whole cone.
 	fn set_cone_outer_angle(&mut self, f32) -> AltoResult<()>;
 
 	/// `alGetSourcef(AL_CONE_OUTER_GAIN)`
@@ -176,6 +514,13 @@ pub unsafe trait Source {
 	/// `alSourcef(AL_CONE_OUTER_GAIN)`
 	fn set_cone_outer_gain(&mut self, f32) -> AltoResult<()>;
 
+	/// The inner angle, outer angle, and outer gain of the sound cone.
+	fn cone(&self) -> (Angle, Angle, f32);
+	/// `alSourcef(AL_CONE_INNER_ANGLE, AL_CONE_OUTER_ANGLE, AL_CONE_OUTER_GAIN)`
+	/// Set the whole sound cone in a single deferred batch. Both angles must be within 0 to 360 degrees
+	/// with the inner angle no wider than the outer, and the outer gain must be within 0 to 1.
+	fn set_cone(&mut self, inner: Angle, outer: Angle, outer_gain: f32) -> AltoResult<()>;
+
 	/// `alGetSourcef(AL_SEC_OFFSET)`
 	fn sec_offset(&self) -> f32;
 	/// `alSourcef(AL_SEC_OFFSET)`
@@ -191,13 +536,67 @@ pub unsafe trait Source {
 	/// `alSourcei(AL_BYTE_OFFSET)`
 	fn set_byte_offset(&mut self, sys::ALint) -> AltoResult<()>;
 
+	/// `alSourcei(AL_BYTE_OFFSET/AL_SAMPLE_OFFSET)` or `alSourcef(AL_SEC_OFFSET)`
+	/// Move the playback position, failing with `AltoError::SeekOutOfRange` unless it lies before the end
+	/// of the attached buffer for a static source, or of all queued buffers for a streaming source.
+	/// The error carries that length in the unit of the requested position.
+	///
+	/// For a streaming source, the position is relative to the start of the oldest buffer still queued,
+	/// including processed buffers that have not yet been unqueued, and may land in any later buffer.
+	/// Seeking a stopped source rewinds it first, so that the position takes effect on the next `play`
+	/// rather than playback restarting from the beginning. Seeking a playing or paused source takes effect immediately.
+	fn seek(&mut self, pos: SeekPos) -> AltoResult<()>;
+	/// `alGetSourcei(AL_SAMPLE_OFFSET)`
+	/// The playback position in sample frames, relative to the same start as `seek`.
+	fn tell(&self) -> SeekPos;
+	/// `alGetSourcei(AL_SAMPLE_OFFSET)`
+	/// The playback position as a duration, computed from the sample offset and the frequency of the buffer
+	/// it falls in, rather than from the imprecise `AL_SEC_OFFSET`. The 64-bit offset of `AL_SOFT_source_latency`
+	/// is used if present. For a streaming source this also counts the buffers already unqueued through it, so the
+	/// position keeps advancing across the whole stream; see `StreamingSource::reset_playback_position`.
+	fn playback_position(&self) -> AltoResult<Duration>;
+
+	/// Capture the properties of this source, including those of any extensions present on its context.
+	fn snapshot(&self) -> AltoResult<SourceSnapshot>;
+	/// Restore the properties captured by `snapshot`, which may have been taken from another source.
+	/// The properties are set in a single deferred batch. Those that were not captured, or whose
+	/// extension is not present on this source's context, are skipped.
+	fn apply_snapshot(&mut self, value: &SourceSnapshot) -> AltoResult<()>;
+
 	/// `alGetSourcedvSOFT(AL_SEC_OFFSET_LATENCY_SOFT)`
 	/// Requires `AL_SOFT_source_latency`
+	/// Returns the offset and the device latency in seconds, read together atomically.
 	fn soft_sec_offset_latency(&self) -> AltoResult<(f64, f64)>;
 
-	/// `alGetSourcei16vSOFT(AL_SAMPLE_OFFSET_LATENCY_SOFT)`
+	/// `alGetSourcedvSOFT(AL_POSITION)`
+	/// Requires `AL_SOFT_source_latency`
+	fn soft_position_f64<V: From<[f64; 3]>>(&self) -> AltoResult<V>;
+	/// `alSourcedvSOFT(AL_POSITION)`
+	/// Requires `AL_SOFT_source_latency`
+	/// Like `set_position`, but the coordinates are handed to the implementation without first being
+	/// narrowed to `f32`, for worlds too large to be represented precisely in single precision.
+	/// How much of the precision survives is up to the implementation. There is no double precision
+	/// listener API, so keeping the listener near the origin is still advisable.
+	fn set_soft_position_f64<V: Into<[f64; 3]>>(&mut self, value: V) -> AltoResult<()>;
+	/// `alGetSourcedvSOFT(AL_VELOCITY)`
+	/// Requires `AL_SOFT_source_latency`
+	fn soft_velocity_f64<V: From<[f64; 3]>>(&self) -> AltoResult<V>;
+	/// `alSourcedvSOFT(AL_VELOCITY)`
+	/// Requires `AL_SOFT_source_latency`
+	/// Like `set_velocity`, but without first narrowing to `f32`.
+	fn set_soft_velocity_f64<V: Into<[f64; 3]>>(&mut self, value: V) -> AltoResult<()>;
+
+	/// `alGetSourcei64vSOFT(AL_SAMPLE_OFFSET_LATENCY_SOFT)`
 	/// Requires `AL_SOFT_source_latency`
-	fn soft_sample_frac_offset_latency(&self) -> AltoResult<(i32, i32, i64)>;
+	/// Returns the play cursor and the device latency, read together atomically.
+	/// The cursor is the integer part of the 32.32 fixed point offset, counted in sample frames, so for a
+	/// multi-channel buffer it advances once per frame rather than once per individual channel sample.
+	fn soft_sample_frac_offset_latency(&self) -> AltoResult<(i64, Duration)>;
+	/// `alGetSourcei64vSOFT(AL_SAMPLE_OFFSET_LATENCY_SOFT)`
+	/// Requires `AL_SOFT_source_latency`
+	/// Like `soft_sample_frac_offset_latency`, but returns the cursor as the raw 32.32 fixed point value,
+	/// keeping the fraction of a frame. Shift it right by 32 for whole frames.
+	fn soft_sample_fixed_offset_latency(&self) -> AltoResult<(i64, Duration)>;
 
 	/// `alGetSourcef(AL_SEC_LENGTH_SOFT)`
 	/// Requires `AL_SOFT_source_length`
@@ -216,8 +615,19 @@ pub unsafe trait Source {
 	fn soft_direct_channels(&self) -> bool;
 	/// `alSourcei(AL_DIRECT_CHANNELS_SOFT)`
 	/// Requires `AL_SOFT_direct_channels`
+	/// Multi-channel buffers are then played directly to the matching output channels without panning or HRTF.
+	/// This may be changed at any time, including on a streaming source before any buffers are queued.
 	fn set_soft_direct_channels(&mut self, bool) -> AltoResult<()>;
 
+	/// `alGetSourcei(AL_DIRECT_CHANNELS_SOFT)`
+	/// Requires `AL_SOFT_direct_channels_remix` or `AL_SOFT_direct_channels`.
+	/// With only the latter, an enabled source reports `DropUnmatched`.
+	fn soft_direct_channels_mode(&self) -> AltoResult<SoftDirectChannelsMode>;
+	/// `alSourcei(AL_DIRECT_CHANNELS_SOFT)`
+	/// Requires `AL_SOFT_direct_channels_remix` or `AL_SOFT_direct_channels`.
+	/// `RemixUnmatched` is only accepted when `AL_SOFT_direct_channels_remix` is present.
+	fn set_soft_direct_channels_mode(&mut self, value: SoftDirectChannelsMode) -> AltoResult<()>;
+
 	/// `alGetSourcei(AL_DISTANCE_MODEL)`
 	/// Requires `AL_EXT_source_distance_model`
 	fn distance_model(&self) -> DistanceModel;
@@ -225,11 +635,14 @@ pub unsafe trait Source {
 	/// Requires `AL_EXT_source_distance_model`
 	fn set_distance_model(&mut self, DistanceModel) -> AltoResult<()>;
 
-	/// `alGetSourcei(AL_SOURCE_SPATIALIZATION_SOFT)`
-	/// Requires `AL_SOFT_source_spatialization`
+	/// `alGetSourcei(AL_SOURCE_SPATIALIZE_SOFT)`
+	/// Requires `AL_SOFT_source_spatialize`
+	/// Defaults to `Auto`, which spatializes mono buffers but not multi-channel ones.
+	/// `Auto` is also reported if the extension is not present.
 	fn soft_spatialization(&self) -> SoftSourceSpatialization;
-	/// `alSourcei(AL_SOURCE_SPATIALIZATION_SOFT)`
-	/// Requires `AL_SOFT_source_spatialization`
+	/// `alSourcei(AL_SOURCE_SPATIALIZE_SOFT)`
+	/// Requires `AL_SOFT_source_spatialize`
+	/// `Enabled` pans a multi-channel buffer as a single point, which `set_radius` can then widen.
 	fn set_soft_spatialization(&mut self, value: SoftSourceSpatialization) -> AltoResult<()>;
 
 	/// `alGetSourcei(AL_SOURCE_RESAMPLER_SOFT)`
@@ -241,16 +654,30 @@ pub unsafe trait Source {
 
 	/// `alGetSourcefv(AL_STEREO_ANGLES)`
 	/// Requires `AL_EXT_STEREO_ANGLES`
+	/// The angles of the left and right channels of a stereo buffer, in radians counter-clockwise
+	/// from the front, so positive angles are to the left. Defaults to `[PI / 6.0, -PI / 6.0]`.
+	/// Moving both angles away from zero widens the stereo image, and moving them towards zero narrows it.
 	fn stereo_angles<V: From<[f32; 2]>>(&self) -> AltoResult<V>;
 	/// `alSourcefv(AL_STEREO_ANGLES)`
 	/// Requires `AL_EXT_STEREO_ANGLES`
+	/// Both angles must be finite. The angles are ignored, but still accepted, while a mono buffer is playing.
 	fn set_stereo_angles<V: Into<[f32; 2]>>(&mut self, value: V) -> AltoResult<()>;
 
+	/// Pan the source between -1.0 (left) and 1.0 (right), as reconstructed from its current state.
+	fn pan(&self) -> AltoResult<f32>;
+	/// Pan the source between -1.0 (left) and 1.0 (right) with constant power. Values outside the range are clamped.
+	/// A stereo buffer is panned through its stereo angles if `AL_EXT_STEREO_ANGLES` is present, by rotating both
+	/// channels towards one side and narrowing them. Otherwise the source is made listener-relative and placed on
+	/// the unit circle in front of the listener, so the buffer should be attached before panning.
+	fn set_pan(&mut self, value: f32) -> AltoResult<()>;
+
 	/// `alGetSourcef(AL_SOURCE_RADIUS)`
 	/// Requires `AL_EXT_SOURCE_RADIUS`
 	fn radius(&self) -> f32;
 	/// `alSourcef(AL_SOURCE_RADIUS)`
 	/// Requires `AL_EXT_SOURCE_RADIUS`
+	/// The radius is in the same units as the source position and must be non-negative and finite.
+	/// A listener within the radius hears the source widen towards omnidirectional rather than collapse to a point.
 	fn set_radius(&self, value: f32) -> AltoResult<()>;
 
 	/// `alSourcei(AL_DIRECT_FILTER)`
@@ -260,14 +687,21 @@ pub unsafe trait Source {
 	/// Requires `ALC_EXT_EFX`
 	fn clear_direct_filter(&mut self);
 
+	/// `alcGetIntegerv(ALC_MAX_AUXILIARY_SENDS)`
+	/// Requires `ALC_EXT_EFX`
+	/// The number of auxiliary sends this source supports, as granted by the device. Valid send indices are `0..max`.
+	fn max_auxiliary_sends(&self) -> AltoResult<sys::ALint>;
 	/// `alSourceiv(AL_AUXILIARY_SEND_FILTER)`
 	/// Requires `ALC_EXT_EFX`
+	/// Fails with `AltoError::SendOutOfRange` if `send` is not below `max_auxiliary_sends`.
 	fn set_aux_send(&mut self, send: sys::ALint, value: &mut AuxEffectSlot) -> AltoResult<()>;
 	/// `alSourceiv(AL_AUXILIARY_SEND_FILTER)`
 	/// Requires `ALC_EXT_EFX`
+	/// Fails with `AltoError::SendOutOfRange` if `send` is not below `max_auxiliary_sends`.
 	fn set_aux_send_filter<F: Filter>(&mut self, send: sys::ALint, slot: &mut AuxEffectSlot, filter: &F) -> AltoResult<()>;
 	/// `alSourceiv(AL_AUXILIARY_SEND_FILTER)`
 	/// Requires `ALC_EXT_EFX`
+	/// Out of range sends are ignored.
 	fn clear_aux_send(&mut self, send: sys::ALint);
 
 	/// `alGetSourcef(AL_AIR_ABSORPTION_FACTOR)`
@@ -300,6 +734,21 @@ pub unsafe trait Source {
 }
 
 
+/// The object safe part of `Source`, implemented for every source.
+/// This allows batch operations such as `Context::stop_all` to take sources of different types
+/// together, as a slice of `&mut dyn SourceObject`.
+pub trait SourceObject {
+	#[doc(hidden)]
+	fn object_context(&self) -> &Context;
+	#[doc(hidden)]
+	fn object_raw(&self) -> sys::ALuint;
+	/// Recover the concrete source with `downcast_ref`.
+	fn as_any(&self) -> &dyn Any;
+	/// Recover the concrete source with `downcast_mut`.
+	fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+
 /// The current playback state of a source.
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 pub enum SourceState {
@@ -315,10 +764,139 @@ pub enum SourceState {
 }
 
 
+/// A source of a context as reported by `Context::for_each_source`.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct RawSourceInfo {
+	/// Raw handle as provided by OpenAL.
+	pub id: sys::ALuint,
+	/// `AL_SOURCE_STATE`
+	pub state: SourceState,
+	/// `AL_GAIN`, as applied by OpenAL after any mute, mix or fade.
+	pub gain: f32,
+}
+
+
+/// The kind of a source as reported by OpenAL.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum SourceType {
+	/// `AL_UNDETERMINED`, no buffer is attached or queued.
+	Undetermined,
+	/// `AL_STATIC`, a buffer is attached with `AL_BUFFER`.
+	Static,
+	/// `AL_STREAMING`, buffers are queued.
+	Streaming,
+	Unknown(sys::ALint),
+}
+
+
+/// The properties of a source, as captured by `Source::snapshot` and restored by `Source::apply_snapshot`.
+/// Properties that require an extension are `None` if it was not present when the snapshot was taken.
+#[derive(Clone, PartialEq, Debug)]
+pub struct SourceSnapshot {
+	/// `AL_SOURCE_RELATIVE`
+	pub relative: bool,
+	/// `AL_LOOPING`
+	pub looping: bool,
+	/// `AL_GAIN`
+	pub gain: f32,
+	/// `AL_MIN_GAIN`
+	pub min_gain: f32,
+	/// `AL_MAX_GAIN`
+	pub max_gain: f32,
+	/// `AL_REFERENCE_DISTANCE`
+	pub reference_distance: f32,
+	/// `AL_ROLLOFF_FACTOR`
+	pub rolloff_factor: f32,
+	/// `AL_MAX_DISTANCE`
+	pub max_distance: f32,
+	/// `AL_PITCH`
+	pub pitch: f32,
+	/// `AL_POSITION`
+	pub position: [f32; 3],
+	/// `AL_VELOCITY`
+	pub velocity: [f32; 3],
+	/// `AL_DIRECTION`
+	pub direction: [f32; 3],
+	/// `AL_CONE_INNER_ANGLE`
+	pub cone_inner_angle: f32,
+	/// `AL_CONE_OUTER_ANGLE`
+	pub cone_outer_angle: f32,
+	/// `AL_CONE_OUTER_GAIN`
+	pub cone_outer_gain: f32,
+	/// `AL_SAMPLE_OFFSET`
+	pub sample_offset: sys::ALint,
+	/// `AL_DISTANCE_MODEL`
+	/// Requires `AL_EXT_source_distance_model`
+	pub distance_model: Option<DistanceModel>,
+	/// `AL_DIRECT_CHANNELS_SOFT`
+	/// Requires `AL_SOFT_direct_channels`
+	pub soft_direct_channels_mode: Option<SoftDirectChannelsMode>,
+	/// `AL_SOURCE_SPATIALIZE_SOFT`
+	/// Requires `AL_SOFT_source_spatialize`
+	pub soft_spatialization: Option<SoftSourceSpatialization>,
+	/// `AL_SOURCE_RESAMPLER_SOFT`
+	/// Requires `AL_SOFT_source_resampler`
+	pub soft_resampler: Option<sys::ALint>,
+	/// `AL_STEREO_ANGLES`
+	/// Requires `AL_EXT_STEREO_ANGLES`
+	pub stereo_angles: Option<[f32; 2]>,
+	/// `AL_SOURCE_RADIUS`
+	/// Requires `AL_EXT_SOURCE_RADIUS`
+	pub radius: Option<f32>,
+	/// `AL_AIR_ABSORPTION_FACTOR`
+	/// Requires `ALC_EXT_EFX`
+	pub air_absorption_factor: Option<f32>,
+	/// `AL_ROOM_ROLLOFF_FACTOR`
+	/// Requires `ALC_EXT_EFX`
+	pub room_rolloff_factor: Option<f32>,
+	/// `AL_CONE_OUTER_GAINHF`
+	/// Requires `ALC_EXT_EFX`
+	pub cone_outer_gainhf: Option<f32>,
+	/// `AL_DIRECT_FILTER_GAINHF_AUTO`
+	/// Requires `ALC_EXT_EFX`
+	pub direct_filter_gainhf_auto: Option<bool>,
+}
+
+
 pub(crate) struct SourceInner {
 	ctx: Context,
 	src: sys::ALuint,
 	sends: Mutex<Vec<sys::ALuint>>,
+	last_pos: Mutex<Option<[f32; 3]>>,
+	muted: Mutex<Option<f32>>,
+	owned: bool,
+}
+
+
+/// A playback position for `Source::seek` and `Source::tell`.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum SeekPos {
+	/// Offset in bytes, `AL_BYTE_OFFSET`.
+	Bytes(sys::ALint),
+	/// Offset in sample frames, `AL_SAMPLE_OFFSET`.
+	Samples(sys::ALint),
+	/// Offset in seconds, `AL_SEC_OFFSET`.
+	Seconds(f32),
+}
+
+
+/// Which kind of alto source to wrap a raw source in, for `Context::adopt_raw_source`.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum SourceKind {
+	/// Wrap in a `StaticSource`.
+	Static,
+	/// Wrap in a `StreamingSource`.
+	Streaming,
+}
+
+
+/// Either kind of source, for keeping static and streaming sources together in one collection.
+/// It implements the full `Source` API, and the concrete source can be recovered with
+/// `as_static` or `as_streaming`, for example to queue buffers on a streaming source.
+/// `Context::adopt_raw_source` also returns this, since the kind is chosen at runtime.
+pub enum AnySource {
+	Static(StaticSource),
+	Streaming(StreamingSource),
 }
 
 
@@ -326,6 +904,7 @@ pub(crate) struct SourceInner {
 pub struct StaticSource {
 	src: Arc<SourceInner>,
 	buf: Option<Arc<Buffer>>,
+	callback: Option<Arc<CallbackBuffer>>,
 }
 
 
@@ -333,6 +912,228 @@ pub struct StaticSource {
 pub struct StreamingSource {
 	src: Arc<SourceInner>,
 	bufs: VecDeque<Buffer>,
+	scratch: Vec<sys::ALuint>,
+	pool: Option<BufferPool>,
+	playing: bool,
+	/// Number of buffers at the front of the queue that had already played when an underrun was detected.
+	underrun: Option<usize>,
+	underruns: usize,
+	auto_restart: bool,
+	/// Total duration of the buffers unqueued since creation or `reset_playback_position`.
+	played: Duration,
+}
+
+
+struct BufferPool {
+	free: Vec<Buffer>,
+	frames: usize,
+}
+
+
+/// A buffer taken from a streaming source's pool with `StreamingSource::acquire_free`.
+/// Fill it with `set_data`, then hand it back with `StreamingSource::queue`.
+/// Dropping it instead deletes the buffer, leaving the pool one buffer smaller.
+pub struct PooledBuffer {
+	buf: Buffer,
+	frames: usize,
+}
+
+
+/// The state of a pooled streaming source, as reported by `StreamingSource::reclaim`.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum PoolStatus {
+	/// The source is playing or paused. If no buffer is free, all of them are still queued.
+	Streaming,
+	/// The source has not been started, or was stopped or rewound through this `StreamingSource`.
+	Idle,
+	/// The source stopped on its own after playing every queued buffer, and must be played again
+	/// once more buffers are queued.
+	Underrun,
+}
+
+
+/// Map the position of a volume slider, from 0 to 1, to a gain for `Source::set_gain` or `Context::set_gain`.
+/// The curve is linear in decibels over the 60dB below unity, so each step of the slider sounds equally loud:
+/// the gain is `10^(3 * (position - 1))`, from 0.001 just above 0 up to 1, and exactly 0 at position 0.
+/// Positions outside 0 to 1 are clamped, and NaN is treated as 0.
+pub fn gain_from_slider(position: f32) -> f32 {
+	if position.is_nan() || position <= 0.0 {
+		return 0.0;
+	}
+	10f32.powf(SLIDER_RANGE_DB / 20.0 * (position.min(1.0) - 1.0))
+}
+
+
+/// The inverse of `gain_from_slider`, placing a gain back on a volume slider from 0 to 1.
+/// Gains of 0.001 or less map to 0, and gains of 1 or more to 1.
+pub fn slider_from_gain(gain: f32) -> f32 {
+	if gain.is_nan() || gain <= 0.0 {
+		return 0.0;
+	}
+	(1.0 + 20.0 * gain.log10() / SLIDER_RANGE_DB).clamp(0.0, 1.0)
+}
+
+
+fn dot3(a: [f32; 3], b: [f32; 3]) -> f32 { a[0] * b[0] + a[1] * b[1] + a[2] * b[2] }
+fn sub3(a: [f32; 3], b: [f32; 3]) -> [f32; 3] { [a[0] - b[0], a[1] - b[1], a[2] - b[2]] }
+fn scale3(a: [f32; 3], s: f32) -> [f32; 3] { [a[0] * s, a[1] * s, a[2] * s] }
+fn add3(a: [f32; 3], b: [f32; 3]) -> [f32; 3] { [a[0] + b[0], a[1] + b[1], a[2] + b[2]] }
+fn cross3(a: [f32; 3], b: [f32; 3]) -> [f32; 3] { [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]] }
+fn normalize3(a: [f32; 3]) -> [f32; 3] { scale3(a, 1.0 / dot3(a, a).sqrt()) }
+
+
+/// The duration of `frames` sample frames at `freq`, or zero if the frequency is unknown.
+fn frames_duration(frames: i64, freq: sys::ALint) -> Duration {
+	if frames <= 0 || freq <= 0 {
+		return Duration::from_secs(0);
+	}
+	let freq = freq as i64;
+	Duration::new((frames / freq) as u64, ((frames % freq) * 1_000_000_000 / freq) as u32)
+}
+
+
+/// The playback position of a streaming source that has already unqueued `played`, and whose play cursor
+/// is `offset` frames into the queue `bufs`. Each buffer is timed at its own frequency.
+fn queue_position<'a, I: Iterator<Item=&'a Buffer>>(mut played: Duration, mut offset: i64, bufs: I) -> Duration {
+	for buf in bufs {
+		if offset <= 0 {
+			break;
+		}
+		let len = offset.min(buf.len as i64);
+		played += frames_duration(len, buf.frequency());
+		offset -= len;
+	}
+	played
+}
+
+
+/// Treat running out of sources as an absent value rather than an error.
+fn exhausted<T>(result: AltoResult<T>) -> AltoResult<Option<T>> {
+	match result {
+		Ok(value) => Ok(Some(value)),
+		Err(AltoError::OutOfMemory) | Err(AltoError::InvalidValue) => Ok(None),
+		Err(e) => Err(e),
+	}
+}
+
+
+/// Treat a missing extension as an absent value rather than an error.
+fn optional<T>(result: AltoResult<T>) -> AltoResult<Option<T>> {
+	match result {
+		Ok(value) => Ok(Some(value)),
+		Err(AltoError::ExtensionNotPresent) => Ok(None),
+		Err(e) => Err(e),
+	}
+}
+
+
+fn check_orientation(at: [f32; 3], up: [f32; 3]) -> AltoResult<()> {
+	if at.iter().any(|c| !c.is_finite()) {
+		return Err(AltoError::InvalidOrientation("ALTO ERROR: Orientation `at` vector is not finite"));
+	}
+	if up.iter().any(|c| !c.is_finite()) {
+		return Err(AltoError::InvalidOrientation("ALTO ERROR: Orientation `up` vector is not finite"));
+	}
+
+	let (at_len2, up_len2) = (dot3(at, at), dot3(up, up));
+	if at_len2 == 0.0 {
+		return Err(AltoError::InvalidOrientation("ALTO ERROR: Orientation `at` vector is zero"));
+	}
+	if up_len2 == 0.0 {
+		return Err(AltoError::InvalidOrientation("ALTO ERROR: Orientation `up` vector is zero"));
+	}
+
+	let cross = [at[1] * up[2] - at[2] * up[1], at[2] * up[0] - at[0] * up[2], at[0] * up[1] - at[1] * up[0]];
+	if dot3(cross, cross) <= 1e-12 * at_len2 * up_len2 {
+		return Err(AltoError::InvalidOrientation("ALTO ERROR: Orientation `at` and `up` vectors are parallel"));
+	}
+
+	Ok(())
+}
+
+
+impl DistanceModel {
+	/// Compute the distance attenuation factor a source would receive under this model,
+	/// following the formulas of the OpenAL 1.1 specification. This does not require a context,
+	/// and does not account for cone attenuation or the source's min and max gain.
+	/// Unknown models yield no attenuation.
+	pub fn attenuation(&self, distance: f32, ref_dist: f32, max_dist: f32, rolloff: f32) -> f32 {
+		let clamped = |d: f32| if max_dist < ref_dist { d.max(ref_dist) } else { d.max(ref_dist).min(max_dist) };
+
+		match *self {
+			DistanceModel::Inverse | DistanceModel::InverseClamped => {
+				let distance = if *self == DistanceModel::InverseClamped { clamped(distance) } else { distance };
+				let denom = ref_dist + rolloff * (distance - ref_dist);
+				if denom > 0.0 { ref_dist / denom } else { 1.0 }
+			},
+			DistanceModel::Linear | DistanceModel::LinearClamped => {
+				let distance = if *self == DistanceModel::LinearClamped { clamped(distance) } else { distance.min(max_dist) };
+				if max_dist != ref_dist {
+					(1.0 - rolloff * (distance - ref_dist) / (max_dist - ref_dist)).max(0.0)
+				} else {
+					1.0
+				}
+			},
+			DistanceModel::Exponent | DistanceModel::ExponentClamped => {
+				let distance = if *self == DistanceModel::ExponentClamped { clamped(distance) } else { distance };
+				if distance > 0.0 && ref_dist > 0.0 { (distance / ref_dist).powf(-rolloff) } else { 1.0 }
+			},
+			DistanceModel::None | DistanceModel::Unknown(..) => 1.0,
+		}
+	}
+}
+
+
+impl Attenuation {
+	/// The reference distance, rolloff factor and max distance this applies.
+	pub fn values(&self) -> (f32, f32, f32) {
+		match *self {
+			Attenuation::SmallProp => (0.5, 1.5, 15.0),
+			Attenuation::Voice => (1.0, 1.0, 40.0),
+			Attenuation::Vehicle => (4.0, 1.0, 200.0),
+			Attenuation::Distant => (20.0, 0.5, 2000.0),
+			Attenuation::Custom{reference, rolloff, max} => (reference, rolloff, max),
+		}
+	}
+}
+
+
+impl Angle {
+	/// An angle of `value` degrees.
+	pub fn degrees(value: f32) -> Angle { Angle(value) }
+	/// An angle of `value` radians.
+	pub fn radians(value: f32) -> Angle { Angle(value.to_degrees()) }
+
+
+	/// This angle in degrees.
+	pub fn to_degrees(self) -> f32 { self.0 }
+	/// This angle in radians.
+	pub fn to_radians(self) -> f32 { self.0.to_radians() }
+}
+
+
+impl SourceState {
+	fn from_raw(value: sys::ALint) -> SourceState {
+		match value {
+			sys::AL_INITIAL => SourceState::Initial,
+			sys::AL_PLAYING => SourceState::Playing,
+			sys::AL_PAUSED => SourceState::Paused,
+			sys::AL_STOPPED => SourceState::Stopped,
+			ss => SourceState::Unknown(ss),
+		}
+	}
+}
+
+
+impl SourceType {
+	fn from_raw(value: sys::ALint) -> SourceType {
+		match value {
+			sys::AL_UNDETERMINED => SourceType::Undetermined,
+			sys::AL_STATIC => SourceType::Static,
+			sys::AL_STREAMING => SourceType::Streaming,
+			st => SourceType::Unknown(st),
+		}
+	}
 }
 
 
@@ -348,16 +1149,52 @@ impl Context {
 			ctx: ctx,
 			exts: exts,
 			defer_rc: Arc::new(AtomicUsize::new(0)),
+			error_checking: AtomicUsize::new(ErrorChecking::Always as usize),
+			mixes: Mutex::new(HashMap::new()),
+			listener_pos: Mutex::new(None),
+			events: EventRouter{
+				installed: AtomicBool::new(false),
+				buffer_completed: AtomicI32::new(0),
+				generation: Mutex::new(0),
+				cond: Condvar::new(),
+				buffer_handlers: Mutex::new(HashMap::new()),
+			},
+			fades: Mutex::new(HashMap::new()),
+			fade_cursor: AtomicUsize::new(0),
+			fade_timer: Mutex::new(None),
+			one_shots: Mutex::new(OneShotPool::new()),
+			handles: HandleQueue::new(),
+			sources: Mutex::new(HashMap::new()),
 		}))
 	}
 
 
 	/// The device from which this context was created.
 	pub fn device(&self) -> &Device { &self.0.dev }
-	/// Raw context pointer as provided by OpenAL.
+	/// Raw context pointer as provided by OpenAL, for passing to native code alongside `DeviceObject::as_raw`.
+	/// The pointer stays valid for as long as any clone of this context is alive, and must not be destroyed.
+	/// Native code that makes it current should restore the previous context afterwards, or run inside `with_current`.
 	pub fn as_raw(&self) -> *mut sys::ALCcontext { self.0.ctx }
 
 
+	/// `alcGetIntegerv(ALC_CONNECTED)`
+	/// Requires `ALC_EXT_disconnect`
+	/// Whether the device of this context is still connected. Once it is not, sources stop and can't be
+	/// played again, and any AL error raised by the context is reported as `AltoError::DeviceDisconnected`.
+	pub fn device_connected(&self) -> AltoResult<bool> {
+		self.0.dev.connected()
+	}
+
+
+	/// `alcGetIntegerv(ALC_MAX_AUXILIARY_SENDS)`
+	/// Requires `ALC_EXT_EFX`
+	/// The number of auxiliary sends granted to each source of this context by its device.
+	pub fn max_aux_sends(&self) -> AltoResult<sys::ALint> {
+		self.0.dev.0.exts.ALC_EXT_EFX()?;
+		Ok(self.0.dev.max_aux_sends())
+	}
+
+
 	/// `alIsExtensionPresent()`
 	pub fn is_extension_present(&self, ext: ext::Al) -> bool {
 		match ext {
@@ -375,6 +1212,7 @@ impl Context {
 //			ext::Al::SoftBufferSubData => self.0.ext.AL_SOFT_buffer_sub_data().is_ok(),
 			ext::Al::SoftDeferredUpdates => self.0.exts.AL_SOFT_deferred_updates().is_ok(),
 			ext::Al::SoftDirectChannels => self.0.exts.AL_SOFT_direct_channels().is_ok(),
+			ext::Al::SoftDirectChannelsRemix => self.0.exts.AL_SOFT_direct_channels_remix().is_ok(),
 			ext::Al::SoftLoopPoints => self.0.exts.AL_SOFT_loop_points().is_ok(),
 			ext::Al::SoftMsadpcm => self.0.exts.AL_SOFT_MSADPCM().is_ok(),
 			ext::Al::SoftSourceLatency => self.0.exts.AL_SOFT_source_latency().is_ok(),
@@ -385,10 +1223,46 @@ impl Context {
 			ext::Al::SoftGainClampEx => self.0.exts.AL_SOFT_gain_clamp_ex().is_ok(),
 			ext::Al::StereoAngles => self.0.exts.AL_EXT_STEREO_ANGLES().is_ok(),
 			ext::Al::SourceRadius => self.0.exts.AL_EXT_SOURCE_RADIUS().is_ok(),
+			ext::Al::SoftSourceStartDelay => self.0.exts.AL_SOFT_source_start_delay().is_ok(),
+			ext::Al::SoftEvents => self.0.exts.AL_SOFT_events().is_ok(),
+			ext::Al::SoftCallbackBuffer => self.0.exts.AL_SOFT_callback_buffer().is_ok(),
+			ext::Al::SoftBufferLengthQuery => self.0.exts.AL_SOFT_buffer_length_query().is_ok(),
+		}
+	}
+
+
+	/// Current error checking policy for property setters.
+	pub fn error_checking(&self) -> ErrorChecking {
+		match self.0.error_checking.load(Ordering::Relaxed) {
+			ec if ec == ErrorChecking::OnDebug as usize => ErrorChecking::OnDebug,
+			ec if ec == ErrorChecking::Never as usize => ErrorChecking::Never,
+			_ => ErrorChecking::Always,
+		}
+	}
+	/// Set the error checking policy for the per-frame float and vector property setters
+	/// of the listener and of sources created from this context.
+	/// Since OpenAL only records the first error that occurs, any error left pending while
+	/// checking was disabled is discarded whenever the policy changes.
+	pub fn set_error_checking(&self, value: ErrorChecking) {
+		let old = self.0.error_checking.swap(value as usize, Ordering::SeqCst);
+		if old != value as usize {
+			let _lock = self.make_current(true);
+			unsafe { self.0.dev.0.alto.0.api.alGetError(); }
 		}
 	}
 
 
+	/// `alGetError()`
+	/// Discard any error pending on this context. OpenAL keeps a single error slot per context, which
+	/// records the first error to occur and is only reset by reading it, so later errors are lost while
+	/// an earlier one is pending. Clearing it before a sequence of calls ensures that any error reported
+	/// afterwards was caused by those calls.
+	pub fn clear_error(&self) {
+		let _lock = self.make_current(true);
+		unsafe { self.0.dev.0.alto.0.api.alGetError(); }
+	}
+
+
 	/// `alGetInteger(AL_DISTANCE_MODEL)`
 	pub fn distance_model(&self) -> DistanceModel {
 		let _lock = self.make_current(true);
@@ -479,7 +1353,7 @@ impl Context {
 	pub fn set_gain(&self, value: f32) -> AltoResult<()> {
 		let _lock = self.make_current(true);
 		unsafe { self.0.dev.0.alto.0.api.alListenerf(sys::AL_GAIN, value); }
-		self.get_error()
+		self.get_prop_error()
 	}
 
 
@@ -491,11 +1365,11 @@ impl Context {
 		value.into()
 	}
 	/// `alListenerfv(AL_POSITION)`
-	pub fn set_position<V: Into<[f32; 3]>>(&self, value: V) -> AltoResult<()> {
+	pub fn set_position<V: AsAlVec3>(&self, value: V) -> AltoResult<()> {
 		let _lock = self.make_current(true);
-		let value = value.into();
+		let value = value.as_al_vec3();
 		unsafe { self.0.dev.0.alto.0.api.alListenerfv(sys::AL_POSITION, &value as *const [f32; 3] as *const sys::ALfloat); }
-		self.get_error()
+		self.get_prop_error()
 	}
 
 
@@ -507,11 +1381,37 @@ impl Context {
 		value.into()
 	}
 	/// `alListenerfv(AL_VELOCITY)`
-	pub fn set_velocity<V: Into<[f32; 3]>>(&self, value: V) -> AltoResult<()> {
+	pub fn set_velocity<V: AsAlVec3>(&self, value: V) -> AltoResult<()> {
 		let _lock = self.make_current(true);
-		let value = value.into();
+		let value = value.as_al_vec3();
 		unsafe { self.0.dev.0.alto.0.api.alListenerfv(sys::AL_VELOCITY, &value as *const [f32; 3] as *const sys::ALfloat); }
-		self.get_error()
+		self.get_prop_error()
+	}
+
+
+	/// `alListenerfv(AL_POSITION)` and `alListenerfv(AL_VELOCITY)`
+	/// Set the listener position along with a velocity derived from the position passed to the previous call
+	/// and the elapsed time in seconds. The first call sets zero velocity, and if `dt` is zero only the
+	/// position is changed.
+	pub fn update_listener_position_with_velocity<V: AsAlVec3>(&self, pos: V, dt: f32) -> AltoResult<()> {
+		let pos = pos.as_al_vec3();
+		let mut last_pos = self.0.listener_pos.lock();
+		if dt == 0.0 {
+			self.set_position(pos)?;
+			*last_pos = Some(pos);
+			return Ok(());
+		}
+		let vel = derive_velocity(*last_pos, pos, dt, f32::INFINITY)?;
+
+		let _lock = self.make_current(true);
+		unsafe {
+			self.0.dev.0.alto.0.api.alListenerfv(sys::AL_POSITION, &pos as *const [f32; 3] as *const sys::ALfloat);
+			self.0.dev.0.alto.0.api.alListenerfv(sys::AL_VELOCITY, &vel as *const [f32; 3] as *const sys::ALfloat);
+		}
+		self.get_prop_error()?;
+
+		*last_pos = Some(pos);
+		Ok(())
 	}
 
 
@@ -523,11 +1423,25 @@ impl Context {
 		(value[0].into(), value[1].into())
 	}
 	/// `alListenerfv(AL_ORIENTATION)`
-	pub fn set_orientation<V: Into<[f32; 3]>>(&self, value: (V, V)) -> AltoResult<()> {
+	/// Both vectors must be finite and non-zero, and must not be parallel.
+	pub fn set_orientation<V: AsAlVec3>(&self, value: (V, V)) -> AltoResult<()> {
+		let value = [value.0.as_al_vec3(), value.1.as_al_vec3()];
+		check_orientation(value[0], value[1])?;
+
 		let _lock = self.make_current(true);
-		let value = [value.0.into(), value.1.into()];
 		unsafe { self.0.dev.0.alto.0.api.alListenerfv(sys::AL_ORIENTATION, &value as *const [[f32; 3]; 2] as *const sys::ALfloat); }
-		self.get_error()
+		self.get_prop_error()
+	}
+	/// `alListenerfv(AL_ORIENTATION)`
+	/// Like `set_orientation`, but normalizes the at vector and makes the up vector a unit vector orthogonal to it first.
+	pub fn set_orientation_normalized<V: AsAlVec3>(&self, value: (V, V)) -> AltoResult<()> {
+		let (at, up) = (value.0.as_al_vec3(), value.1.as_al_vec3());
+		check_orientation(at, up)?;
+
+		let at = scale3(at, 1.0 / dot3(at, at).sqrt());
+		let up = sub3(up, scale3(at, dot3(up, at)));
+		let up = scale3(up, 1.0 / dot3(up, up).sqrt());
+		self.set_orientation((at, up))
 	}
 
 
@@ -584,13 +1498,105 @@ impl Context {
 	}
 
 
-	/// `alGenBuffers()`
-	/// To ensure well defined state, the buffer is assigned data immediately.
-	pub fn new_buffer<F: SampleFrame, B: AsBufferData<F>>(&self, data: B, freq: i32) -> AltoResult<Buffer> {
+	/// World scale in units per meter, derived from `AL_SPEED_OF_SOUND` and,
+	/// if `ALC_EXT_EFX` is present, `AL_METERS_PER_UNIT`.
+	pub fn units_per_meter(&self) -> UnitsPerMeter {
+		let sos_units = self.speed_of_sound() / SPEED_OF_SOUND_METERS;
+		if self.0.dev.0.exts.ALC_EXT_EFX().is_err() {
+			return UnitsPerMeter::Coherent(sos_units);
+		}
+
+		let mpu_units = 1.0 / self.meters_per_unit();
+		if (sos_units - mpu_units).abs() <= 1e-4 * sos_units.abs().max(mpu_units.abs()) {
+			UnitsPerMeter::Coherent(sos_units)
+		} else {
+			UnitsPerMeter::Diverged{speed_of_sound: sos_units, meters_per_unit: mpu_units}
+		}
+	}
+	/// `alSpeedOfSound()` and `alListenerf(AL_METERS_PER_UNIT)`
+	/// Scale the speed of sound and, if `ALC_EXT_EFX` is present, the EFX meters per unit consistently
+	/// for a world measured in `units` per meter. Both are applied in one deferred batch.
+	pub fn set_units_per_meter(&self, units: f32) -> AltoResult<()> {
+		if !units.is_finite() || units <= 0.0 {
+			return Err(AltoError::InvalidValue);
+		}
+
+		let ctx = self.defer_updates();
+		ctx.set_speed_of_sound(SPEED_OF_SOUND_METERS * units)?;
+		if ctx.0.dev.0.exts.ALC_EXT_EFX().is_ok() {
+			ctx.set_meters_per_unit(1.0 / units)?;
+		}
+		Ok(())
+	}
+
+
+	/// `alGenBuffers()`
+	/// To ensure well defined state, the buffer is assigned data immediately.
+	pub fn new_buffer<F: SampleFrame, B: AsBufferData<F>>(&self, data: B, freq: i32) -> AltoResult<Buffer> {
 		Buffer::new(self.clone(), data, freq)
 	}
 
 
+	/// `alGenBuffers()`, `alBufferCallbackSOFT()`
+	/// Requires `AL_SOFT_callback_buffer`
+	/// Create a buffer whose sample frames are supplied by `callback` as the source playing it needs them.
+	/// The callback fills the start of the slice it is given and returns how many frames it wrote; writing
+	/// fewer than requested ends playback. It is called on the mixer thread, so it must be real-time safe,
+	/// meaning it should not block, allocate or take locks, and it must not call into OpenAL or alto.
+	pub fn new_callback_buffer<F: SampleFrame, C: FnMut(&mut [F]) -> usize + Send + 'static>(&self, freq: i32, callback: C) -> AltoResult<CallbackBuffer> {
+		CallbackBuffer::new(self.clone(), freq, callback)
+	}
+
+
+	/// `alBufferData()`
+	/// Whether buffers of the raw `format` token can be created on this context, such as one obtained from
+	/// `Format::into_raw`. This is probed by uploading no data to a temporary buffer, which is deleted
+	/// afterwards, so that an unsupported format can be detected before the real upload.
+	pub fn is_buffer_format_supported(&self, format: sys::ALenum) -> bool {
+		let _lock = self.make_current(true);
+		let api = &self.0.dev.0.alto.0.api;
+		let _ = self.get_error();
+
+		let mut buf = 0;
+		unsafe { api.alGenBuffers(1, &mut buf as *mut sys::ALuint); }
+		if self.get_error().is_err() {
+			return false;
+		}
+
+		let data = [0u8; 4];
+		unsafe { api.alBufferData(buf, format, data.as_ptr() as *const sys::ALvoid, 0, 44100); }
+		let supported = self.get_error().is_ok();
+		unsafe { api.alDeleteBuffers(1, &buf as *const sys::ALuint); }
+		let _ = self.get_error();
+		supported
+	}
+
+
+	/// `alIsBuffer()`
+	/// Check that `buf` may be used by sources of this context, whichever context of the same device created it.
+	/// Fails with `AltoError::WrongDevice` if it belongs to another device, or `AltoError::InvalidName` if
+	/// the implementation does not recognize the buffer from this context.
+	pub fn check_buffer(&self, buf: &Buffer) -> AltoResult<()> {
+		if !self.shares_buffers_with(&buf.ctx) {
+			return Err(AltoError::WrongDevice);
+		}
+
+		let _lock = self.make_current(true);
+		if unsafe { self.0.dev.0.alto.0.api.alIsBuffer(buf.buf) } == sys::AL_TRUE {
+			Ok(())
+		} else {
+			Err(AltoError::InvalidName)
+		}
+	}
+
+
+	/// Whether buffers created from this context can be used by sources of `other`, which is
+	/// the case exactly when both contexts were created on the same device.
+	pub fn shares_buffers_with(&self, other: &Context) -> bool {
+		self.device().as_raw() == other.device().as_raw()
+	}
+
+
 	/// `alGenSources()`
 	pub fn new_static_source(&self) -> AltoResult<StaticSource> {
 		StaticSource::new(self.clone())
@@ -603,6 +1609,66 @@ impl Context {
 	}
 
 
+	/// `alIsSource()`
+	/// Wrap a source generated outside of alto, such as by native middleware sharing this context.
+	/// Its `AL_SOURCE_TYPE` must agree with `kind` unless it is still undetermined, or this fails with
+	/// `AltoError::SourceTypeMismatch`.
+	/// A streaming source must have an empty queue, since alto only tracks the buffers it queues itself,
+	/// while a buffer already attached to a static source stays unowned, so `StaticSource::buffer`
+	/// returns `None` until one is set. If `delete_on_drop` is true the source is deleted when the wrapper
+	/// is dropped, just like sources created by alto; otherwise it is left to its creator.
+	///
+	/// # Safety
+	/// `id` must be a source of this context that no other alto object wraps. Unless `delete_on_drop` is set,
+	/// it must also remain valid until the wrapper is dropped.
+	pub unsafe fn adopt_raw_source(&self, id: sys::ALuint, kind: SourceKind, delete_on_drop: bool) -> AltoResult<AnySource> {
+		let (ty, queued) = {
+			let _lock = self.make_current(true);
+			let api = &self.0.dev.0.alto.0.api;
+			if api.alIsSource(id) == sys::AL_FALSE {
+				return Err(AltoError::InvalidName);
+			}
+
+			let (mut ty, mut queued) = (0, 0);
+			api.alGetSourcei(id, sys::AL_SOURCE_TYPE, &mut ty);
+			api.alGetSourcei(id, sys::AL_BUFFERS_QUEUED, &mut queued);
+			self.get_error()?;
+			(SourceType::from_raw(ty), queued)
+		};
+
+		match (kind, ty) {
+			(SourceKind::Static, SourceType::Static) | (SourceKind::Static, SourceType::Undetermined) => {
+				Ok(AnySource::Static(StaticSource::from_raw(self.clone(), id, delete_on_drop)))
+			},
+			(SourceKind::Streaming, SourceType::Streaming) | (SourceKind::Streaming, SourceType::Undetermined) => {
+				if queued != 0 {
+					return Err(AltoError::InvalidOperation);
+				}
+				Ok(AnySource::Streaming(StreamingSource::from_raw(self.clone(), id, delete_on_drop)))
+			},
+			(_, ty) => Err(AltoError::SourceTypeMismatch(ty)),
+		}
+	}
+
+
+	/// `alGenSources()`
+	/// Like `new_static_source`, but returns `Ok(None)` if the implementation has no more sources to give,
+	/// which OpenAL reports as `AL_OUT_OF_MEMORY` or `AL_INVALID_VALUE`. Any other error is returned as usual.
+	/// This lets an application that spawns sounds dynamically tell a full source pool, where it may steal
+	/// a voice, from a genuine failure.
+	pub fn try_new_static_source(&self) -> AltoResult<Option<StaticSource>> {
+		exhausted(self.new_static_source())
+	}
+
+
+	/// `alGenSources()`
+	/// Like `new_streaming_source`, but returns `Ok(None)` if the implementation has no more sources to give.
+	/// See `try_new_static_source`.
+	pub fn try_new_streaming_source(&self) -> AltoResult<Option<StreamingSource>> {
+		exhausted(self.new_streaming_source())
+	}
+
+
 	/// `alDeferUpdatesSOFT()`
 	/// Requires `AL_SOFT_deferred_updates`
 	pub fn defer_updates(&self) -> DeferLock {
@@ -610,6 +1676,304 @@ impl Context {
 	}
 
 
+	/// `alSourcePlayv()`
+	/// Start all of the given sources on the same mix sample.
+	///
+	/// Like the other batch operations, the sources may be of mixed types when passed as `&mut dyn SourceObject`,
+	/// and errors are checked once after the whole batch. Every source is checked against this context before
+	/// anything is issued, and OpenAL validates every source before acting on any, so if an error is returned
+	/// no source has changed state.
+	pub fn play_all<S: SourceObject + ?Sized>(&self, sources: &mut [&mut S]) -> AltoResult<()> {
+		self.source_batch(sources, |api, n, srcs| unsafe { api.alSourcePlayv(n, srcs) })
+	}
+	/// `alSourcePausev()`
+	pub fn pause_all<S: SourceObject + ?Sized>(&self, sources: &mut [&mut S]) -> AltoResult<()> {
+		self.source_batch(sources, |api, n, srcs| unsafe { api.alSourcePausev(n, srcs) })
+	}
+	/// `alSourceStopv()`
+	pub fn stop_all<S: SourceObject + ?Sized>(&self, sources: &mut [&mut S]) -> AltoResult<()> {
+		self.source_batch(sources, |api, n, srcs| unsafe { api.alSourceStopv(n, srcs) })
+	}
+	/// `alSourceRewindv()`
+	pub fn rewind_all<S: SourceObject + ?Sized>(&self, sources: &mut [&mut S]) -> AltoResult<()> {
+		self.source_batch(sources, |api, n, srcs| unsafe { api.alSourceRewindv(n, srcs) })
+	}
+
+
+	/// `alSourceStopv()`
+	/// Stop every playing or paused source of this context, including those of `play_once`, with a single call,
+	/// and return how many there were. Sources dropped by other threads meanwhile are simply skipped.
+	/// As with `stop_all`, a streaming source stopped this way still counts as intended to play.
+	pub fn stop_all_sources(&self) -> AltoResult<usize> {
+		let pool = self.0.one_shots.lock();
+		let sources = self.0.sources.lock();
+		let _lock = self.make_current(true);
+		let api = &self.0.dev.0.alto.0.api;
+
+		let active: Vec<_> = sources.keys().cloned().chain(pool.busy_sources())
+			.filter(|&src| {
+				let mut state = 0;
+				unsafe { api.alGetSourcei(src, sys::AL_SOURCE_STATE, &mut state); }
+				state == sys::AL_PLAYING || state == sys::AL_PAUSED
+			})
+			.collect();
+		if !active.is_empty() {
+			unsafe { api.alSourceStopv(active.len() as sys::ALsizei, active.as_ptr()); }
+		}
+		self.get_error().map(|_| active.len())
+	}
+
+
+	/// Call `f` with the id, state and gain of every source of this context, including those of `play_once`,
+	/// such as for a debugging overlay. The sources are queried up front, so `f` sees a snapshot, and may
+	/// itself create or drop sources. Sources dropped by other threads meanwhile are simply skipped.
+	pub fn for_each_source<F: FnMut(RawSourceInfo)>(&self, mut f: F) {
+		let infos: Vec<_> = {
+			let pool = self.0.one_shots.lock();
+			let sources = self.0.sources.lock();
+			let _lock = self.make_current(true);
+			let api = &self.0.dev.0.alto.0.api;
+
+			sources.keys().cloned().chain(pool.busy_sources())
+				.map(|id| {
+					let (mut state, mut gain) = (0, 0.0);
+					unsafe {
+						api.alGetSourcei(id, sys::AL_SOURCE_STATE, &mut state);
+						api.alGetSourcef(id, sys::AL_GAIN, &mut gain);
+					}
+					RawSourceInfo{id, state: SourceState::from_raw(state), gain}
+				})
+				.collect()
+		};
+
+		for info in infos {
+			f(info);
+		}
+	}
+
+
+	/// `alSourcePlayAtTimevSOFT()`
+	/// Requires `AL_SOFT_source_start_delay`
+	/// Start all of the given sources together when the device clock reaches `time` nanoseconds.
+	pub fn soft_play_all_at_time<S: SourceObject + ?Sized>(&self, sources: &mut [&mut S], time: i64) -> AltoResult<()> {
+		let apat = self.0.exts.AL_SOFT_source_start_delay()?.alSourcePlayAtTimevSOFT?;
+		self.source_batch(sources, |_, n, srcs| unsafe { apat(n, srcs, time) })
+	}
+
+
+	fn source_batch<S: SourceObject + ?Sized, F: FnOnce(&sys::AlApi, sys::ALsizei, *const sys::ALuint)>(&self, sources: &mut [&mut S], f: F) -> AltoResult<()> {
+		if sources.iter().any(|s| *s.object_context() != *self) {
+			return Err(AltoError::WrongContext);
+		}
+
+		let srcs: Vec<_> = sources.iter().map(|s| s.object_raw()).collect();
+		let _lock = self.make_current(true);
+		f(&self.0.dev.0.alto.0.api, srcs.len() as sys::ALsizei, srcs.as_ptr());
+		self.get_error()
+	}
+
+
+	/// Create a voice manager that plays logical voices on at most `max_sources` real sources.
+	pub fn new_voice_manager(&self, max_sources: usize) -> VoiceManager {
+		VoiceManager::new(self.clone(), max_sources)
+	}
+
+
+	/// Create an empty source group with unit gain and pitch.
+	pub fn new_source_group(&self) -> SourceGroup {
+		SourceGroup::new(self.clone())
+	}
+
+
+	/// Advance every gain fade on this context by `dt`, applying the new gains in a single deferred batch.
+	/// Applications that drive their own update loop call this once per frame; otherwise see `set_fade_timer`.
+	pub fn tick(&self, dt: Duration) -> AltoResult<()> {
+		let _defer = self.defer_updates();
+		let mut fades = self.0.fades.lock();
+		let mut result = Ok(());
+		fades.retain(|&src, fade| {
+			match self.advance_fade(src, fade, dt) {
+				Ok(keep) => keep,
+				Err(e) => {
+					result = Err(e);
+					false
+				},
+			}
+		});
+		result
+	}
+
+
+	/// Finalize the fades that have completed or whose source has stopped, within `allowance`, resuming after the
+	/// source at which the last call ran out. Returns how many were finalized and whether every fade was examined.
+	pub(crate) fn finalize_fades(&self, allowance: &mut Allowance) -> AltoResult<(usize, bool)> {
+		let mut fades = self.0.fades.lock();
+		let mut srcs: Vec<_> = fades.keys().cloned().collect();
+		srcs.sort();
+		let resume = self.0.fade_cursor.load(Ordering::Relaxed) as sys::ALuint;
+		let start = srcs.iter().position(|&src| src >= resume).unwrap_or(0);
+		srcs.rotate_left(start);
+
+		let mut finalized = 0;
+		for src in srcs {
+			if !allowance.take() {
+				self.0.fade_cursor.store(src as usize, Ordering::Relaxed);
+				return Ok((finalized, false));
+			}
+
+			let fade = fades.get_mut(&src).unwrap();
+			let stopped = fade.restore.is_some() && {
+				let _lock = self.make_current(true);
+				let mut value = 0;
+				unsafe { self.0.dev.0.alto.0.api.alGetSourcei(src, sys::AL_SOURCE_STATE, &mut value); }
+				value == sys::AL_STOPPED
+			};
+			if stopped || fade.elapsed >= fade.duration {
+				let result = self.advance_fade(src, fade, Duration::from_secs(0));
+				fades.remove(&src);
+				result?;
+				finalized += 1;
+			}
+		}
+		self.0.fade_cursor.store(0, Ordering::Relaxed);
+		Ok((finalized, true))
+	}
+
+
+	/// Apply the next step of a fade, returning whether it is still running.
+	fn advance_fade(&self, src: sys::ALuint, fade: &mut Fade, dt: Duration) -> AltoResult<bool> {
+		if let Some(restore) = fade.restore {
+			let stopped = {
+				let _lock = self.make_current(true);
+				let mut value = 0;
+				unsafe { self.0.dev.0.alto.0.api.alGetSourcei(src, sys::AL_SOURCE_STATE, &mut value); }
+				value == sys::AL_STOPPED
+			};
+			if stopped {
+				self.set_source_gain(src, restore)?;
+				return Ok(false);
+			}
+		}
+
+		fade.elapsed = fade.elapsed.checked_add(dt).unwrap_or(fade.duration);
+		if fade.elapsed < fade.duration {
+			let gain = fade.from + (fade.to - fade.from) * (fade.elapsed.as_secs_f32() / fade.duration.as_secs_f32());
+			self.set_source_gain(src, gain)?;
+			return Ok(true);
+		}
+
+		match fade.restore {
+			Some(restore) => {
+				{
+					let _lock = self.make_current(true);
+					unsafe { self.0.dev.0.alto.0.api.alSourceStop(src); }
+				}
+				self.set_source_gain(src, restore)?;
+			},
+			None => self.set_source_gain(src, fade.to)?,
+		}
+		Ok(false)
+	}
+
+
+	/// Advance gain fades from a background thread every `interval`, or stop doing so with `None`.
+	/// A context has at most one such thread, which exits once the context is dropped.
+	pub fn set_fade_timer(&self, interval: Option<Duration>) -> AltoResult<()> {
+		if interval == Some(Duration::from_secs(0)) {
+			return Err(AltoError::InvalidValue);
+		}
+
+		let mut timer = self.0.fade_timer.lock();
+		if let Some(stop) = timer.take() {
+			stop.store(true, Ordering::Release);
+		}
+
+		if let Some(interval) = interval {
+			let stop = Arc::new(AtomicBool::new(false));
+			let thread_stop = stop.clone();
+			let weak = WeakContext(Arc::downgrade(&self.0));
+			thread::Builder::new().name("alto-fade-timer".into()).spawn(move || {
+				let mut last = Instant::now();
+				loop {
+					thread::sleep(interval);
+					if thread_stop.load(Ordering::Acquire) {
+						break;
+					}
+					let ctx = match weak.0.upgrade() {
+						Some(inner) => Context(inner),
+						None => break,
+					};
+
+					let now = Instant::now();
+					let _ = ctx.tick(now - last);
+					last = now;
+				}
+			}).map_err(AltoError::Io)?;
+			*timer = Some(stop);
+		}
+		Ok(())
+	}
+
+
+	/// Start tracking positions to compute Doppler velocities automatically.
+	pub fn begin_auto_doppler(&self) -> DopplerTracker {
+		DopplerTracker::new(self.clone())
+	}
+
+
+	/// Whether any `DeferLock` is currently held on this context, on any thread.
+	/// Always false if `AL_SOFT_deferred_updates` is unavailable, since updates are then never deferred.
+	pub fn is_suspended(&self) -> bool {
+		self.0.defer_rc.load(Ordering::SeqCst) != 0
+	}
+
+
+	/// `alcGetThreadContext()` or `alcGetCurrentContext()`
+	/// Whether this context is current for the calling thread, either through its thread-local slot
+	/// or, if no thread context is set, the process-wide one.
+	/// Neither query can fail. The thread-local slot can only change on the calling thread, so it is read
+	/// without locking; only the process-wide fallback takes the context lock.
+	pub fn is_current(&self) -> bool {
+		let alto = &self.0.dev.0.alto;
+
+		if let Ok(&ext::ALC_EXT_thread_local_context{alcGetThreadContext: Ok(agtc), ..}) = alto.0.exts.ALC_EXT_thread_local_context() {
+			let thread_ctx = unsafe { agtc() };
+			if !thread_ctx.is_null() {
+				return thread_ctx == self.0.ctx;
+			}
+		}
+
+		let _lock = ALTO_CTX_LOCK.lock();
+		unsafe { alto.0.api.alcGetCurrentContext() == self.0.ctx }
+	}
+
+
+	/// `alcSetThreadContext()` or `alcMakeContextCurrent()`
+	/// Make this context current while `f` runs, then restore the context that was current before,
+	/// even if `f` panics. This is meant for code that calls OpenAL directly, such as through `Alto::raw_api`.
+	/// The thread-local slot is used if `ALC_EXT_thread_local_context` is present. Otherwise the process-wide
+	/// context is switched and alto's context lock is held while `f` runs, so `f` must not call into alto.
+	pub fn with_current<R, F: FnOnce() -> R>(&self, f: F) -> AltoResult<R> {
+		let alto = &self.0.dev.0.alto;
+		let _restore = if let Ok(&ext::ALC_EXT_thread_local_context{alcSetThreadContext: Ok(astc), alcGetThreadContext: Ok(agtc)}) = alto.0.exts.ALC_EXT_thread_local_context() {
+			let prev = unsafe { agtc() };
+			if unsafe { astc(self.0.ctx) } == sys::ALC_FALSE {
+				return Err(AltoError::InvalidContext);
+			}
+			RestoreCurrent{alto, prev, thread: true, _lock: None}
+		} else {
+			let lock = ALTO_CTX_LOCK.lock();
+			let prev = unsafe { alto.0.api.alcGetCurrentContext() };
+			if unsafe { alto.0.api.alcMakeContextCurrent(self.0.ctx) } == sys::ALC_FALSE {
+				return Err(AltoError::InvalidContext);
+			}
+			RestoreCurrent{alto, prev, thread: false, _lock: Some(lock)}
+		};
+
+		Ok(f())
+	}
+
+
 	/// `alGenAuxiliaryEffectSlots()`
 	/// Requires `ALC_EXT_EFX`
 	pub fn new_aux_effect_slot(&self) -> AltoResult<AuxEffectSlot> {
@@ -641,8 +2005,10 @@ impl Context {
 			unsafe { astc(ctx); }
 			None
 		} else {
+			// Take the lock before switching, or another thread's calls could land on this context.
+			let lock = ALTO_CTX_LOCK.lock();
 			unsafe { dev.0.alto.0.api.alcMakeContextCurrent(ctx); }
-			Some(ALTO_CTX_LOCK.lock())
+			Some(lock)
 		}
 	}
 
@@ -650,7 +2016,70 @@ impl Context {
 	pub(crate) fn get_error(&self) -> AltoResult<()> {
 		match unsafe { self.0.dev.0.alto.0.api.alGetError() } {
 			sys::AL_NO_ERROR => Ok(()),
-			e => Err(AltoError::from_al(e))
+			// Only checked once something failed, so the usual path doesn't pay for the query.
+			e => Err(AltoError::from_al_on_device(e, self.device_connected())),
+		}
+	}
+
+
+	/// Install the `AL_SOFT_events` callback that routes source state changes and buffer completions,
+	/// if it isn't already, and return the router.
+	fn events(&self) -> Option<&EventRouter> {
+		if !self.0.events.installed.load(Ordering::Acquire) {
+			let ase = self.0.exts.AL_SOFT_events().ok()?;
+			let changed = ase.AL_EVENT_TYPE_SOURCE_STATE_CHANGED_SOFT.ok()?;
+			let _lock = self.make_current(true);
+			unsafe {
+				ase.alEventCallbackSOFT.ok()?(Some(event_callback), &self.0.events as *const EventRouter as *mut sys::ALvoid);
+				ase.alEventControlSOFT.ok()?(1, &changed, sys::AL_TRUE);
+			}
+			self.get_error().ok()?;
+			self.0.events.installed.store(true, Ordering::Release);
+		}
+
+		Some(&self.0.events)
+	}
+
+
+	/// Enable buffer-completed events for the router, if they aren't already.
+	fn enable_buffer_events(&self) -> AltoResult<&EventRouter> {
+		let ase = self.0.exts.AL_SOFT_events()?;
+		let completed = ase.AL_EVENT_TYPE_BUFFER_COMPLETED_SOFT?;
+		let events = self.events().ok_or(AltoError::ExtensionNotPresent)?;
+		if events.buffer_completed.load(Ordering::Acquire) == 0 {
+			let _lock = self.make_current(true);
+			unsafe { ase.alEventControlSOFT?(1, &completed, sys::AL_TRUE); }
+			self.get_error()?;
+			events.buffer_completed.store(completed, Ordering::Release);
+		}
+
+		Ok(events)
+	}
+
+
+	/// Set the gain of a source, scaled by the gain of its group if it has one.
+	fn set_source_gain(&self, src: sys::ALuint, value: f32) -> AltoResult<()> {
+		let mut mixes = self.0.mixes.lock();
+		let mix = mixes.get_mut(&src);
+		let scale = mix.as_ref().map(|mix| mix.gain).unwrap_or(1.0);
+
+		let _lock = self.make_current(true);
+		unsafe { self.0.dev.0.alto.0.api.alSourcef(src, sys::AL_GAIN, value * scale); }
+		self.get_prop_error()?;
+
+		if let Some(mix) = mix {
+			mix.base_gain = value;
+		}
+		Ok(())
+	}
+
+
+	/// Like `get_error`, but honors the error checking policy of the context.
+	pub(crate) fn get_prop_error(&self) -> AltoResult<()> {
+		match self.error_checking() {
+			ErrorChecking::Always => self.get_error(),
+			ErrorChecking::OnDebug if cfg!(debug_assertions) => self.get_error(),
+			_ => Ok(()),
 		}
 	}
 }
@@ -671,8 +2100,21 @@ impl Eq for Context { }
 
 impl Drop for ContextInner {
 	fn drop(&mut self) {
-		let _lock = Context::make_raw_current(&self.dev, ptr::null_mut());
-		unsafe { self.dev.0.alto.0.api.alcDestroyContext(self.ctx); }
+		// Every alto object holds a reference to its context, so no other thread can be using it by now.
+		// Release it from both current slots under the lock so it can't be made current again before it's destroyed.
+		let api = &self.dev.0.alto.0.api;
+		let _lock = ALTO_CTX_LOCK.lock();
+
+		if let Ok(&ext::ALC_EXT_thread_local_context{alcSetThreadContext: Ok(astc), alcGetThreadContext: Ok(agtc)}) = self.dev.0.alto.0.exts.ALC_EXT_thread_local_context() {
+			if unsafe { agtc() } == self.ctx {
+				unsafe { astc(ptr::null_mut()); }
+			}
+		}
+		if unsafe { api.alcGetCurrentContext() } == self.ctx {
+			unsafe { api.alcMakeContextCurrent(ptr::null_mut()); }
+		}
+
+		unsafe { api.alcDestroyContext(self.ctx); }
 	}
 }
 
@@ -681,6 +2123,73 @@ unsafe impl Send for Context { }
 unsafe impl Sync for Context { }
 
 
+unsafe impl Send for WeakContext { }
+
+
+impl<'a> Drop for RestoreCurrent<'a> {
+	fn drop(&mut self) {
+		if self.thread {
+			if let Ok(&ext::ALC_EXT_thread_local_context{alcSetThreadContext: Ok(astc), ..}) = self.alto.0.exts.ALC_EXT_thread_local_context() {
+				unsafe { astc(self.prev); }
+			}
+		} else {
+			unsafe { self.alto.0.api.alcMakeContextCurrent(self.prev); }
+		}
+	}
+}
+
+
+impl FadeHandle {
+	/// Whether the fade has reached its target, been replaced by another fade, or lost its source.
+	pub fn is_finished(&self) -> bool {
+		self.ctx.0.fades.lock().get(&self.src).map(|fade| fade.id != self.id).unwrap_or(true)
+	}
+
+
+	/// Let the fade run to completion without keeping the handle.
+	pub fn detach(mut self) {
+		self.detached = true;
+	}
+}
+
+
+impl Drop for FadeHandle {
+	fn drop(&mut self) {
+		if self.detached {
+			return;
+		}
+
+		let mut fades = self.ctx.0.fades.lock();
+		if fades.get(&self.src).map(|fade| fade.id == self.id).unwrap_or(false) {
+			fades.remove(&self.src);
+		}
+	}
+}
+
+
+extern "C" fn event_callback(ty: sys::ALenum, object: sys::ALuint, param: sys::ALuint, _: sys::ALsizei, _: *const sys::ALchar, user: *mut sys::ALvoid) {
+	// Called from OpenAL's event thread. Only the enabled types are delivered, so anything else is a state change.
+	let events = unsafe { &*(user as *const EventRouter) };
+	if ty == events.buffer_completed.load(Ordering::Acquire) {
+		if let Some(handler) = events.buffer_handlers.lock().get_mut(&object) {
+			handler(param as usize);
+		}
+	} else {
+		// Waiters re-check the state themselves.
+		*events.generation.lock() += 1;
+		events.cond.notify_all();
+	}
+}
+
+
+unsafe extern "C" fn buffer_callback<F: SampleFrame>(user: *mut sys::ALvoid, data: *mut sys::ALvoid, len: sys::ALsizei) -> sys::ALsizei {
+	let callback = &mut *(user as *mut BufferCallback<F>);
+	let frame_size = mem::size_of::<F::Sample>() * F::len();
+	let frames = slice::from_raw_parts_mut(data as *mut F, len as usize / frame_size);
+	(cmp::min(callback(frames), frames.len()) * frame_size) as sys::ALsizei
+}
+
+
 impl<'c> DeferLock<'c> {
 	fn new(ctx: &'c Context) -> DeferLock {
 		let _ = (|| -> AltoResult<_> {
@@ -725,6 +2234,243 @@ impl<'c> Drop for DeferLock<'c> {
 }
 
 
+impl SourceGroup {
+	fn new(ctx: Context) -> SourceGroup {
+		SourceGroup{ctx, id: NEXT_SOURCE_GROUP.fetch_add(1, Ordering::Relaxed), gain: 1.0, pitch: 1.0, members: Vec::new()}
+	}
+
+
+	/// The context from which this group was created.
+	pub fn context(&self) -> &Context { &self.ctx }
+
+
+	/// Gain multiplier applied to all members.
+	pub fn gain(&self) -> f32 { self.gain }
+	/// `alSourcef(AL_GAIN)`
+	/// Set the gain multiplier and reapply the gain of every member in a single deferred batch.
+	pub fn set_gain(&mut self, value: f32) -> AltoResult<()> {
+		if !value.is_finite() || value < 0.0 {
+			return Err(AltoError::InvalidValue);
+		}
+
+		self.gain = value;
+		self.apply()
+	}
+
+
+	/// Pitch multiplier applied to all members.
+	pub fn pitch(&self) -> f32 { self.pitch }
+	/// `alSourcef(AL_PITCH)`
+	/// Set the pitch multiplier and reapply the pitch of every member in a single deferred batch.
+	pub fn set_pitch(&mut self, value: f32) -> AltoResult<()> {
+		if !value.is_finite() || value <= 0.0 {
+			return Err(AltoError::InvalidValue);
+		}
+
+		self.pitch = value;
+		self.apply()
+	}
+
+
+	/// Add a source to this group, taking its current gain and pitch as its base values.
+	/// A source can only belong to one group at a time.
+	pub fn add<S: Source>(&mut self, src: &S) -> AltoResult<()> {
+		if *src.context() != self.ctx {
+			return Err(AltoError::WrongContext);
+		}
+
+		let mut mixes = self.ctx.0.mixes.lock();
+		match mixes.get(&src.as_raw()) {
+			Some(mix) if mix.group == self.id => return Ok(()),
+			Some(..) => return Err(AltoError::InvalidOperation),
+			None => (),
+		}
+
+		let _lock = self.ctx.make_current(true);
+		let (mut base_gain, mut base_pitch) = (0.0, 0.0);
+		unsafe {
+			self.ctx.0.dev.0.alto.0.api.alGetSourcef(src.as_raw(), sys::AL_GAIN, &mut base_gain);
+			self.ctx.0.dev.0.alto.0.api.alGetSourcef(src.as_raw(), sys::AL_PITCH, &mut base_pitch);
+			self.ctx.0.dev.0.alto.0.api.alSourcef(src.as_raw(), sys::AL_GAIN, base_gain * self.gain);
+			self.ctx.0.dev.0.alto.0.api.alSourcef(src.as_raw(), sys::AL_PITCH, base_pitch * self.pitch);
+		}
+		self.ctx.get_error()?;
+
+		mixes.insert(src.as_raw(), SourceMix{group: self.id, base_gain, base_pitch, gain: self.gain, pitch: self.pitch});
+		self.members.push(src.as_raw());
+		Ok(())
+	}
+
+
+	/// Remove a source from this group, restoring its base gain and pitch.
+	pub fn remove<S: Source>(&mut self, src: &S) -> AltoResult<()> {
+		if *src.context() != self.ctx {
+			return Err(AltoError::WrongContext);
+		}
+
+		self.members.retain(|&m| m != src.as_raw());
+		self.restore(&[src.as_raw()])
+	}
+
+
+	/// Number of live sources in this group.
+	pub fn len(&self) -> usize {
+		let mixes = self.ctx.0.mixes.lock();
+		self.members.iter().filter(|m| mixes.get(m).map(|mix| mix.group == self.id).unwrap_or(false)).count()
+	}
+	/// Whether this group has no live sources.
+	pub fn is_empty(&self) -> bool { self.len() == 0 }
+
+
+	fn apply(&mut self) -> AltoResult<()> {
+		let ctx = self.ctx.defer_updates();
+		let mut mixes = ctx.0.mixes.lock();
+		let _lock = ctx.make_current(true);
+
+		let (id, gain, pitch) = (self.id, self.gain, self.pitch);
+		self.members.retain(|m| match mixes.get_mut(m) {
+			Some(ref mut mix) if mix.group == id => {
+				mix.gain = gain;
+				mix.pitch = pitch;
+				unsafe {
+					ctx.0.dev.0.alto.0.api.alSourcef(*m, sys::AL_GAIN, mix.base_gain * gain);
+					ctx.0.dev.0.alto.0.api.alSourcef(*m, sys::AL_PITCH, mix.base_pitch * pitch);
+				}
+				true
+			},
+			_ => false,
+		});
+
+		ctx.get_prop_error()
+	}
+
+
+	fn restore(&self, members: &[sys::ALuint]) -> AltoResult<()> {
+		let mut mixes = self.ctx.0.mixes.lock();
+		let _lock = self.ctx.make_current(true);
+
+		for m in members {
+			if mixes.get(m).map(|mix| mix.group == self.id).unwrap_or(false) {
+				let mix = mixes.remove(m).unwrap();
+				unsafe {
+					self.ctx.0.dev.0.alto.0.api.alSourcef(*m, sys::AL_GAIN, mix.base_gain);
+					self.ctx.0.dev.0.alto.0.api.alSourcef(*m, sys::AL_PITCH, mix.base_pitch);
+				}
+			}
+		}
+
+		self.ctx.get_prop_error()
+	}
+}
+
+
+impl Drop for SourceGroup {
+	fn drop(&mut self) {
+		let _ = self.restore(&self.members);
+	}
+}
+
+
+impl DopplerTracker {
+	fn new(ctx: Context) -> DopplerTracker {
+		DopplerTracker{ctx, teleport_distance: f32::INFINITY, listener: None, sources: HashMap::new()}
+	}
+
+
+	/// The context whose listener and sources are being tracked.
+	pub fn context(&self) -> &Context { &self.ctx }
+
+
+	/// Distance moved in a single update beyond which the object is considered to have teleported.
+	/// Infinite by default.
+	pub fn teleport_distance(&self) -> f32 { self.teleport_distance }
+	/// Set the teleport distance. A teleporting object snaps to its new position with zero velocity
+	/// instead of producing a spike in Doppler shift.
+	pub fn set_teleport_distance(&mut self, value: f32) -> AltoResult<()> {
+		if value.is_nan() || value <= 0.0 {
+			return Err(AltoError::InvalidValue);
+		}
+
+		self.teleport_distance = value;
+		Ok(())
+	}
+
+
+	/// `alListenerfv(AL_POSITION)` and `alListenerfv(AL_VELOCITY)`
+	/// Set the listener position, along with a velocity derived from its previous position
+	/// and the elapsed time `dt` in seconds. The first update sets zero velocity.
+	pub fn update_listener<V: AsAlVec3>(&mut self, pos: V, dt: f32) -> AltoResult<()> {
+		let pos = pos.as_al_vec3();
+		let vel = self.velocity(self.listener, pos, dt)?;
+
+		let _lock = self.ctx.make_current(true);
+		unsafe {
+			self.ctx.0.dev.0.alto.0.api.alListenerfv(sys::AL_POSITION, &pos as *const [f32; 3] as *const sys::ALfloat);
+			self.ctx.0.dev.0.alto.0.api.alListenerfv(sys::AL_VELOCITY, &vel as *const [f32; 3] as *const sys::ALfloat);
+		}
+		self.ctx.get_prop_error()?;
+
+		self.listener = Some(pos);
+		Ok(())
+	}
+
+
+	/// `alSourcefv(AL_POSITION)` and `alSourcefv(AL_VELOCITY)`
+	/// Set the position of a source, along with a velocity derived from its previous position
+	/// and the elapsed time `dt` in seconds. The first update of each source sets zero velocity.
+	pub fn update_source<S: Source, V: AsAlVec3>(&mut self, src: &S, pos: V, dt: f32) -> AltoResult<()> {
+		if *src.context() != self.ctx {
+			return Err(AltoError::WrongContext);
+		}
+
+		let pos = pos.as_al_vec3();
+		let vel = self.velocity(self.sources.get(&src.as_raw()).cloned(), pos, dt)?;
+
+		let _lock = self.ctx.make_current(true);
+		unsafe {
+			self.ctx.0.dev.0.alto.0.api.alSourcefv(src.as_raw(), sys::AL_POSITION, &pos as *const [f32; 3] as *const sys::ALfloat);
+			self.ctx.0.dev.0.alto.0.api.alSourcefv(src.as_raw(), sys::AL_VELOCITY, &vel as *const [f32; 3] as *const sys::ALfloat);
+		}
+		self.ctx.get_prop_error()?;
+
+		self.sources.insert(src.as_raw(), pos);
+		Ok(())
+	}
+
+
+	/// Discard the position history of a source, so that its next update sets zero velocity.
+	/// This should be called when a source stops being tracked, since OpenAL may reuse its name.
+	pub fn forget_source<S: Source>(&mut self, src: &S) {
+		self.sources.remove(&src.as_raw());
+	}
+
+
+	fn velocity(&self, prev: Option<[f32; 3]>, pos: [f32; 3], dt: f32) -> AltoResult<[f32; 3]> {
+		derive_velocity(prev, pos, dt, self.teleport_distance)
+	}
+}
+
+
+/// Velocity from the previous position to the current one over `dt` seconds.
+/// Zero if there is no previous position or the distance moved exceeds `teleport_distance`.
+fn derive_velocity(prev: Option<[f32; 3]>, pos: [f32; 3], dt: f32, teleport_distance: f32) -> AltoResult<[f32; 3]> {
+	if !dt.is_finite() || dt <= 0.0 || pos.iter().any(|c| !c.is_finite()) {
+		return Err(AltoError::InvalidValue);
+	}
+
+	let prev = match prev {
+		Some(prev) => prev,
+		None => return Ok([0.0, 0.0, 0.0]),
+	};
+	let delta = sub3(pos, prev);
+	if dot3(delta, delta).sqrt() > teleport_distance {
+		return Ok([0.0, 0.0, 0.0]);
+	}
+
+	Ok(scale3(delta, 1.0 / dt))
+}
+
+
 impl Buffer {
 	pub(crate) fn new<F: SampleFrame, B: AsBufferData<F>>(ctx: Context, data: B, freq: i32) -> AltoResult<Buffer> {
 		let mut buf = 0;
@@ -784,40 +2530,182 @@ impl Buffer {
 	}
 
 
-	/// `alGetBufferi(AL_CHANNELS)`
-	pub fn channels(&self) -> sys::ALint {
-		let _lock = self.ctx.make_current(true);
-		let mut value = 0;
-		unsafe { self.ctx.0.dev.0.alto.0.api.alGetBufferi(self.buf, sys::AL_CHANNELS, &mut value); }
-		value
-	}
+	/// `alGetBufferi(AL_CHANNELS)`
+	pub fn channels(&self) -> sys::ALint {
+		let _lock = self.ctx.make_current(true);
+		let mut value = 0;
+		unsafe { self.ctx.0.dev.0.alto.0.api.alGetBufferi(self.buf, sys::AL_CHANNELS, &mut value); }
+		value
+	}
+
+
+	/// `alGetBufferi(AL_SIZE)`
+	pub fn size(&self) -> sys::ALint {
+		let _lock = self.ctx.make_current(true);
+		let mut value = 0;
+		unsafe { self.ctx.0.dev.0.alto.0.api.alGetBufferi(self.buf, sys::AL_SIZE, &mut value); }
+		value
+	}
+
+
+	/// `alGetBufferi(AL_SAMPLE_LENGTH_SOFT)`
+	/// Length of the buffer in sample frames. Uses `AL_SOFT_buffer_length_query` if present, which stays exact
+	/// for compressed formats such as ADPCM; otherwise the length is computed from `size`, `bits` and `channels`.
+	pub fn sample_length(&self) -> sys::ALint {
+		if let Ok(param) = self.ctx.0.exts.AL_SOFT_buffer_length_query().and_then(|asblq| asblq.AL_SAMPLE_LENGTH_SOFT) {
+			let _lock = self.ctx.make_current(true);
+			let mut value = 0;
+			unsafe { self.ctx.0.dev.0.alto.0.api.alGetBufferi(self.buf, param, &mut value); }
+			return value;
+		}
+
+		let frame_bits = self.bits() * self.channels();
+		if frame_bits > 0 { (self.size() as i64 * 8 / frame_bits as i64) as sys::ALint } else { 0 }
+	}
+
+
+	/// `alGetBufferf(AL_SEC_LENGTH_SOFT)`
+	/// Duration of the buffer in seconds. Uses `AL_SOFT_buffer_length_query` if present, and otherwise
+	/// divides `sample_length` by the frequency.
+	pub fn sec_length(&self) -> f32 {
+		if let Ok(param) = self.ctx.0.exts.AL_SOFT_buffer_length_query().and_then(|asblq| asblq.AL_SEC_LENGTH_SOFT) {
+			let _lock = self.ctx.make_current(true);
+			let mut value = 0.0;
+			unsafe { self.ctx.0.dev.0.alto.0.api.alGetBufferf(self.buf, param, &mut value); }
+			return value;
+		}
+
+		let freq = self.frequency();
+		if freq > 0 { self.sample_length() as f32 / freq as f32 } else { 0.0 }
+	}
+
+
+	/// `alGetBufferiv(AL_LOOP_POINTS_SOFT)`
+	/// Requires `AL_SOFT_loop_points`
+	pub fn soft_loop_points(&self) -> (sys::ALint, sys::ALint) {
+		let _lock = self.ctx.make_current(true);
+		(|| -> AltoResult<_> {
+			let mut value = [0, self.len];
+			unsafe { self.ctx.0.dev.0.alto.0.api.alGetBufferiv(self.buf, self.ctx.0.exts.AL_SOFT_loop_points()?.AL_LOOP_POINTS_SOFT?, &mut value as *mut [sys::ALint; 2] as *mut sys::ALint) }
+			Ok((value[0], value[1]))
+		})().unwrap_or((0, self.len))
+	}
+	/// `alBufferiv(AL_LOOP_POINTS_SOFT)`
+	/// Requires `AL_SOFT_loop_points`
+	/// Same as `set_loop_points`.
+	pub fn set_soft_loop_points(&self, value: (sys::ALint, sys::ALint)) -> AltoResult<()> {
+		self.set_loop_points(value.0, value.1)
+	}
+
+
+	/// `alGetBufferiv(AL_LOOP_POINTS_SOFT)`
+	/// Requires `AL_SOFT_loop_points`
+	/// The loop points as `(start, end)` sample frames. Unlike `soft_loop_points`, this fails with
+	/// `AltoError::ExtensionNotPresent` rather than reporting the whole buffer when the extension is missing.
+	pub fn loop_points(&self) -> AltoResult<(sys::ALint, sys::ALint)> {
+		let param = self.ctx.0.exts.AL_SOFT_loop_points()?.AL_LOOP_POINTS_SOFT?;
+		let _lock = self.ctx.make_current(true);
+		let mut value = [0, 0];
+		unsafe { self.ctx.0.dev.0.alto.0.api.alGetBufferiv(self.buf, param, &mut value as *mut [sys::ALint; 2] as *mut sys::ALint); }
+		self.ctx.get_error().map(|_| (value[0], value[1]))
+	}
+	/// `alBufferiv(AL_LOOP_POINTS_SOFT)`
+	/// Requires `AL_SOFT_loop_points`
+	/// Set the loop points to `start` and `end` sample frames. Fails with `AltoError::LoopPointsOutOfRange`
+	/// unless `0 <= start < end <= sample_length()`, and with `AltoError::BufferInUse` if the buffer is attached
+	/// to a source, since OpenAL refuses to change the loop points of a buffer that is in use.
+	pub fn set_loop_points(&self, start: sys::ALint, end: sys::ALint) -> AltoResult<()> {
+		let param = self.ctx.0.exts.AL_SOFT_loop_points()?.AL_LOOP_POINTS_SOFT?;
+		let len = self.sample_length();
+		if start < 0 || start >= end || end > len {
+			return Err(AltoError::LoopPointsOutOfRange{start, end, len});
+		}
+
+		let _lock = self.ctx.make_current(true);
+		unsafe { self.ctx.0.dev.0.alto.0.api.alBufferiv(self.buf, param, &[start, end] as *const [sys::ALint; 2] as *const sys::ALint); }
+		match self.ctx.get_error() {
+			Err(AltoError::InvalidOperation) => Err(AltoError::BufferInUse),
+			result => result,
+		}
+	}
+}
+
+
+impl CallbackBuffer {
+	fn new<F: SampleFrame, C: FnMut(&mut [F]) -> usize + Send + 'static>(ctx: Context, freq: i32, callback: C) -> AltoResult<CallbackBuffer> {
+		let abcs = ctx.0.exts.AL_SOFT_callback_buffer()?.alBufferCallbackSOFT?;
+		let format = F::format().into_raw(Some(&ctx))?;
+
+		let mut buf = 0;
+		{
+			let _lock = ctx.make_current(true);
+			unsafe { ctx.0.dev.0.alto.0.api.alGenBuffers(1, &mut buf as *mut sys::ALuint); }
+			ctx.get_error()?;
+		}
+		let buf = Buffer{ctx, buf, len: 0};
+
+		// The closure is boxed twice so that the mixer gets a thin pointer whose target never moves.
+		let mut callback: Box<BufferCallback<F>> = Box::new(Box::new(callback));
+		let user = &mut *callback as *mut BufferCallback<F> as *mut sys::ALvoid;
+		{
+			let _lock = buf.ctx.make_current(true);
+			unsafe { abcs(buf.buf, format, freq, Some(buffer_callback::<F>), user); }
+			buf.ctx.get_error()?;
+		}
+
+		Ok(CallbackBuffer{buf, callback, owner: Mutex::new(None)})
+	}
+
+
+	/// Whether this buffer is attached to a source that is still alive.
+	pub fn is_attached(&self) -> bool { self.owner.lock().as_ref().is_some_and(|owner| owner.src.upgrade().is_some()) }
+
+
+	fn release(&self) {
+		*self.owner.lock() = None;
+	}
+}
+
+
+impl Deref for CallbackBuffer {
+	type Target = Buffer;
 
+	fn deref(&self) -> &Buffer { &self.buf }
+}
 
-	/// `alGetBufferi(AL_SIZE)`
-	pub fn size(&self) -> sys::ALint {
-		let _lock = self.ctx.make_current(true);
-		let mut value = 0;
-		unsafe { self.ctx.0.dev.0.alto.0.api.alGetBufferi(self.buf, sys::AL_SIZE, &mut value); }
-		value
-	}
 
+// The callback is only ever invoked by the mixer, never through a shared reference.
+unsafe impl Sync for CallbackBuffer { }
 
-	/// `alGetBufferiv(AL_LOOP_POINTS_SOFT)`
-	/// Requires `AL_SOFT_loop_points`
-	pub fn soft_loop_points(&self) -> (sys::ALint, sys::ALint) {
-		let _lock = self.ctx.make_current(true);
-		(|| -> AltoResult<_> {
-			let mut value = [0, self.len];
-			unsafe { self.ctx.0.dev.0.alto.0.api.alGetBufferiv(self.buf, self.ctx.0.exts.AL_SOFT_loop_points()?.AL_LOOP_POINTS_SOFT?, &mut value as *mut [sys::ALint; 2] as *mut sys::ALint) }
-			Ok((value[0], value[1]))
-		})().unwrap_or((0, self.len))
-	}
-	/// `alBufferiv(AL_LOOP_POINTS_SOFT)`
-	/// Requires `AL_SOFT_loop_points`
-	pub fn set_soft_loop_points(&self, value: (sys::ALint, sys::ALint)) -> AltoResult<()> {
-		let _lock = self.ctx.make_current(true);
-		unsafe { self.ctx.0.dev.0.alto.0.api.alBufferiv(self.buf, self.ctx.0.exts.AL_SOFT_loop_points()?.AL_LOOP_POINTS_SOFT?, &[value.0, value.1] as *const [sys::ALint; 2] as *const sys::ALint); }
-		self.ctx.get_error()
+
+impl Drop for CallbackBuffer {
+	fn drop(&mut self) {
+		// A source whose wrapper did not delete it still has the buffer attached, and the mixer would keep
+		// calling into the closure after it is freed.
+		if let Some(owner) = self.owner.get_mut().take() {
+			let _lock = owner.ctx.make_current(true);
+			let api = &owner.ctx.0.dev.0.alto.0.api;
+			unsafe {
+				let mut value = 0;
+				if api.alIsSource(owner.id) != sys::AL_FALSE {
+					api.alGetSourcei(owner.id, sys::AL_BUFFER, &mut value);
+				}
+				if value as sys::ALuint == self.buf.buf {
+					api.alSourceStop(owner.id);
+					api.alSourcei(owner.id, sys::AL_BUFFER, 0);
+				}
+			}
+			let _ = owner.ctx.get_error();
+		}
+
+		self.buf.ctx.release_one_shots(self.buf.buf);
+		let _lock = self.buf.ctx.make_current(true);
+		unsafe { self.buf.ctx.0.dev.0.alto.0.api.alDeleteBuffers(1, &mut self.buf.buf as *mut sys::ALuint); }
+		if self.buf.ctx.get_error().is_err() {
+			// The buffer is still attached somewhere, so the mixer may call the closure again.
+			mem::forget(mem::replace(&mut self.callback, Box::new(())));
+		}
+		self.buf.buf = 0;
 	}
 }
 
@@ -828,6 +2716,7 @@ impl Drop for Buffer {
 			return; 
 		}
 
+		self.ctx.release_one_shots(self.buf);
 		let _lock = self.ctx.make_current(true);
 		unsafe { self.ctx.0.dev.0.alto.0.api.alDeleteBuffers(1, &mut self.buf as *mut sys::ALuint); }
 	}
@@ -843,18 +2732,85 @@ impl SourceInner {
 		let _lock = self.ctx.make_current(true);
 		let mut value = 0;
 		unsafe { self.ctx.0.dev.0.alto.0.api.alGetSourcei(self.src, sys::AL_SOURCE_STATE, &mut value); }
-		match value {
-			sys::AL_INITIAL => SourceState::Initial,
-			sys::AL_PLAYING => SourceState::Playing,
-			sys::AL_PAUSED => SourceState::Paused,
-			sys::AL_STOPPED => SourceState::Stopped,
-			ss => SourceState::Unknown(ss),
-		}
+		SourceState::from_raw(value)
+	}
+	fn source_type(&self) -> AltoResult<SourceType> {
+		let _lock = self.ctx.make_current(true);
+		let mut value = 0;
+		unsafe { self.ctx.0.dev.0.alto.0.api.alGetSourcei(self.src, sys::AL_SOURCE_TYPE, &mut value); }
+		self.ctx.get_error().map(|_| SourceType::from_raw(value))
 	}
 	fn play(&self) {
+		self.cancel_stop_fade();
 		let _lock = self.ctx.make_current(true);
 		unsafe { self.ctx.0.dev.0.alto.0.api.alSourcePlay(self.src); }
 	}
+	fn wait_until(&self, state: SourceState, timeout: Duration, poll: Duration) -> AltoResult<bool> {
+		if poll == Duration::from_secs(0) {
+			return Err(AltoError::InvalidValue);
+		}
+
+		let start = Instant::now();
+		let signal = self.ctx.events();
+		loop {
+			// Read the generation before the state, so a change in between is not slept through.
+			let generation = signal.map(|s| *s.generation.lock());
+			if self.state() == state {
+				return Ok(true);
+			}
+
+			let elapsed = start.elapsed();
+			if elapsed >= timeout {
+				return Ok(false);
+			}
+			let mut wait = cmp::min(poll, timeout - elapsed);
+
+			match signal {
+				Some(signal) => {
+					let mut g = signal.generation.lock();
+					if Some(*g) == generation {
+						signal.cond.wait_for(&mut g, wait);
+					}
+				},
+				None => {
+					if state == SourceState::Stopped {
+						if let Some(remaining) = self.remaining() {
+							wait = cmp::min(wait, remaining + Duration::from_millis(1));
+						}
+					}
+					thread::sleep(wait);
+				},
+			}
+		}
+	}
+	fn wait_until_stopped(&self, timeout: Duration) -> AltoResult<bool> {
+		self.wait_until(SourceState::Stopped, timeout, Duration::from_millis(10))
+	}
+	/// Time until the queued audio of a playing, non-looping source runs out, if it can be determined.
+	fn remaining(&self) -> Option<Duration> {
+		let mut looping = 0;
+		{
+			let _lock = self.ctx.make_current(true);
+			unsafe { self.ctx.0.dev.0.alto.0.api.alGetSourcei(self.src, sys::AL_LOOPING, &mut looping); }
+		}
+		if looping == sys::AL_TRUE as sys::ALint {
+			return None;
+		}
+		let pitch = self.pitch();
+		if pitch.is_nan() || pitch <= 0.0 {
+			return None;
+		}
+
+		let secs = (self.soft_sec_length().ok()? - self.sec_offset()).max(0.0) / pitch;
+		Some(Duration::from_millis((secs * 1000.0) as u64))
+	}
+	fn soft_play_at_time(&self, time: i64) -> AltoResult<()> {
+		let assd = self.ctx.0.exts.AL_SOFT_source_start_delay()?;
+		self.cancel_stop_fade();
+		let _lock = self.ctx.make_current(true);
+		unsafe { assd.alSourcePlayAtTimeSOFT?(self.src, time); }
+		self.ctx.get_error()
+	}
 	fn pause(&self) {
 		let _lock = self.ctx.make_current(true);
 		unsafe { self.ctx.0.dev.0.alto.0.api.alSourcePause(self.src); }
@@ -869,6 +2825,14 @@ impl SourceInner {
 	}
 
 
+	fn buffer_raw(&self) -> AltoResult<Option<sys::ALuint>> {
+		let _lock = self.ctx.make_current(true);
+		let mut value = 0;
+		unsafe { self.ctx.0.dev.0.alto.0.api.alGetSourcei(self.src, sys::AL_BUFFER, &mut value); }
+		self.ctx.get_error().map(|_| if value == 0 { None } else { Some(value as sys::ALuint) })
+	}
+
+
 	fn relative(&self) -> bool {
 		let _lock = self.ctx.make_current(true);
 		let mut value = 0;
@@ -882,15 +2846,109 @@ impl SourceInner {
 
 
 	fn gain(&self) -> f32 {
+		if let Some(gain) = *self.muted.lock() {
+			return gain;
+		}
+		self.al_gain()
+	}
+	fn al_gain(&self) -> f32 {
+		if let Some(mix) = self.ctx.0.mixes.lock().get(&self.src) {
+			return mix.base_gain;
+		}
+
 		let _lock = self.ctx.make_current(true);
 		let mut value = 0.0;
 		unsafe { self.ctx.0.dev.0.alto.0.api.alGetSourcef(self.src, sys::AL_GAIN, &mut value); }
 		value
 	}
 	fn set_gain(&self, value: f32) -> AltoResult<()> {
-		let _lock = self.ctx.make_current(true);
-		unsafe { self.ctx.0.dev.0.alto.0.api.alSourcef(self.src, sys::AL_GAIN, value); }
-		self.ctx.get_error()
+		if let Some(ref mut gain) = *self.muted.lock() {
+			if !value.is_finite() || value < 0.0 {
+				return Err(AltoError::InvalidValue);
+			}
+			*gain = value;
+			return Ok(());
+		}
+		self.ctx.set_source_gain(self.src, value)
+	}
+	fn gain_step(&self, target: f32, dt: f32, rate: f32) -> AltoResult<bool> {
+		if !target.is_finite() || target < 0.0 || !dt.is_finite() || dt < 0.0 || !rate.is_finite() || rate <= 0.0 {
+			return Err(AltoError::InvalidValue);
+		}
+
+		let gain = self.gain();
+		let step = rate * dt;
+		let value = if (target - gain).abs() <= step { target } else if target > gain { gain + step } else { gain - step };
+		if value != gain {
+			self.set_gain(value)?;
+		}
+		Ok(value == target)
+	}
+	fn stop_with_fade(&self, duration: Duration) -> AltoResult<()> {
+		let mut fades = self.ctx.0.fades.lock();
+		let restore = match fades.get(&self.src) {
+			Some(&Fade{restore: Some(restore), ..}) => restore,
+			_ => self.gain(),
+		};
+
+		if duration == Duration::from_secs(0) || self.state() != SourceState::Playing {
+			fades.remove(&self.src);
+			drop(fades);
+			self.stop();
+			return self.set_gain(restore);
+		}
+
+		let id = NEXT_FADE.fetch_add(1, Ordering::Relaxed);
+		fades.insert(self.src, Fade{id, from: self.gain(), to: 0.0, elapsed: Duration::from_secs(0), duration, restore: Some(restore)});
+		Ok(())
+	}
+	/// Cancel a pending `stop_with_fade`, restoring the gain the source had before it.
+	fn cancel_stop_fade(&self) {
+		let restore = {
+			let mut fades = self.ctx.0.fades.lock();
+			match fades.get(&self.src).and_then(|fade| fade.restore) {
+				Some(restore) => {
+					fades.remove(&self.src);
+					Some(restore)
+				},
+				None => None,
+			}
+		};
+		if let Some(restore) = restore {
+			let _ = self.set_gain(restore);
+		}
+	}
+	fn fade_gain_to(&self, target: f32, duration: Duration) -> AltoResult<FadeHandle> {
+		if !target.is_finite() || target < 0.0 {
+			return Err(AltoError::InvalidValue);
+		}
+
+		let from = self.gain();
+		let id = NEXT_FADE.fetch_add(1, Ordering::Relaxed);
+		if duration == Duration::from_secs(0) {
+			self.ctx.0.fades.lock().remove(&self.src);
+			self.set_gain(target)?;
+		} else {
+			self.ctx.0.fades.lock().insert(self.src, Fade{id, from, to: target, elapsed: Duration::from_secs(0), duration, restore: None});
+		}
+		Ok(FadeHandle{ctx: self.ctx.clone(), src: self.src, id, detached: false})
+	}
+	fn is_muted(&self) -> bool { self.muted.lock().is_some() }
+	fn set_muted(&self, value: bool) -> AltoResult<()> {
+		let mut muted = self.muted.lock();
+		match (value, *muted) {
+			(true, None) => {
+				let gain = self.al_gain();
+				self.ctx.set_source_gain(self.src, 0.0)?;
+				*muted = Some(gain);
+			},
+			(false, Some(gain)) => {
+				self.ctx.set_source_gain(self.src, gain)?;
+				*muted = None;
+			},
+			_ => (),
+		}
+		Ok(())
 	}
 
 
@@ -903,7 +2961,7 @@ impl SourceInner {
 	fn set_min_gain(&self, value: f32) -> AltoResult<()> {
 		let _lock = self.ctx.make_current(true);
 		unsafe { self.ctx.0.dev.0.alto.0.api.alSourcef(self.src, sys::AL_MIN_GAIN, value); }
-		self.ctx.get_error()
+		self.ctx.get_prop_error()
 	}
 
 
@@ -916,7 +2974,7 @@ impl SourceInner {
 	fn set_max_gain(&self, value: f32) -> AltoResult<()> {
 		let _lock = self.ctx.make_current(true);
 		unsafe { self.ctx.0.dev.0.alto.0.api.alSourcef(self.src, sys::AL_MAX_GAIN, value); }
-		self.ctx.get_error()
+		self.ctx.get_prop_error()
 	}
 
 
@@ -929,7 +2987,7 @@ impl SourceInner {
 	fn set_reference_distance(&self, value: f32) -> AltoResult<()> {
 		let _lock = self.ctx.make_current(true);
 		unsafe { self.ctx.0.dev.0.alto.0.api.alSourcef(self.src, sys::AL_REFERENCE_DISTANCE, value); }
-		self.ctx.get_error()
+		self.ctx.get_prop_error()
 	}
 
 
@@ -942,7 +3000,7 @@ impl SourceInner {
 	fn set_rolloff_factor(&self, value: f32) -> AltoResult<()> {
 		let _lock = self.ctx.make_current(true);
 		unsafe { self.ctx.0.dev.0.alto.0.api.alSourcef(self.src, sys::AL_ROLLOFF_FACTOR, value); }
-		self.ctx.get_error()
+		self.ctx.get_prop_error()
 	}
 
 
@@ -955,20 +3013,54 @@ impl SourceInner {
 	fn set_max_distance(&self, value: f32) -> AltoResult<()> {
 		let _lock = self.ctx.make_current(true);
 		unsafe { self.ctx.0.dev.0.alto.0.api.alSourcef(self.src, sys::AL_MAX_DISTANCE, value); }
+		self.ctx.get_prop_error()
+	}
+
+
+	fn attenuation(&self) -> Attenuation {
+		Attenuation::Custom{reference: self.reference_distance(), rolloff: self.rolloff_factor(), max: self.max_distance()}
+	}
+	fn set_attenuation(&self, value: Attenuation) -> AltoResult<()> {
+		let (reference, rolloff, max) = value.values();
+		if ![reference, rolloff, max].iter().all(|v| v.is_finite() && *v >= 0.0) || reference > max {
+			return Err(AltoError::InvalidValue);
+		}
+
+		let _defer = self.ctx.defer_updates();
+		let _lock = self.ctx.make_current(true);
+		let api = &self.ctx.0.dev.0.alto.0.api;
+		unsafe {
+			api.alSourcef(self.src, sys::AL_REFERENCE_DISTANCE, reference);
+			api.alSourcef(self.src, sys::AL_ROLLOFF_FACTOR, rolloff);
+			api.alSourcef(self.src, sys::AL_MAX_DISTANCE, max);
+		}
 		self.ctx.get_error()
 	}
 
 
 	fn pitch(&self) -> f32 {
+		if let Some(mix) = self.ctx.0.mixes.lock().get(&self.src) {
+			return mix.base_pitch;
+		}
+
 		let _lock = self.ctx.make_current(true);
 		let mut value = 0.0;
 		unsafe { self.ctx.0.dev.0.alto.0.api.alGetSourcef(self.src, sys::AL_PITCH, &mut value); }
 		value
 	}
 	fn set_pitch(&self, value: f32) -> AltoResult<()> {
+		let mut mixes = self.ctx.0.mixes.lock();
+		let mix = mixes.get_mut(&self.src);
+		let scale = mix.as_ref().map(|mix| mix.pitch).unwrap_or(1.0);
+
 		let _lock = self.ctx.make_current(true);
-		unsafe { self.ctx.0.dev.0.alto.0.api.alSourcef(self.src, sys::AL_PITCH, value); }
-		self.ctx.get_error()
+		unsafe { self.ctx.0.dev.0.alto.0.api.alSourcef(self.src, sys::AL_PITCH, value * scale); }
+		self.ctx.get_prop_error()?;
+
+		if let Some(mix) = mix {
+			mix.base_pitch = value;
+		}
+		Ok(())
 	}
 
 
@@ -978,11 +3070,11 @@ impl SourceInner {
 		unsafe { self.ctx.0.dev.0.alto.0.api.alGetSourcefv(self.src, sys::AL_POSITION, &mut value as *mut [f32; 3] as *mut sys::ALfloat); }
 		value.into()
 	}
-	fn set_position<V: Into<[f32; 3]>>(&self, value: V) -> AltoResult<()> {
+	fn set_position<V: AsAlVec3>(&self, value: V) -> AltoResult<()> {
 		let _lock = self.ctx.make_current(true);
-		let value = value.into();
+		let value = value.as_al_vec3();
 		unsafe { self.ctx.0.dev.0.alto.0.api.alSourcefv(self.src, sys::AL_POSITION, &value as *const [f32; 3] as *const sys::ALfloat); }
-		self.ctx.get_error()
+		self.ctx.get_prop_error()
 	}
 
 
@@ -992,11 +3084,55 @@ impl SourceInner {
 		unsafe { self.ctx.0.dev.0.alto.0.api.alGetSourcefv(self.src, sys::AL_VELOCITY, &mut value as *mut [f32; 3] as *mut sys::ALfloat); }
 		value.into()
 	}
-	fn set_velocity<V: Into<[f32; 3]>>(&self, value: V) -> AltoResult<()> {
+	fn set_velocity<V: AsAlVec3>(&self, value: V) -> AltoResult<()> {
 		let _lock = self.ctx.make_current(true);
-		let value = value.into();
+		let value = value.as_al_vec3();
 		unsafe { self.ctx.0.dev.0.alto.0.api.alSourcefv(self.src, sys::AL_VELOCITY, &value as *const [f32; 3] as *const sys::ALfloat); }
-		self.ctx.get_error()
+		self.ctx.get_prop_error()
+	}
+	fn update_position_with_velocity<V: AsAlVec3>(&self, pos: V, dt: f32) -> AltoResult<()> {
+		let pos = pos.as_al_vec3();
+		let mut last_pos = self.last_pos.lock();
+		let vel = derive_velocity(*last_pos, pos, dt, f32::INFINITY)?;
+
+		let _lock = self.ctx.make_current(true);
+		unsafe {
+			self.ctx.0.dev.0.alto.0.api.alSourcefv(self.src, sys::AL_POSITION, &pos as *const [f32; 3] as *const sys::ALfloat);
+			self.ctx.0.dev.0.alto.0.api.alSourcefv(self.src, sys::AL_VELOCITY, &vel as *const [f32; 3] as *const sys::ALfloat);
+		}
+		self.ctx.get_prop_error()?;
+
+		*last_pos = Some(pos);
+		Ok(())
+	}
+
+
+	fn set_head_locked<V: AsAlVec3>(&self, offset: V) -> AltoResult<()> {
+		let _defer = self.ctx.defer_updates();
+		self.set_relative(true);
+		self.set_position(offset)?;
+		self.set_velocity([0.0, 0.0, 0.0])
+	}
+	fn detach_to_world<V: AsAlVec3>(&self, listener_pos: V, listener_orientation: (V, V)) -> AltoResult<()> {
+		let (at, up) = (listener_orientation.0.as_al_vec3(), listener_orientation.1.as_al_vec3());
+		check_orientation(at, up)?;
+		if !self.relative() {
+			return Ok(());
+		}
+
+		// The same basis OpenAL uses to transform world space into the listener's frame.
+		let n = normalize3(at);
+		let v = normalize3(up);
+		let u = normalize3(cross3(n, v));
+		let to_world = |p: [f32; 3]| add3(add3(scale3(u, p[0]), scale3(v, p[1])), scale3(n, -p[2]));
+
+		let pos = add3(listener_pos.as_al_vec3(), to_world(self.position()));
+		let vel = add3(self.ctx.velocity(), to_world(self.velocity()));
+
+		let _defer = self.ctx.defer_updates();
+		self.set_relative(false);
+		self.set_position(pos)?;
+		self.set_velocity(vel)
 	}
 
 
@@ -1006,11 +3142,11 @@ impl SourceInner {
 		unsafe { self.ctx.0.dev.0.alto.0.api.alGetSourcefv(self.src, sys::AL_DIRECTION, &mut value as *mut [f32; 3] as *mut sys::ALfloat); }
 		value.into()
 	}
-	fn set_direction<V: Into<[f32; 3]>>(&self, value: V) -> AltoResult<()> {
+	fn set_direction<V: AsAlVec3>(&self, value: V) -> AltoResult<()> {
 		let _lock = self.ctx.make_current(true);
-		let value = value.into();
+		let value = value.as_al_vec3();
 		unsafe { self.ctx.0.dev.0.alto.0.api.alSourcefv(self.src, sys::AL_DIRECTION, &value as *const [f32; 3] as *const sys::ALfloat); }
-		self.ctx.get_error()
+		self.ctx.get_prop_error()
 	}
 
 
@@ -1023,7 +3159,7 @@ impl SourceInner {
 	fn set_cone_inner_angle(&self, value: f32) -> AltoResult<()> {
 		let _lock = self.ctx.make_current(true);
 		unsafe { self.ctx.0.dev.0.alto.0.api.alSourcef(self.src, sys::AL_CONE_INNER_ANGLE, value); }
-		self.ctx.get_error()
+		self.ctx.get_prop_error()
 	}
 
 
@@ -1036,7 +3172,7 @@ impl SourceInner {
 	fn set_cone_outer_angle(&self, value: f32) -> AltoResult<()> {
 		let _lock = self.ctx.make_current(true);
 		unsafe { self.ctx.0.dev.0.alto.0.api.alSourcef(self.src, sys::AL_CONE_OUTER_ANGLE, value); }
-		self.ctx.get_error()
+		self.ctx.get_prop_error()
 	}
 
 
@@ -1049,7 +3185,21 @@ impl SourceInner {
 	fn set_cone_outer_gain(&self, value: f32) -> AltoResult<()> {
 		let _lock = self.ctx.make_current(true);
 		unsafe { self.ctx.0.dev.0.alto.0.api.alSourcef(self.src, sys::AL_CONE_OUTER_GAIN, value); }
-		self.ctx.get_error()
+		self.ctx.get_prop_error()
+	}
+	fn cone(&self) -> (Angle, Angle, f32) {
+		(Angle::degrees(self.cone_inner_angle()), Angle::degrees(self.cone_outer_angle()), self.cone_outer_gain())
+	}
+	fn set_cone(&self, inner: Angle, outer: Angle, outer_gain: f32) -> AltoResult<()> {
+		let (inner, outer) = (inner.to_degrees(), outer.to_degrees());
+		if !(0.0..=360.0).contains(&inner) || !(0.0..=360.0).contains(&outer) || inner > outer || !(0.0..=1.0).contains(&outer_gain) {
+			return Err(AltoError::InvalidValue);
+		}
+
+		let _defer = self.ctx.defer_updates();
+		self.set_cone_inner_angle(inner)?;
+		self.set_cone_outer_angle(outer)?;
+		self.set_cone_outer_gain(outer_gain)
 	}
 
 
@@ -1092,6 +3242,212 @@ impl SourceInner {
 	}
 
 
+	fn static_len(&self) -> AltoResult<(sys::ALint, sys::ALint, sys::ALint)> {
+		let _lock = self.ctx.make_current(true);
+		let api = &self.ctx.0.dev.0.alto.0.api;
+		let mut buf = 0;
+		unsafe { api.alGetSourcei(self.src, sys::AL_BUFFER, &mut buf); }
+		self.ctx.get_error()?;
+		if buf == 0 {
+			return Ok((0, 0, 0));
+		}
+
+		let (mut size, mut bits, mut channels, mut freq) = (0, 0, 0, 0);
+		unsafe {
+			api.alGetBufferi(buf as sys::ALuint, sys::AL_SIZE, &mut size);
+			api.alGetBufferi(buf as sys::ALuint, sys::AL_BITS, &mut bits);
+			api.alGetBufferi(buf as sys::ALuint, sys::AL_CHANNELS, &mut channels);
+			api.alGetBufferi(buf as sys::ALuint, sys::AL_FREQUENCY, &mut freq);
+		}
+		self.ctx.get_error()?;
+		let frame = bits * channels / 8;
+		Ok((size, if frame > 0 { size / frame } else { 0 }, freq))
+	}
+
+
+	fn seek(&self, pos: SeekPos, bytes: sys::ALint, samples: sys::ALint, freq: sys::ALint) -> AltoResult<()> {
+		let (in_range, limit) = match pos {
+			SeekPos::Bytes(value) => (value >= 0 && value < bytes, SeekPos::Bytes(bytes)),
+			SeekPos::Samples(value) => (value >= 0 && value < samples, SeekPos::Samples(samples)),
+			SeekPos::Seconds(value) => {
+				if value.is_nan() {
+					return Err(AltoError::InvalidValue);
+				}
+				let secs = if freq > 0 { samples as f32 / freq as f32 } else { 0.0 };
+				(value >= 0.0 && value < secs, SeekPos::Seconds(secs))
+			},
+		};
+		if !in_range {
+			return Err(AltoError::SeekOutOfRange(limit));
+		}
+
+		let _lock = self.ctx.make_current(true);
+		let api = &self.ctx.0.dev.0.alto.0.api;
+		let mut state = 0;
+		unsafe { api.alGetSourcei(self.src, sys::AL_SOURCE_STATE, &mut state); }
+		if state == sys::AL_STOPPED {
+			unsafe { api.alSourceRewind(self.src); }
+		}
+		match pos {
+			SeekPos::Bytes(value) => unsafe { api.alSourcei(self.src, sys::AL_BYTE_OFFSET, value) },
+			SeekPos::Samples(value) => unsafe { api.alSourcei(self.src, sys::AL_SAMPLE_OFFSET, value) },
+			SeekPos::Seconds(value) => unsafe { api.alSourcef(self.src, sys::AL_SEC_OFFSET, value) },
+		}
+		self.ctx.get_error()
+	}
+	fn tell(&self) -> SeekPos { SeekPos::Samples(self.sample_offset()) }
+	fn playback_position(&self) -> AltoResult<Duration> {
+		let offset = self.frame_offset()?;
+		let freq = {
+			let _lock = self.ctx.make_current(true);
+			let api = &self.ctx.0.dev.0.alto.0.api;
+			let (mut buf, mut freq) = (0, 0);
+			unsafe {
+				api.alGetSourcei(self.src, sys::AL_BUFFER, &mut buf);
+				if buf != 0 {
+					api.alGetBufferi(buf as sys::ALuint, sys::AL_FREQUENCY, &mut freq);
+				}
+			}
+			self.ctx.get_error()?;
+			freq
+		};
+		Ok(frames_duration(offset, freq))
+	}
+
+
+	/// The play cursor in sample frames, from the whole start of the queue for a streaming source.
+	fn frame_offset(&self) -> AltoResult<i64> {
+		match self.soft_sample_frac_offset_latency() {
+			Ok((offset, _)) => Ok(offset),
+			Err(AltoError::ExtensionNotPresent) => {
+				let _lock = self.ctx.make_current(true);
+				let mut value = 0;
+				unsafe { self.ctx.0.dev.0.alto.0.api.alGetSourcei(self.src, sys::AL_SAMPLE_OFFSET, &mut value); }
+				self.ctx.get_error().map(|_| value as i64)
+			},
+			Err(e) => Err(e),
+		}
+	}
+
+
+	/// Stop the source and release its buffer or queue, returning it to the undetermined type.
+	fn detach_buffers(&self) -> AltoResult<()> {
+		self.stop();
+		{
+			let _lock = self.ctx.make_current(true);
+			unsafe { self.ctx.0.dev.0.alto.0.api.alSourcei(self.src, sys::AL_BUFFER, 0); }
+			self.ctx.get_error()?;
+		}
+		self.expect_type(SourceType::Undetermined)
+	}
+
+
+	/// Fail with `AltoError::SourceTypeMismatch` unless OpenAL reports the source as `ty`.
+	fn expect_type(&self, ty: SourceType) -> AltoResult<()> {
+		match self.source_type()? {
+			found if found == ty => Ok(()),
+			found => Err(AltoError::SourceTypeMismatch(found)),
+		}
+	}
+
+
+	fn snapshot(&self) -> AltoResult<SourceSnapshot> {
+		let looping = {
+			let _lock = self.ctx.make_current(true);
+			let mut value = 0;
+			unsafe { self.ctx.0.dev.0.alto.0.api.alGetSourcei(self.src, sys::AL_LOOPING, &mut value); }
+			self.ctx.get_error()?;
+			value == sys::AL_TRUE as sys::ALint
+		};
+		let efx = self.ctx.0.dev.0.exts.ALC_EXT_EFX().is_ok();
+
+		Ok(SourceSnapshot{
+			relative: self.relative(),
+			looping,
+			gain: self.gain(),
+			min_gain: self.min_gain(),
+			max_gain: self.max_gain(),
+			reference_distance: self.reference_distance(),
+			rolloff_factor: self.rolloff_factor(),
+			max_distance: self.max_distance(),
+			pitch: self.pitch(),
+			position: self.position(),
+			velocity: self.velocity(),
+			direction: self.direction(),
+			cone_inner_angle: self.cone_inner_angle(),
+			cone_outer_angle: self.cone_outer_angle(),
+			cone_outer_gain: self.cone_outer_gain(),
+			sample_offset: self.sample_offset(),
+			distance_model: self.ctx.0.exts.AL_EXT_source_distance_model().ok().map(|_| self.distance_model()),
+			soft_direct_channels_mode: optional(self.soft_direct_channels_mode())?,
+			soft_spatialization: self.ctx.0.exts.AL_SOFT_source_spatialize().ok().map(|_| self.soft_spatialization()),
+			soft_resampler: optional(self.soft_resampler())?,
+			stereo_angles: optional(self.stereo_angles())?,
+			radius: self.ctx.0.exts.AL_EXT_SOURCE_RADIUS().ok().map(|_| self.radius()),
+			air_absorption_factor: if efx { Some(self.air_absorption_factor()) } else { None },
+			room_rolloff_factor: if efx { Some(self.room_rolloff_factor()) } else { None },
+			cone_outer_gainhf: if efx { Some(self.cone_outer_gainhf()) } else { None },
+			direct_filter_gainhf_auto: if efx { Some(self.direct_filter_gainhf_auto()) } else { None },
+		})
+	}
+	fn apply_snapshot(&self, value: &SourceSnapshot) -> AltoResult<()> {
+		let _defer = self.ctx.defer_updates();
+
+		self.set_relative(value.relative);
+		{
+			let _lock = self.ctx.make_current(true);
+			unsafe { self.ctx.0.dev.0.alto.0.api.alSourcei(self.src, sys::AL_LOOPING, if value.looping { sys::AL_TRUE } else { sys::AL_FALSE } as sys::ALint); }
+			self.ctx.get_error()?;
+		}
+		self.set_gain(value.gain)?;
+		self.set_min_gain(value.min_gain)?;
+		self.set_max_gain(value.max_gain)?;
+		self.set_reference_distance(value.reference_distance)?;
+		self.set_rolloff_factor(value.rolloff_factor)?;
+		self.set_max_distance(value.max_distance)?;
+		self.set_pitch(value.pitch)?;
+		self.set_position(value.position)?;
+		self.set_velocity(value.velocity)?;
+		self.set_direction(value.direction)?;
+		self.set_cone_inner_angle(value.cone_inner_angle)?;
+		self.set_cone_outer_angle(value.cone_outer_angle)?;
+		self.set_cone_outer_gain(value.cone_outer_gain)?;
+
+		if let Some(dm) = value.distance_model {
+			optional(self.set_distance_model(dm))?;
+		}
+		if let Some(mode) = value.soft_direct_channels_mode {
+			optional(self.set_soft_direct_channels_mode(mode))?;
+		}
+		if let Some(ssp) = value.soft_spatialization {
+			optional(self.set_soft_spatialization(ssp))?;
+		}
+		if let Some(resampler) = value.soft_resampler {
+			optional(self.set_soft_resampler(resampler))?;
+		}
+		if let Some(angles) = value.stereo_angles {
+			optional(self.set_stereo_angles(angles))?;
+		}
+		if let Some(radius) = value.radius {
+			optional(self.set_radius(radius))?;
+		}
+		if let Some(factor) = value.air_absorption_factor {
+			optional(self.set_air_absorption_factor(factor))?;
+		}
+		if let Some(factor) = value.room_rolloff_factor {
+			optional(self.set_room_rolloff_factor(factor))?;
+		}
+		if let Some(gainhf) = value.cone_outer_gainhf {
+			optional(self.set_cone_outer_gainhf(gainhf))?;
+		}
+		if let Some(auto) = value.direct_filter_gainhf_auto {
+			optional(self.set_direct_filter_gainhf_auto(auto))?;
+		}
+
+		self.set_sample_offset(value.sample_offset)
+	}
+
+
 	fn soft_sec_offset_latency(&self) -> AltoResult<(f64, f64)> {
 		let assl = self.ctx.0.exts.AL_SOFT_source_latency()?;
 		let _lock = self.ctx.make_current(true);
@@ -1101,12 +3457,31 @@ impl SourceInner {
 	}
 
 
-	fn soft_sample_offset_frac_latency(&self) -> AltoResult<(i32, i32, i64)> {
+	fn soft_vec_f64<V: From<[f64; 3]>>(&self, param: sys::ALenum) -> AltoResult<V> {
+		let assl = self.ctx.0.exts.AL_SOFT_source_latency()?;
+		let _lock = self.ctx.make_current(true);
+		let mut value = [0.0, 0.0, 0.0];
+		unsafe { assl.alGetSourcedvSOFT?(self.src, param, &mut value as *mut [f64; 3] as *mut f64); }
+		self.ctx.get_error().map(|_| value.into())
+	}
+	fn set_soft_vec_f64<V: Into<[f64; 3]>>(&self, param: sys::ALenum, value: V) -> AltoResult<()> {
+		let assl = self.ctx.0.exts.AL_SOFT_source_latency()?;
+		let _lock = self.ctx.make_current(true);
+		let value = value.into();
+		unsafe { assl.alSourcedvSOFT?(self.src, param, &value as *const [f64; 3] as *const f64); }
+		self.ctx.get_prop_error()
+	}
+
+
+	fn soft_sample_frac_offset_latency(&self) -> AltoResult<(i64, Duration)> {
+		self.soft_sample_fixed_offset_latency().map(|(offset, latency)| (offset >> 32, latency))
+	}
+	fn soft_sample_fixed_offset_latency(&self) -> AltoResult<(i64, Duration)> {
 		let assl = self.ctx.0.exts.AL_SOFT_source_latency()?;
 		let _lock = self.ctx.make_current(true);
 		let mut value = [0, 0];
 		unsafe { assl.alGetSourcei64vSOFT?(self.src, assl.AL_SAMPLE_OFFSET_LATENCY_SOFT?, &mut value as *mut [i64; 2] as *mut i64); }
-		self.ctx.get_error().map(|_| ((value[0] >> 32) as i32, value[0] as i32, value[1]))
+		self.ctx.get_error().map(|_| (value[0], Duration::from_nanos(value[1].max(0) as u64)))
 	}
 
 
@@ -1123,6 +3498,37 @@ impl SourceInner {
 		unsafe { self.ctx.0.dev.0.alto.0.api.alSourcei(self.src, self.ctx.0.exts.AL_SOFT_direct_channels()?.AL_DIRECT_CHANNELS_SOFT?, if value { sys::AL_TRUE } else { sys::AL_FALSE } as sys::ALint); }
 		self.ctx.get_error()
 	}
+	fn soft_direct_channels_mode(&self) -> AltoResult<SoftDirectChannelsMode> {
+		let adc = self.ctx.0.exts.AL_SOFT_direct_channels()?.AL_DIRECT_CHANNELS_SOFT?;
+		let _lock = self.ctx.make_current(true);
+		let mut value = 0;
+		unsafe { self.ctx.0.dev.0.alto.0.api.alGetSourcei(self.src, adc, &mut value); }
+		self.ctx.get_error()?;
+
+		if value == sys::AL_FALSE as sys::ALint {
+			return Ok(SoftDirectChannelsMode::Off);
+		}
+		match self.ctx.0.exts.AL_SOFT_direct_channels_remix() {
+			Ok(adcr) => match value {
+				v if v == adcr.AL_DROP_UNMATCHED_SOFT? => Ok(SoftDirectChannelsMode::DropUnmatched),
+				v if v == adcr.AL_REMIX_UNMATCHED_SOFT? => Ok(SoftDirectChannelsMode::RemixUnmatched),
+				_ => Err(AltoError::InvalidEnum),
+			},
+			Err(_) => Ok(SoftDirectChannelsMode::DropUnmatched),
+		}
+	}
+	fn set_soft_direct_channels_mode(&self, value: SoftDirectChannelsMode) -> AltoResult<()> {
+		let adc = self.ctx.0.exts.AL_SOFT_direct_channels()?.AL_DIRECT_CHANNELS_SOFT?;
+		let value = match (value, self.ctx.0.exts.AL_SOFT_direct_channels_remix()) {
+			(SoftDirectChannelsMode::Off, _) => sys::AL_FALSE as sys::ALint,
+			(SoftDirectChannelsMode::DropUnmatched, Ok(adcr)) => adcr.AL_DROP_UNMATCHED_SOFT?,
+			(SoftDirectChannelsMode::DropUnmatched, Err(_)) => sys::AL_TRUE as sys::ALint,
+			(SoftDirectChannelsMode::RemixUnmatched, adcr) => adcr?.AL_REMIX_UNMATCHED_SOFT?,
+		};
+		let _lock = self.ctx.make_current(true);
+		unsafe { self.ctx.0.dev.0.alto.0.api.alSourcei(self.src, adc, value); }
+		self.ctx.get_error()
+	}
 
 
 	fn soft_sec_length(&self) -> AltoResult<f32> {
@@ -1238,16 +3644,62 @@ impl SourceInner {
 		let _lock = self.ctx.make_current(true);
 		let mut value = [0.0, 0.0];
 		unsafe { self.ctx.0.dev.0.alto.0.api.alGetSourcefv(self.src, aesa.AL_STEREO_ANGLES?, &mut value as *mut [f32; 2] as *mut f32); }
-		Ok(value.into())
+		self.ctx.get_error().map(|_| value.into())
 	}
 	fn set_stereo_angles<V: Into<[f32; 2]>>(&self, value: V) -> AltoResult<()> {
 		let aesa = self.ctx.0.exts.AL_EXT_STEREO_ANGLES()?;
+		let value = value.into();
+		if !value[0].is_finite() || !value[1].is_finite() {
+			return Err(AltoError::InvalidValue);
+		}
+
 		let _lock = self.ctx.make_current(true);
 		unsafe {
-			let value = value.into();
 			self.ctx.0.dev.0.alto.0.api.alSourcefv(self.src, aesa.AL_STEREO_ANGLES?, &value as *const [f32; 2] as *const f32);
 		}
-		self.ctx.get_error()
+		self.ctx.get_error()
+	}
+
+
+	fn pans_by_angles(&self) -> AltoResult<bool> {
+		if self.ctx.0.exts.AL_EXT_STEREO_ANGLES().is_err() {
+			return Ok(false);
+		}
+		let buf = match self.buffer_raw()? {
+			Some(buf) => buf,
+			None => return Ok(false),
+		};
+
+		let _lock = self.ctx.make_current(true);
+		let mut value = 0;
+		unsafe { self.ctx.0.dev.0.alto.0.api.alGetBufferi(buf, sys::AL_CHANNELS, &mut value); }
+		self.ctx.get_error().map(|_| value == 2)
+	}
+	fn pan(&self) -> AltoResult<f32> {
+		let center = if self.pans_by_angles()? {
+			let angles: [f32; 2] = self.stereo_angles()?;
+			-(angles[0] + angles[1]) / 2.0
+		} else {
+			let pos: [f32; 3] = self.position();
+			pos[0].atan2(-pos[2])
+		};
+		Ok((center / FRAC_PI_2).clamp(-1.0, 1.0))
+	}
+	fn set_pan(&self, value: f32) -> AltoResult<()> {
+		if value.is_nan() {
+			return Err(AltoError::InvalidValue);
+		}
+
+		let value = value.clamp(-1.0, 1.0);
+		let angle = value * FRAC_PI_2;
+		if self.pans_by_angles()? {
+			let spread = FRAC_PI_6 * (1.0 - value.abs());
+			self.set_stereo_angles([spread - angle, -spread - angle])
+		} else {
+			let _defer = self.ctx.defer_updates();
+			self.set_relative(true);
+			self.set_position([angle.sin(), 0.0, -angle.cos()])
+		}
 	}
 
 
@@ -1256,17 +3708,18 @@ impl SourceInner {
 			let aesr = self.ctx.0.exts.AL_EXT_SOURCE_RADIUS()?;
 			let _lock = self.ctx.make_current(true);
 			let mut value = 0.0;
-			unsafe { self.ctx.0.dev.0.alto.0.api.alGetSourcefv(self.src, aesr.AL_SOURCE_RADIUS?, &mut value); }
-			Ok(value.into())
+			unsafe { self.ctx.0.dev.0.alto.0.api.alGetSourcef(self.src, aesr.AL_SOURCE_RADIUS?, &mut value); }
+			self.ctx.get_error().map(|_| value)
 		})().unwrap_or(0.0)
 	}
 	fn set_radius(&self, value: f32) -> AltoResult<()> {
 		let aesr = self.ctx.0.exts.AL_EXT_SOURCE_RADIUS()?;
-		let _lock = self.ctx.make_current(true);
-		unsafe {
-			let value = value.into();
-			self.ctx.0.dev.0.alto.0.api.alSourcefv(self.src, aesr.AL_SOURCE_RADIUS?, &value);
+		if !value.is_finite() || value < 0.0 {
+			return Err(AltoError::InvalidValue);
 		}
+
+		let _lock = self.ctx.make_current(true);
+		unsafe { self.ctx.0.dev.0.alto.0.api.alSourcef(self.src, aesr.AL_SOURCE_RADIUS?, value); }
 		self.ctx.get_error()
 	}
 
@@ -1291,6 +3744,16 @@ impl SourceInner {
 	}
 
 
+	fn max_auxiliary_sends(&self) -> AltoResult<sys::ALint> {
+		self.ctx.max_aux_sends()
+	}
+	fn check_send(&self, send: sys::ALint) -> AltoResult<()> {
+		let max = self.max_auxiliary_sends()?;
+		if send < 0 || send >= max {
+			return Err(AltoError::SendOutOfRange{index: send, max});
+		}
+		Ok(())
+	}
 	fn set_aux_send(arc_self: &Arc<SourceInner>, send: sys::ALint, slot: &mut AuxEffectSlot) -> AltoResult<()> {
 		SourceInner::set_aux_send_impl(arc_self, send, slot, 0)
 	}
@@ -1303,7 +3766,8 @@ impl SourceInner {
 	}
 	fn set_aux_send_impl(arc_self: &Arc<SourceInner>, send: sys::ALint, slot: &mut AuxEffectSlot, filter: sys::ALuint) -> AltoResult<()> {
 		let efx = arc_self.ctx.0.dev.0.exts.ALC_EXT_EFX()?;
-		if send >= arc_self.ctx.0.dev.max_aux_sends() || *slot.context() != arc_self.ctx {
+		arc_self.check_send(send)?;
+		if *slot.context() != arc_self.ctx {
 			return Err(AltoError::WrongContext);
 		}
 
@@ -1318,9 +3782,7 @@ impl SourceInner {
 	fn clear_aux_send(&self, send: sys::ALint) {
 		let _ = (|| -> AltoResult<_> {
 			let efx = self.ctx.0.dev.0.exts.ALC_EXT_EFX()?;
-			if send >= self.ctx.0.dev.max_aux_sends() {
-				return Err(AltoError::InvalidValue);
-			}
+			self.check_send(send)?;
 
 			let _lock = self.ctx.make_current(true);
 			let mut sends = self.sends.lock();
@@ -1354,7 +3816,7 @@ impl SourceInner {
 		let efx = self.ctx.0.dev.0.exts.ALC_EXT_EFX()?;
 		let _lock = self.ctx.make_current(true);
 		unsafe { self.ctx.0.dev.0.alto.0.api.alSourcef(self.src, efx.AL_AIR_ABSORPTION_FACTOR?, value); }
-		self.ctx.get_error()
+		self.ctx.get_prop_error()
 	}
 
 
@@ -1371,7 +3833,7 @@ impl SourceInner {
 		let efx = self.ctx.0.dev.0.exts.ALC_EXT_EFX()?;
 		let _lock = self.ctx.make_current(true);
 		unsafe { self.ctx.0.dev.0.alto.0.api.alSourcef(self.src, efx.AL_ROOM_ROLLOFF_FACTOR?, value); }
-		self.ctx.get_error()
+		self.ctx.get_prop_error()
 	}
 
 
@@ -1388,7 +3850,7 @@ impl SourceInner {
 		let efx = self.ctx.0.dev.0.exts.ALC_EXT_EFX()?;
 		let _lock = self.ctx.make_current(true);
 		unsafe { self.ctx.0.dev.0.alto.0.api.alSourcef(self.src, efx.AL_CONE_OUTER_GAINHF?, value); }
-		self.ctx.get_error()
+		self.ctx.get_prop_error()
 	}
 
 
@@ -1428,6 +3890,21 @@ impl Hash for SourceInner {
 
 impl Drop for SourceInner {
 	fn drop(&mut self) {
+		self.ctx.0.mixes.lock().remove(&self.src);
+		self.ctx.0.fades.lock().remove(&self.src);
+		// The handler may own alto objects, so it is dropped after the table is unlocked.
+		let _handler = self.ctx.0.events.buffer_handlers.lock().remove(&self.src);
+		// Held until the source is deleted, so `stop_all_sources` never sees a deleted name.
+		let mut sources = self.ctx.0.sources.lock();
+		if let Some(count) = sources.get_mut(&self.src) {
+			*count -= 1;
+		}
+		if sources.get(&self.src) == Some(&0) {
+			sources.remove(&self.src);
+		}
+		if !self.owned {
+			return;
+		}
 		let _lock = self.ctx.make_current(true);
 		unsafe { self.ctx.0.dev.0.alto.0.api.alDeleteSources(1, &mut self.src as *mut sys::ALuint); }
 	}
@@ -1442,26 +3919,55 @@ impl StaticSource {
 			unsafe { ctx.0.dev.0.alto.0.api.alGenSources(1, &mut src as *mut sys::ALuint); }
 			ctx.get_error()?;
 		}
+		Ok(StaticSource::from_raw(ctx, src, true))
+	}
+	fn from_raw(ctx: Context, src: sys::ALuint, owned: bool) -> StaticSource {
+		*ctx.0.sources.lock().entry(src).or_insert(0) += 1;
 		let sends = iter::repeat(0).take(ctx.0.dev.0.max_aux_sends() as usize).collect();
-		Ok(StaticSource{src: Arc::new(SourceInner{ctx: ctx, src: src, sends: Mutex::new(sends)}), buf: None})
+		StaticSource{src: Arc::new(SourceInner{ctx: ctx, src: src, sends: Mutex::new(sends), last_pos: Mutex::new(None), muted: Mutex::new(None), owned}), buf: None, callback: None}
 	}
 
 
+	/// The buffer attached with `set_buffer`. The source holds a reference to it until
+	/// the buffer is replaced or cleared, so it cannot be deleted while attached.
 	pub fn buffer(&self) -> Option<&Arc<Buffer>> { self.buf.as_ref() }
 
 
 	/// `alSourcei(AL_BUFFER)`
+	/// Fails with `AltoError::SourcePlaying` if the source is playing or paused, since OpenAL only allows
+	/// the buffer of a stopped or initial source to be replaced. See `set_buffer_stopping`.
 	pub fn set_buffer(&mut self, buf: Arc<Buffer>) -> AltoResult<()> {
 		if buf.ctx.device().as_raw() != self.src.ctx.device().as_raw() {
 			return Err(AltoError::WrongDevice);
 		}
+		match self.src.state() {
+			SourceState::Playing | SourceState::Paused => return Err(AltoError::SourcePlaying("set_buffer")),
+			_ => (),
+		}
 
 		{
 			let _lock = self.src.ctx.make_current(true);
 			unsafe { self.src.ctx.0.dev.0.alto.0.api.alSourcei(self.src.src, sys::AL_BUFFER, buf.buf as sys::ALint); }
+			self.src.ctx.get_error()?;
 		}
 
 		self.buf = Some(buf);
+		self.release_callback();
+		Ok(())
+	}
+	/// `alSourceStop()`, `alSourcei(AL_BUFFER)`, `alSourcePlay()`
+	/// Stop the source if it is playing or paused, replace its buffer, then start it from the beginning
+	/// of the new buffer if `play` is true.
+	pub fn set_buffer_stopping(&mut self, buf: Arc<Buffer>, play: bool) -> AltoResult<()> {
+		if buf.ctx.device().as_raw() != self.src.ctx.device().as_raw() {
+			return Err(AltoError::WrongDevice);
+		}
+
+		self.src.stop();
+		self.set_buffer(buf)?;
+		if play {
+			self.src.play();
+		}
 		Ok(())
 	}
 	/// `alSourcei(AL_BUFFER)`
@@ -1472,6 +3978,67 @@ impl StaticSource {
 		}
 
 		self.buf = None;
+		self.release_callback();
+	}
+
+
+	/// The callback buffer attached with `set_callback_buffer`.
+	pub fn callback_buffer(&self) -> Option<&Arc<CallbackBuffer>> { self.callback.as_ref() }
+
+
+	/// `alSourcei(AL_BUFFER)`
+	/// Requires `AL_SOFT_callback_buffer`
+	/// Attach a callback buffer, replacing any buffer attached before. Fails with `AltoError::InvalidOperation`
+	/// if `buf` is attached to another source, since a callback buffer can only be played by one source,
+	/// and with `AltoError::SourcePlaying` if this source is playing or paused.
+	pub fn set_callback_buffer(&mut self, buf: Arc<CallbackBuffer>) -> AltoResult<()> {
+		if buf.buf.ctx.device().as_raw() != self.src.ctx.device().as_raw() {
+			return Err(AltoError::WrongDevice);
+		}
+		match self.src.state() {
+			SourceState::Playing | SourceState::Paused => return Err(AltoError::SourcePlaying("set_callback_buffer")),
+			_ => (),
+		}
+
+		{
+			let mut owner = buf.owner.lock();
+			if owner.as_ref().and_then(|owner| owner.src.upgrade()).is_some_and(|src| !Arc::ptr_eq(&src, &self.src)) {
+				return Err(AltoError::InvalidOperation);
+			}
+
+			let _lock = self.src.ctx.make_current(true);
+			unsafe { self.src.ctx.0.dev.0.alto.0.api.alSourcei(self.src.src, sys::AL_BUFFER, buf.buf.buf as sys::ALint); }
+			self.src.ctx.get_error()?;
+			*owner = Some(CallbackOwner{src: Arc::downgrade(&self.src), ctx: self.src.ctx.clone(), id: self.src.src});
+		}
+
+		self.buf = None;
+		if self.callback.as_ref().is_some_and(|cb| !Arc::ptr_eq(cb, &buf)) {
+			self.release_callback();
+		}
+		self.callback = Some(buf);
+		Ok(())
+	}
+
+
+	fn release_callback(&mut self) {
+		if let Some(callback) = self.callback.take() {
+			callback.release();
+		}
+	}
+
+
+	/// Stop the source, detach its buffer and rewrap the same AL source as a `StreamingSource`.
+	/// Every property other than the buffer and playback offset is kept, including EFX sends and group membership.
+	pub fn into_streaming(self) -> AltoResult<StreamingSource> {
+		let StaticSource{src, buf, callback} = self;
+		src.detach_buffers()?;
+		drop(buf);
+		if let Some(callback) = callback {
+			callback.release();
+		}
+
+		Ok(StreamingSource{src, bufs: VecDeque::new(), scratch: Vec::new(), pool: None, playing: false, underrun: None, underruns: 0, auto_restart: false, played: Duration::from_secs(0) })
 	}
 
 
@@ -1487,24 +4054,112 @@ impl StaticSource {
 		let _lock = self.src.ctx.make_current(true);
 		unsafe { self.src.ctx.0.dev.0.alto.0.api.alSourcei(self.src.src, sys::AL_LOOPING, if value { sys::AL_TRUE } else { sys::AL_FALSE } as sys::ALint); }
 	}
+
+
+	/// `alGetBufferiv(AL_LOOP_POINTS_SOFT)`
+	/// Requires `AL_SOFT_loop_points`
+	/// The loop region of the attached buffer as `(start_frame, end_frame)`, or `None` if no buffer is attached
+	/// or its loop points span the whole buffer.
+	pub fn loop_region(&self) -> Option<(usize, usize)> {
+		let buf = self.buf.as_ref()?;
+		self.src.ctx.0.exts.AL_SOFT_loop_points().ok()?;
+		match buf.soft_loop_points() {
+			(0, end) if end == buf.sample_length() => None,
+			(start, end) => Some((start as usize, end as usize)),
+		}
+	}
+	/// `alBufferiv(AL_LOOP_POINTS_SOFT)`, `alSourcei(AL_LOOPING)`
+	/// Requires `AL_SOFT_loop_points`
+	/// Play the attached buffer up to `end_frame` and then keep looping back to `start_frame`, so that an intro
+	/// plays once before the region repeats. This enables looping.
+	///
+	/// The loop points belong to the buffer, so every source playing it loops the same region.
+	/// OpenAL only lets them change while the buffer is attached to no source, so it is detached from this source
+	/// around the change and reattached at the same playback offset, resuming a playing or paused source.
+	/// Fails with `AltoError::InvalidValue` unless `start_frame < end_frame <= buffer length`,
+	/// with `AltoError::InvalidOperation` if no buffer is attached, and with `AltoError::BufferInUse` if another
+	/// source also has it attached. A source that was playing or paused is resumed even if the change fails.
+	pub fn set_loop_region(&mut self, start_frame: usize, end_frame: usize) -> AltoResult<()> {
+		let len = self.buf.as_ref().ok_or(AltoError::InvalidOperation)?.sample_length() as usize;
+		if start_frame >= end_frame || end_frame > len {
+			return Err(AltoError::InvalidValue);
+		}
+
+		self.write_loop_points(start_frame as sys::ALint, end_frame as sys::ALint)?;
+		self.set_looping(true);
+		Ok(())
+	}
+	/// `alBufferiv(AL_LOOP_POINTS_SOFT)`
+	/// Requires `AL_SOFT_loop_points`
+	/// Reset the loop points of the attached buffer to span all of it, in the same way as `set_loop_region`.
+	/// Looping itself is left enabled.
+	pub fn clear_loop_region(&mut self) -> AltoResult<()> {
+		let len = self.buf.as_ref().ok_or(AltoError::InvalidOperation)?.sample_length();
+		self.write_loop_points(0, len)
+	}
+
+
+	fn write_loop_points(&mut self, start: sys::ALint, end: sys::ALint) -> AltoResult<()> {
+		self.src.ctx.0.exts.AL_SOFT_loop_points()?.AL_LOOP_POINTS_SOFT?;
+		let buf = self.buf.clone().ok_or(AltoError::InvalidOperation)?;
+		let state = self.src.state();
+		let offset = self.src.sample_offset();
+
+		self.src.detach_buffers()?;
+		let result = buf.set_loop_points(start, end);
+		// Reattach and resume even if the loop points were refused, so the source is left as it was.
+		{
+			let _lock = self.src.ctx.make_current(true);
+			unsafe { self.src.ctx.0.dev.0.alto.0.api.alSourcei(self.src.src, sys::AL_BUFFER, buf.buf as sys::ALint); }
+			self.src.ctx.get_error()?;
+		}
+		if let SourceState::Playing | SourceState::Paused = state {
+			self.src.set_sample_offset(offset)?;
+			self.src.play();
+			if state == SourceState::Paused {
+				self.src.pause();
+			}
+		}
+		result
+	}
+}
+
+
+impl<S: Source + Any> SourceObject for S {
+	#[inline] fn object_context(&self) -> &Context { self.context() }
+	#[inline] fn object_raw(&self) -> sys::ALuint { self.as_raw() }
+	#[inline] fn as_any(&self) -> &dyn Any { self }
+	#[inline] fn as_any_mut(&mut self) -> &mut dyn Any { self }
 }
 
 
 unsafe impl Source for StaticSource {
 	#[inline] fn context(&self) -> &Context { self.src.context() }
 	#[inline] fn as_raw(&self) -> sys::ALuint { self.src.as_raw() }
+	#[inline] fn handle(&self) -> SourceHandle { SourceHandle::new(&self.src) }
+	#[inline] fn update(&mut self) -> SourceUpdate<'_> { SourceUpdate::new(&self.src) }
 
 	#[inline] fn state(&self) -> SourceState { self.src.state() }
+	#[inline] fn source_type(&self) -> AltoResult<SourceType> { self.src.source_type() }
 	#[inline] fn play(&mut self) -> () { self.src.play() }
+	#[inline] fn wait_until(&self, state: SourceState, timeout: Duration, poll: Duration) -> AltoResult<bool> { self.src.wait_until(state, timeout, poll) }
+	#[inline] fn wait_until_stopped(&self, timeout: Duration) -> AltoResult<bool> { self.src.wait_until_stopped(timeout) }
+	#[inline] fn soft_play_at_time(&mut self, time: i64) -> AltoResult<()> { self.src.soft_play_at_time(time) }
 	#[inline] fn pause(&mut self) -> () { self.src.pause() }
 	#[inline] fn stop(&mut self) -> () { self.src.stop() }
+	#[inline] fn stop_with_fade(&mut self, duration: Duration) -> AltoResult<()> { self.src.stop_with_fade(duration) }
 	#[inline] fn rewind(&mut self) -> () { self.src.rewind() }
+	#[inline] fn buffer_raw(&self) -> AltoResult<Option<sys::ALuint>> { self.src.buffer_raw() }
 
 	#[inline] fn relative(&self) -> bool { self.src.relative() }
 	#[inline] fn set_relative(&mut self, value: bool) { self.src.set_relative(value) }
 
 	#[inline] fn gain(&self) -> f32 { self.src.gain() }
 	#[inline] fn set_gain(&mut self, value: f32) -> AltoResult<()> { self.src.set_gain(value) }
+	#[inline] fn gain_step(&mut self, target: f32, dt: f32, rate: f32) -> AltoResult<bool> { self.src.gain_step(target, dt, rate) }
+	#[inline] fn fade_gain_to(&mut self, target: f32, duration: Duration) -> AltoResult<FadeHandle> { self.src.fade_gain_to(target, duration) }
+	#[inline] fn is_muted(&self) -> bool { self.src.is_muted() }
+	#[inline] fn set_muted(&mut self, value: bool) -> AltoResult<()> { self.src.set_muted(value) }
 
 	#[inline] fn min_gain(&self) -> f32 { self.src.min_gain() }
 	#[inline] fn set_min_gain(&mut self, value: f32) -> AltoResult<()> { self.src.set_min_gain(value) }
@@ -1521,17 +4176,23 @@ unsafe impl Source for StaticSource {
 	#[inline] fn max_distance(&self) -> f32 { self.src.max_distance() }
 	#[inline] fn set_max_distance(&mut self, value: f32) -> AltoResult<()> { self.src.set_max_distance(value) }
 
+	#[inline] fn attenuation(&self) -> Attenuation { self.src.attenuation() }
+	#[inline] fn set_attenuation(&mut self, value: Attenuation) -> AltoResult<()> { self.src.set_attenuation(value) }
+
 	#[inline] fn pitch(&self) -> f32 { self.src.pitch() }
 	#[inline] fn set_pitch(&mut self, value: f32) -> AltoResult<()> { self.src.set_pitch(value) }
 
 	#[inline] fn position<V: From<[f32; 3]>>(&self) -> V { self.src.position() }
-	#[inline] fn set_position<V: Into<[f32; 3]>>(&mut self, value: V) -> AltoResult<()> { self.src.set_position(value) }
+	#[inline] fn set_position<V: AsAlVec3>(&mut self, value: V) -> AltoResult<()> { self.src.set_position(value) }
 
 	#[inline] fn velocity<V: From<[f32; 3]>>(&self) -> V { self.src.velocity() }
-	#[inline] fn set_velocity<V: Into<[f32; 3]>>(&mut self, value: V) -> AltoResult<()> { self.src.set_velocity(value) }
+	#[inline] fn set_velocity<V: AsAlVec3>(&mut self, value: V) -> AltoResult<()> { self.src.set_velocity(value) }
+	#[inline] fn update_position_with_velocity<V: AsAlVec3>(&mut self, pos: V, dt: f32) -> AltoResult<()> { self.src.update_position_with_velocity(pos, dt) }
+	#[inline] fn set_head_locked<V: AsAlVec3>(&mut self, offset: V) -> AltoResult<()> { self.src.set_head_locked(offset) }
+	#[inline] fn detach_to_world<V: AsAlVec3>(&mut self, listener_pos: V, listener_orientation: (V, V)) -> AltoResult<()> { self.src.detach_to_world(listener_pos, listener_orientation) }
 
 	#[inline] fn direction<V: From<[f32; 3]>>(&self) -> V { self.src.direction() }
-	#[inline] fn set_direction<V: Into<[f32; 3]>>(&mut self, value: V) -> AltoResult<()> { self.src.set_direction(value) }
+	#[inline] fn set_direction<V: AsAlVec3>(&mut self, value: V) -> AltoResult<()> { self.src.set_direction(value) }
 
 	#[inline] fn cone_inner_angle(&self) -> f32 { self.src.cone_inner_angle() }
 	#[inline] fn set_cone_inner_angle(&mut self, value: f32) -> AltoResult<()> { self.src.set_cone_inner_angle(value) }
@@ -1541,6 +4202,8 @@ unsafe impl Source for StaticSource {
 
 	#[inline] fn cone_outer_gain(&self) -> f32 { self.src.cone_outer_gain() }
 	#[inline] fn set_cone_outer_gain(&mut self, value: f32) -> AltoResult<()> { self.src.set_cone_outer_gain(value) }
+	#[inline] fn cone(&self) -> (Angle, Angle, f32) { self.src.cone() }
+	#[inline] fn set_cone(&mut self, inner: Angle, outer: Angle, outer_gain: f32) -> AltoResult<()> { self.src.set_cone(inner, outer, outer_gain) }
 
 	#[inline] fn sec_offset(&self) -> f32 { self.src.sec_offset() }
 	#[inline] fn set_sec_offset(&mut self, value: f32) -> AltoResult<()> { self.src.set_sec_offset(value) }
@@ -1550,10 +4213,23 @@ unsafe impl Source for StaticSource {
 
 	#[inline] fn byte_offset(&self) -> sys::ALint { self.src.byte_offset() }
 	#[inline] fn set_byte_offset(&mut self, value: sys::ALint) -> AltoResult<()> { self.src.set_byte_offset(value) }
+	fn seek(&mut self, pos: SeekPos) -> AltoResult<()> {
+		let (bytes, samples, freq) = self.src.static_len()?;
+		self.src.seek(pos, bytes, samples, freq)
+	}
+	#[inline] fn tell(&self) -> SeekPos { self.src.tell() }
+	#[inline] fn playback_position(&self) -> AltoResult<Duration> { self.src.playback_position() }
+	#[inline] fn snapshot(&self) -> AltoResult<SourceSnapshot> { self.src.snapshot() }
+	#[inline] fn apply_snapshot(&mut self, value: &SourceSnapshot) -> AltoResult<()> { self.src.apply_snapshot(value) }
 
 	#[inline] fn soft_sec_offset_latency(&self) -> AltoResult<(f64, f64)> { self.src.soft_sec_offset_latency() }
+	#[inline] fn soft_position_f64<V: From<[f64; 3]>>(&self) -> AltoResult<V> { self.src.soft_vec_f64(sys::AL_POSITION) }
+	#[inline] fn set_soft_position_f64<V: Into<[f64; 3]>>(&mut self, value: V) -> AltoResult<()> { self.src.set_soft_vec_f64(sys::AL_POSITION, value) }
+	#[inline] fn soft_velocity_f64<V: From<[f64; 3]>>(&self) -> AltoResult<V> { self.src.soft_vec_f64(sys::AL_VELOCITY) }
+	#[inline] fn set_soft_velocity_f64<V: Into<[f64; 3]>>(&mut self, value: V) -> AltoResult<()> { self.src.set_soft_vec_f64(sys::AL_VELOCITY, value) }
 
-	#[inline] fn soft_sample_frac_offset_latency(&self) -> AltoResult<(i32, i32, i64)> { self.src.soft_sample_offset_frac_latency() }
+	#[inline] fn soft_sample_frac_offset_latency(&self) -> AltoResult<(i64, Duration)> { self.src.soft_sample_frac_offset_latency() }
+	#[inline] fn soft_sample_fixed_offset_latency(&self) -> AltoResult<(i64, Duration)> { self.src.soft_sample_fixed_offset_latency() }
 
 	#[inline] fn soft_sec_length(&self) -> AltoResult<f32> { self.src.soft_sec_length() }
 
@@ -1564,6 +4240,9 @@ unsafe impl Source for StaticSource {
 	#[inline] fn soft_direct_channels(&self) -> bool { self.src.soft_direct_channels() }
 	#[inline] fn set_soft_direct_channels(&mut self, value: bool) -> AltoResult<()> { self.src.set_soft_direct_channels(value) }
 
+	#[inline] fn soft_direct_channels_mode(&self) -> AltoResult<SoftDirectChannelsMode> { self.src.soft_direct_channels_mode() }
+	#[inline] fn set_soft_direct_channels_mode(&mut self, value: SoftDirectChannelsMode) -> AltoResult<()> { self.src.set_soft_direct_channels_mode(value) }
+
 	#[inline] fn distance_model(&self) -> DistanceModel { self.src.distance_model() }
 	#[inline] fn set_distance_model(&mut self, value: DistanceModel) -> AltoResult<()> { self.src.set_distance_model(value) }
 
@@ -1575,6 +4254,8 @@ unsafe impl Source for StaticSource {
 
 	#[inline] fn stereo_angles<V: From<[f32; 2]>>(&self) -> AltoResult<V> { self.src.stereo_angles() }
 	#[inline] fn set_stereo_angles<V: Into<[f32; 2]>>(&mut self, value: V) -> AltoResult<()> { self.src.set_stereo_angles(value) }
+	#[inline] fn pan(&self) -> AltoResult<f32> { self.src.pan() }
+	#[inline] fn set_pan(&mut self, value: f32) -> AltoResult<()> { self.src.set_pan(value) }
 
 	#[inline] fn radius(&self) -> f32 { self.src.radius() }
 	#[inline] fn set_radius(&self, value: f32) -> AltoResult<()> { self.src.set_radius(value) }
@@ -1582,6 +4263,7 @@ unsafe impl Source for StaticSource {
 	#[inline] fn set_direct_filter<F: Filter>(&mut self, value: &F) -> AltoResult<()> { self.src.set_direct_filter(value) }
 	#[inline] fn clear_direct_filter(&mut self) { self.src.clear_direct_filter() }
 
+	#[inline] fn max_auxiliary_sends(&self) -> AltoResult<sys::ALint> { self.src.max_auxiliary_sends() }
 	#[inline] fn set_aux_send(&mut self, send: sys::ALint, slot: &mut AuxEffectSlot) -> AltoResult<()> { SourceInner::set_aux_send(&self.src, send, slot) }
 	#[inline] fn set_aux_send_filter<F: Filter>(&mut self, send: sys::ALint, slot: &mut AuxEffectSlot, filter: &F) -> AltoResult<()> { SourceInner::set_aux_send_filter(&self.src, send, slot, filter) }
 	#[inline] fn clear_aux_send(&mut self, send: sys::ALint) { self.src.clear_aux_send(send) }
@@ -1616,8 +4298,35 @@ impl StreamingSource {
 			unsafe { ctx.0.dev.0.alto.0.api.alGenSources(1, &mut src as *mut sys::ALuint); }
 			ctx.get_error()?;
 		}
+		Ok(StreamingSource::from_raw(ctx, src, true))
+	}
+	fn from_raw(ctx: Context, src: sys::ALuint, owned: bool) -> StreamingSource {
+		*ctx.0.sources.lock().entry(src).or_insert(0) += 1;
 		let sends = iter::repeat(0).take(ctx.0.dev.0.max_aux_sends() as usize).collect();
-		Ok(StreamingSource{src: Arc::new(SourceInner{ctx: ctx, src: src, sends: Mutex::new(sends)}), bufs: VecDeque::new() })
+		StreamingSource{src: Arc::new(SourceInner{ctx: ctx, src: src, sends: Mutex::new(sends), last_pos: Mutex::new(None), muted: Mutex::new(None), owned}), bufs: VecDeque::new(), scratch: Vec::new(), pool: None, playing: false, underrun: None, underruns: 0, auto_restart: false, played: Duration::from_secs(0) }
+	}
+
+
+	/// Stop the source, unqueue every buffer and rewrap the same AL source as a `StaticSource` playing `buf`.
+	/// Every property other than the buffers and playback offset is kept, including EFX sends and group membership.
+	/// The queued buffers and any pool are deleted.
+	pub fn into_static(self, buf: Arc<Buffer>) -> AltoResult<StaticSource> {
+		self.src.ctx.check_buffer(&buf)?;
+		let StreamingSource{src, bufs, pool, ..} = self;
+		src.detach_buffers()?;
+		drop((bufs, pool));
+
+		let mut static_src = StaticSource{src, buf: None, callback: None};
+		static_src.set_buffer(buf)?;
+		static_src.src.expect_type(SourceType::Static)?;
+		Ok(static_src)
+	}
+
+
+	/// Restart the count of unqueued buffers behind `Source::playback_position` from zero, such as after
+	/// seeking a stream or starting a new one on the same source.
+	pub fn reset_playback_position(&mut self) {
+		self.played = Duration::from_secs(0);
 	}
 
 
@@ -1627,6 +4336,14 @@ impl StreamingSource {
 	}
 
 
+	/// The buffers in the queue, oldest first, including processed buffers that have not been unqueued.
+	/// This borrows the source, so it is a consistent snapshot of the queue, though playback carries on.
+	/// Compare with `buffers_processed` or `Source::buffer_raw` to see which buffer is playing.
+	pub fn queued_buffers<'a>(&'a self) -> vec_deque::Iter<'a, Buffer> {
+		self.bufs.iter()
+	}
+
+
 	/// `alGetSourcei(AL_BUFFERS_PROCESSED)`
 	pub fn buffers_processed(&self) -> sys::ALint {
 		let _lock = self.src.ctx.make_current(true);
@@ -1637,17 +4354,38 @@ impl StreamingSource {
 
 
 	/// `alSourceQueueBuffers()`
+	/// If auto restart is enabled and the source has underrun, it is played again after queueing,
+	/// starting from the first buffer queued since the underrun rather than replaying earlier buffers still in the queue.
 	pub fn queue_buffer(&mut self, buf: Buffer) -> AltoResult<()> {
+		if buf.ctx.device().as_raw() != self.src.ctx.device().as_raw() {
+			return Err(AltoError::WrongDevice);
+		}
+		let restart = if self.auto_restart && self.check_underrun()? {
+			Some(self.bufs.iter().take(self.underrun.unwrap_or(0)).map(|b| b.len).sum::<sys::ALsizei>())
+		} else {
+			None
+		};
+
 		{
-			if buf.ctx.device().as_raw() != self.src.ctx.device().as_raw() {
-				return Err(AltoError::WrongDevice);
-			}
 			let _lock = self.src.ctx.make_current(true);
 
 			unsafe { self.src.ctx.0.dev.0.alto.0.api.alSourceQueueBuffers(self.src.src, 1, &buf.buf); }
 		}
 
 		self.bufs.push_back(buf);
+		if let Some(skip) = restart {
+			{
+				let _lock = self.src.ctx.make_current(true);
+				let api = &self.src.ctx.0.dev.0.alto.0.api;
+				unsafe {
+					api.alSourceRewind(self.src.src);
+					if skip > 0 {
+						api.alSourcei(self.src.src, sys::AL_SAMPLE_OFFSET, skip);
+					}
+				}
+			}
+			self.play();
+		}
 		Ok(())
 	}
 
@@ -1661,26 +4399,287 @@ impl StreamingSource {
 			self.src.ctx.get_error()?;
 		}
 
-		Ok(self.bufs.pop_front().unwrap())
+		self.underrun = self.underrun.map(|n| n.saturating_sub(1));
+		let buf = self.bufs.pop_front().unwrap();
+		self.played += frames_duration(buf.len as i64, buf.frequency());
+		Ok(buf)
+	}
+
+
+	/// Block until every queued buffer has played and the source has stopped, returning `false` if `timeout`
+	/// elapses first. The thread sleeps for the duration of the buffer currently playing, as computed from
+	/// its length, frequency and the source's pitch, before checking again. With `AL_SOFT_events` it also
+	/// wakes as soon as the source changes state, and otherwise checks at least every 50ms, so a stop from
+	/// another thread ends the wait promptly. A source that was never played returns whether its queue is empty,
+	/// while a paused or looping source keeps waiting until the timeout. Once drained, the source is no longer
+	/// considered to be playing, so the natural stop is not reported by `check_underrun`.
+	pub fn drain(&mut self, timeout: Option<Duration>) -> AltoResult<bool> {
+		let start = Instant::now();
+		let signal = self.src.ctx.events();
+		loop {
+			// Read the generation before the state, so a change in between is not slept through.
+			let generation = signal.map(|s| *s.generation.lock());
+			let (state, processed) = {
+				let _lock = self.src.ctx.make_current(true);
+				let api = &self.src.ctx.0.dev.0.alto.0.api;
+				let (mut state, mut processed) = (0, 0);
+				unsafe {
+					api.alGetSourcei(self.src.src, sys::AL_SOURCE_STATE, &mut state);
+					api.alGetSourcei(self.src.src, sys::AL_BUFFERS_PROCESSED, &mut processed);
+				}
+				self.src.ctx.get_error()?;
+				(state, processed as usize)
+			};
+			match state {
+				sys::AL_STOPPED => {
+					self.set_playing(false);
+					return Ok(processed >= self.bufs.len());
+				},
+				sys::AL_INITIAL => return Ok(self.bufs.is_empty()),
+				_ => (),
+			}
+
+			let mut wait = Duration::from_millis(10);
+			if let Some(buf) = self.bufs.get(processed) {
+				let freq = buf.frequency();
+				let pitch = self.src.pitch();
+				if freq > 0 && pitch > 0.0 {
+					wait = Duration::from_secs_f64(buf.len as f64 / freq as f64 / pitch as f64);
+				}
+			}
+			wait = cmp::max(wait, Duration::from_millis(1));
+			if let Some(timeout) = timeout {
+				let elapsed = start.elapsed();
+				if elapsed >= timeout {
+					return Ok(false);
+				}
+				wait = cmp::min(wait, timeout - elapsed);
+			}
+
+			match signal {
+				Some(signal) => {
+					let mut g = signal.generation.lock();
+					if Some(*g) == generation {
+						signal.cond.wait_for(&mut g, wait);
+					}
+				},
+				None => thread::sleep(cmp::min(wait, Duration::from_millis(50))),
+			}
+		}
+	}
+
+	/// Requires `AL_SOFT_events`
+	/// Call `cb` with the number of buffers that finished playing whenever this source completes queued buffers,
+	/// so they can be unqueued and refilled without polling `buffers_processed`. Replaces any previous handler,
+	/// which is dropped, and stays registered until `clear_buffers_processed` or the source is dropped.
+	/// The callback runs on OpenAL's event thread and must not call into alto or OpenAL; signal another thread instead.
+	pub fn on_buffers_processed<C: FnMut(usize) + Send + 'static>(&mut self, cb: C) -> AltoResult<()> {
+		let events = self.src.ctx.enable_buffer_events()?;
+		let _old = events.buffer_handlers.lock().insert(self.src.src, Box::new(cb));
+		Ok(())
+	}
+
+
+	/// Unregister the handler set by `on_buffers_processed`, if any.
+	pub fn clear_buffers_processed(&mut self) {
+		let _old = self.src.ctx.0.events.buffer_handlers.lock().remove(&self.src.src);
+	}
+
+
+	/// `alSourceUnqueueBuffers()`
+	/// Unqueue all processed buffers with a single call, push them onto `out` in the order they were queued,
+	/// and return how many there were. When no buffers have been processed this only queries `AL_BUFFERS_PROCESSED`.
+	/// The buffer names are unqueued into scratch space kept by the source, which is sized to the queue
+	/// on first use and only grows again if more buffers are queued than ever before.
+	pub fn unqueue_processed_into(&mut self, out: &mut Vec<Buffer>) -> AltoResult<usize> {
+		let len = {
+			let _lock = self.src.ctx.make_current(true);
+			let api = &self.src.ctx.0.dev.0.alto.0.api;
+			let mut processed = 0;
+			unsafe { api.alGetSourcei(self.src.src, sys::AL_BUFFERS_PROCESSED, &mut processed); }
+			self.src.ctx.get_error()?;
+			if processed <= 0 {
+				return Ok(0);
+			}
+
+			let len = cmp::min(processed as usize, self.bufs.len());
+			if self.scratch.len() < self.bufs.len() {
+				self.scratch.resize(self.bufs.len(), 0);
+			}
+			unsafe { api.alSourceUnqueueBuffers(self.src.src, len as sys::ALsizei, self.scratch.as_mut_ptr()); }
+			self.src.ctx.get_error()?;
+			len
+		};
+
+		self.underrun = self.underrun.map(|n| n.saturating_sub(len));
+		self.played += self.bufs.range(..len).map(|b| frames_duration(b.len as i64, b.frequency())).sum::<Duration>();
+		out.extend(self.bufs.drain(..len));
+		Ok(len)
+	}
+
+
+	/// Create `count` empty buffers to rotate between the queue and a free list, for use with
+	/// `acquire_free`, `queue` and `reclaim`. Each buffer holds at most `frames_per_buffer` sample frames.
+	/// Calling this again replaces the free list, and buffers still queued join the new pool once processed.
+	pub fn with_pool(&mut self, count: usize, frames_per_buffer: usize) -> AltoResult<()> {
+		if count == 0 || frames_per_buffer == 0 {
+			return Err(AltoError::InvalidValue);
+		}
+
+		let mut names = vec![0; count];
+		{
+			let _lock = self.src.ctx.make_current(true);
+			unsafe { self.src.ctx.0.dev.0.alto.0.api.alGenBuffers(count as sys::ALsizei, names.as_mut_ptr()); }
+			self.src.ctx.get_error()?;
+		}
+
+		let free = names.into_iter().map(|buf| Buffer{ctx: self.src.ctx.clone(), buf, len: 0}).collect();
+		self.pool = Some(BufferPool{free, frames: frames_per_buffer});
+		Ok(())
+	}
+
+
+	/// Take a free buffer from the pool, reclaiming processed buffers first.
+	/// Returns `None` if there is no pool, or if every buffer is queued. Use `reclaim` to tell
+	/// the latter apart from an underrun.
+	pub fn acquire_free(&mut self) -> Option<PooledBuffer> {
+		if self.pool.as_ref().map(|p| p.free.is_empty()).unwrap_or(true) {
+			let _ = self.reclaim();
+		}
+
+		let pool = self.pool.as_mut()?;
+		let frames = pool.frames;
+		pool.free.pop().map(|buf| PooledBuffer{buf, frames})
+	}
+
+
+	/// `alSourceQueueBuffers()`
+	/// Queue a buffer filled after `acquire_free`.
+	pub fn queue(&mut self, buf: PooledBuffer) -> AltoResult<()> {
+		self.queue_buffer(buf.buf)
+	}
+
+
+	/// `alSourceUnqueueBuffers()`
+	/// Return all processed buffers to the pool's free list, including any that were queued with
+	/// `queue_buffer`, and report whether the source is streaming, idle or has underrun.
+	/// Fails with `AltoError::InvalidOperation` if there is no pool.
+	pub fn reclaim(&mut self) -> AltoResult<PoolStatus> {
+		let mut pool = self.pool.take().ok_or(AltoError::InvalidOperation)?;
+		let result = self.unqueue_processed_into(&mut pool.free);
+		self.pool = Some(pool);
+		result?;
+
+		if self.check_underrun()? {
+			return Ok(PoolStatus::Underrun);
+		}
+		Ok(match self.src.state() {
+			SourceState::Playing | SourceState::Paused => PoolStatus::Streaming,
+			_ => PoolStatus::Idle,
+		})
+	}
+
+
+	/// `alGetSourcei(AL_SOURCE_STATE)` and `alGetSourcei(AL_BUFFERS_PROCESSED)`
+	/// Whether the source has underrun: it was last started with `play` or `soft_play_at_time`, and has since
+	/// stopped on its own with no unprocessed buffers left. Each underrun is counted once in `underruns`,
+	/// and the condition holds until the source is played, stopped or rewound through this `StreamingSource`.
+	/// Sources stopped with `Context::stop_all` still count as intended to play. A stream that has reached
+	/// its end should be stopped explicitly so that it is not reported as an underrun.
+	pub fn check_underrun(&mut self) -> AltoResult<bool> {
+		if !self.playing || self.underrun.is_some() {
+			return Ok(self.underrun.is_some());
+		}
+
+		let (state, processed) = {
+			let _lock = self.src.ctx.make_current(true);
+			let api = &self.src.ctx.0.dev.0.alto.0.api;
+			let (mut state, mut processed) = (0, 0);
+			unsafe {
+				api.alGetSourcei(self.src.src, sys::AL_SOURCE_STATE, &mut state);
+				api.alGetSourcei(self.src.src, sys::AL_BUFFERS_PROCESSED, &mut processed);
+			}
+			self.src.ctx.get_error()?;
+			(state, processed)
+		};
+		if state == sys::AL_STOPPED && processed as usize >= self.bufs.len() {
+			self.underrun = Some(self.bufs.len());
+			self.underruns += 1;
+		}
+		Ok(self.underrun.is_some())
+	}
+
+
+	/// Total number of underruns detected by `check_underrun` over the life of this source.
+	pub fn underruns(&self) -> usize { self.underruns }
+
+
+	/// Whether `queue_buffer` restarts playback after an underrun.
+	pub fn auto_restart(&self) -> bool { self.auto_restart }
+	/// When enabled, `queue_buffer` and `queue` check for an underrun before queueing, and if one is
+	/// found, play the source again from the first buffer queued since the underrun. Disabled by default.
+	pub fn set_auto_restart(&mut self, value: bool) { self.auto_restart = value; }
+
+
+	fn set_playing(&mut self, playing: bool) {
+		self.playing = playing;
+		self.underrun = None;
+	}
+}
+
+
+impl PooledBuffer {
+	/// `alBufferData()`
+	/// Fails with `AltoError::InvalidValue` if `data` holds more sample frames than the pool allows.
+	pub fn set_data<F: SampleFrame, B: AsBufferData<F>>(&mut self, data: B, freq: sys::ALint) -> AltoResult<()> {
+		let (_, size) = data.as_buffer_data();
+		if size / mem::size_of::<F::Sample>() / F::len() > self.frames {
+			return Err(AltoError::InvalidValue);
+		}
+
+		self.buf.set_data(data, freq)
 	}
+
+
+	/// Maximum number of sample frames this buffer may hold.
+	pub fn capacity(&self) -> usize { self.frames }
+}
+
+
+impl Deref for PooledBuffer {
+	type Target = Buffer;
+
+	fn deref(&self) -> &Buffer { &self.buf }
 }
 
 
 unsafe impl Source for StreamingSource {
 	#[inline] fn context(&self) -> &Context { self.src.context() }
 	#[inline] fn as_raw(&self) -> sys::ALuint { self.src.as_raw() }
+	#[inline] fn handle(&self) -> SourceHandle { SourceHandle::new(&self.src) }
+	#[inline] fn update(&mut self) -> SourceUpdate<'_> { SourceUpdate::new(&self.src) }
 
 	#[inline] fn state(&self) -> SourceState { self.src.state() }
-	#[inline] fn play(&mut self) -> () { self.src.play() }
+	#[inline] fn source_type(&self) -> AltoResult<SourceType> { self.src.source_type() }
+	#[inline] fn play(&mut self) { self.set_playing(true); self.src.play() }
+	#[inline] fn wait_until(&self, state: SourceState, timeout: Duration, poll: Duration) -> AltoResult<bool> { self.src.wait_until(state, timeout, poll) }
+	#[inline] fn wait_until_stopped(&self, timeout: Duration) -> AltoResult<bool> { self.src.wait_until_stopped(timeout) }
+	#[inline] fn soft_play_at_time(&mut self, time: i64) -> AltoResult<()> { self.set_playing(true); self.src.soft_play_at_time(time) }
 	#[inline] fn pause(&mut self) -> () { self.src.pause() }
-	#[inline] fn stop(&mut self) -> () { self.src.stop() }
-	#[inline] fn rewind(&mut self) -> () { self.src.rewind() }
+	#[inline] fn stop(&mut self) { self.set_playing(false); self.src.stop() }
+	#[inline] fn stop_with_fade(&mut self, duration: Duration) -> AltoResult<()> { self.set_playing(false); self.src.stop_with_fade(duration) }
+	#[inline] fn rewind(&mut self) { self.set_playing(false); self.src.rewind() }
+	#[inline] fn buffer_raw(&self) -> AltoResult<Option<sys::ALuint>> { self.src.buffer_raw() }
 
 	#[inline] fn relative(&self) -> bool { self.src.relative() }
 	#[inline] fn set_relative(&mut self, value: bool) { self.src.set_relative(value) }
 
 	#[inline] fn gain(&self) -> f32 { self.src.gain() }
 	#[inline] fn set_gain(&mut self, value: f32) -> AltoResult<()> { self.src.set_gain(value) }
+	#[inline] fn gain_step(&mut self, target: f32, dt: f32, rate: f32) -> AltoResult<bool> { self.src.gain_step(target, dt, rate) }
+	#[inline] fn fade_gain_to(&mut self, target: f32, duration: Duration) -> AltoResult<FadeHandle> { self.src.fade_gain_to(target, duration) }
+	#[inline] fn is_muted(&self) -> bool { self.src.is_muted() }
+	#[inline] fn set_muted(&mut self, value: bool) -> AltoResult<()> { self.src.set_muted(value) }
 
 	#[inline] fn min_gain(&self) -> f32 { self.src.min_gain() }
 	#[inline] fn set_min_gain(&mut self, value: f32) -> AltoResult<()> { self.src.set_min_gain(value) }
@@ -1697,17 +4696,23 @@ unsafe impl Source for StreamingSource {
 	#[inline] fn max_distance(&self) -> f32 { self.src.max_distance() }
 	#[inline] fn set_max_distance(&mut self, value: f32) -> AltoResult<()> { self.src.set_max_distance(value) }
 
+	#[inline] fn attenuation(&self) -> Attenuation { self.src.attenuation() }
+	#[inline] fn set_attenuation(&mut self, value: Attenuation) -> AltoResult<()> { self.src.set_attenuation(value) }
+
 	#[inline] fn pitch(&self) -> f32 { self.src.pitch() }
 	#[inline] fn set_pitch(&mut self, value: f32) -> AltoResult<()> { self.src.set_pitch(value) }
 
 	#[inline] fn position<V: From<[f32; 3]>>(&self) -> V { self.src.position() }
-	#[inline] fn set_position<V: Into<[f32; 3]>>(&mut self, value: V) -> AltoResult<()> { self.src.set_position(value) }
+	#[inline] fn set_position<V: AsAlVec3>(&mut self, value: V) -> AltoResult<()> { self.src.set_position(value) }
 
 	#[inline] fn velocity<V: From<[f32; 3]>>(&self) -> V { self.src.velocity() }
-	#[inline] fn set_velocity<V: Into<[f32; 3]>>(&mut self, value: V) -> AltoResult<()> { self.src.set_velocity(value) }
+	#[inline] fn set_velocity<V: AsAlVec3>(&mut self, value: V) -> AltoResult<()> { self.src.set_velocity(value) }
+	#[inline] fn update_position_with_velocity<V: AsAlVec3>(&mut self, pos: V, dt: f32) -> AltoResult<()> { self.src.update_position_with_velocity(pos, dt) }
+	#[inline] fn set_head_locked<V: AsAlVec3>(&mut self, offset: V) -> AltoResult<()> { self.src.set_head_locked(offset) }
+	#[inline] fn detach_to_world<V: AsAlVec3>(&mut self, listener_pos: V, listener_orientation: (V, V)) -> AltoResult<()> { self.src.detach_to_world(listener_pos, listener_orientation) }
 
 	#[inline] fn direction<V: From<[f32; 3]>>(&self) -> V { self.src.direction() }
-	#[inline] fn set_direction<V: Into<[f32; 3]>>(&mut self, value: V) -> AltoResult<()> { self.src.set_direction(value) }
+	#[inline] fn set_direction<V: AsAlVec3>(&mut self, value: V) -> AltoResult<()> { self.src.set_direction(value) }
 
 	#[inline] fn cone_inner_angle(&self) -> f32 { self.src.cone_inner_angle() }
 	#[inline] fn set_cone_inner_angle(&mut self, value: f32) -> AltoResult<()> { self.src.set_cone_inner_angle(value) }
@@ -1717,6 +4722,8 @@ unsafe impl Source for StreamingSource {
 
 	#[inline] fn cone_outer_gain(&self) -> f32 { self.src.cone_outer_gain() }
 	#[inline] fn set_cone_outer_gain(&mut self, value: f32) -> AltoResult<()> { self.src.set_cone_outer_gain(value) }
+	#[inline] fn cone(&self) -> (Angle, Angle, f32) { self.src.cone() }
+	#[inline] fn set_cone(&mut self, inner: Angle, outer: Angle, outer_gain: f32) -> AltoResult<()> { self.src.set_cone(inner, outer, outer_gain) }
 
 	#[inline] fn sec_offset(&self) -> f32 { self.src.sec_offset() }
 	#[inline] fn set_sec_offset(&mut self, value: f32) -> AltoResult<()> { self.src.set_sec_offset(value) }
@@ -1726,10 +4733,27 @@ unsafe impl Source for StreamingSource {
 
 	#[inline] fn byte_offset(&self) -> sys::ALint { self.src.byte_offset() }
 	#[inline] fn set_byte_offset(&mut self, value: sys::ALint) -> AltoResult<()> { self.src.set_byte_offset(value) }
+	fn seek(&mut self, pos: SeekPos) -> AltoResult<()> {
+		let bytes = self.bufs.iter().map(|b| b.size()).sum();
+		let samples = self.bufs.iter().map(|b| b.len).sum();
+		let freq = self.bufs.front().map(|b| b.frequency()).unwrap_or(0);
+		self.src.seek(pos, bytes, samples, freq)
+	}
+	#[inline] fn tell(&self) -> SeekPos { self.src.tell() }
+	fn playback_position(&self) -> AltoResult<Duration> {
+		Ok(queue_position(self.played, self.src.frame_offset()?, self.bufs.iter()))
+	}
+	#[inline] fn snapshot(&self) -> AltoResult<SourceSnapshot> { self.src.snapshot() }
+	#[inline] fn apply_snapshot(&mut self, value: &SourceSnapshot) -> AltoResult<()> { self.src.apply_snapshot(value) }
 
 	#[inline] fn soft_sec_offset_latency(&self) -> AltoResult<(f64, f64)> { self.src.soft_sec_offset_latency() }
+	#[inline] fn soft_position_f64<V: From<[f64; 3]>>(&self) -> AltoResult<V> { self.src.soft_vec_f64(sys::AL_POSITION) }
+	#[inline] fn set_soft_position_f64<V: Into<[f64; 3]>>(&mut self, value: V) -> AltoResult<()> { self.src.set_soft_vec_f64(sys::AL_POSITION, value) }
+	#[inline] fn soft_velocity_f64<V: From<[f64; 3]>>(&self) -> AltoResult<V> { self.src.soft_vec_f64(sys::AL_VELOCITY) }
+	#[inline] fn set_soft_velocity_f64<V: Into<[f64; 3]>>(&mut self, value: V) -> AltoResult<()> { self.src.set_soft_vec_f64(sys::AL_VELOCITY, value) }
 
-	#[inline] fn soft_sample_frac_offset_latency(&self) -> AltoResult<(i32, i32, i64)> { self.src.soft_sample_offset_frac_latency() }
+	#[inline] fn soft_sample_frac_offset_latency(&self) -> AltoResult<(i64, Duration)> { self.src.soft_sample_frac_offset_latency() }
+	#[inline] fn soft_sample_fixed_offset_latency(&self) -> AltoResult<(i64, Duration)> { self.src.soft_sample_fixed_offset_latency() }
 
 	#[inline] fn soft_sec_length(&self) -> AltoResult<f32> { self.src.soft_sec_length() }
 
@@ -1740,6 +4764,9 @@ unsafe impl Source for StreamingSource {
 	#[inline] fn soft_direct_channels(&self) -> bool { self.src.soft_direct_channels() }
 	#[inline] fn set_soft_direct_channels(&mut self, value: bool) -> AltoResult<()> { self.src.set_soft_direct_channels(value) }
 
+	#[inline] fn soft_direct_channels_mode(&self) -> AltoResult<SoftDirectChannelsMode> { self.src.soft_direct_channels_mode() }
+	#[inline] fn set_soft_direct_channels_mode(&mut self, value: SoftDirectChannelsMode) -> AltoResult<()> { self.src.set_soft_direct_channels_mode(value) }
+
 	#[inline] fn distance_model(&self) -> DistanceModel { self.src.distance_model() }
 	#[inline] fn set_distance_model(&mut self, value: DistanceModel) -> AltoResult<()> { self.src.set_distance_model(value) }
 
@@ -1751,6 +4778,8 @@ unsafe impl Source for StreamingSource {
 
 	#[inline] fn stereo_angles<V: From<[f32; 2]>>(&self) -> AltoResult<V> { self.src.stereo_angles() }
 	#[inline] fn set_stereo_angles<V: Into<[f32; 2]>>(&mut self, value: V) -> AltoResult<()> { self.src.set_stereo_angles(value) }
+	#[inline] fn pan(&self) -> AltoResult<f32> { self.src.pan() }
+	#[inline] fn set_pan(&mut self, value: f32) -> AltoResult<()> { self.src.set_pan(value) }
 
 	#[inline] fn radius(&self) -> f32 { self.src.radius() }
 	#[inline] fn set_radius(&self, value: f32) -> AltoResult<()> { self.src.set_radius(value) }
@@ -1758,6 +4787,7 @@ unsafe impl Source for StreamingSource {
 	#[inline] fn set_direct_filter<F: Filter>(&mut self, value: &F) -> AltoResult<()> { self.src.set_direct_filter(value) }
 	#[inline] fn clear_direct_filter(&mut self) { self.src.clear_direct_filter() }
 
+	#[inline] fn max_auxiliary_sends(&self) -> AltoResult<sys::ALint> { self.src.max_auxiliary_sends() }
 	#[inline] fn set_aux_send(&mut self, send: sys::ALint, slot: &mut AuxEffectSlot) -> AltoResult<()> { SourceInner::set_aux_send(&self.src, send, slot) }
 	#[inline] fn set_aux_send_filter<F: Filter>(&mut self, send: sys::ALint, slot: &mut AuxEffectSlot, filter: &F) -> AltoResult<()> { SourceInner::set_aux_send_filter(&self.src, send, slot, filter) }
 	#[inline] fn clear_aux_send(&mut self, send: sys::ALint) { self.src.clear_aux_send(send) }
@@ -1782,3 +4812,187 @@ impl PartialEq for StreamingSource {
 	}
 }
 impl Eq for StreamingSource { }
+
+
+macro_rules! any_source {
+	($s:expr, $src:ident => $e:expr) => {
+		match $s {
+			AnySource::Static($src) => $e,
+			AnySource::Streaming($src) => $e,
+		}
+	};
+}
+
+
+impl AnySource {
+	/// The static source, if this is one.
+	pub fn as_static(&self) -> Option<&StaticSource> {
+		match *self { AnySource::Static(ref src) => Some(src), _ => None }
+	}
+	/// The static source, if this is one.
+	pub fn as_static_mut(&mut self) -> Option<&mut StaticSource> {
+		match *self { AnySource::Static(ref mut src) => Some(src), _ => None }
+	}
+	/// The streaming source, if this is one.
+	pub fn as_streaming(&self) -> Option<&StreamingSource> {
+		match *self { AnySource::Streaming(ref src) => Some(src), _ => None }
+	}
+	/// The streaming source, if this is one.
+	pub fn as_streaming_mut(&mut self) -> Option<&mut StreamingSource> {
+		match *self { AnySource::Streaming(ref mut src) => Some(src), _ => None }
+	}
+}
+
+
+unsafe impl Source for AnySource {
+	#[inline] fn context(&self) -> &Context { any_source!(self, src => src.context()) }
+	#[inline] fn as_raw(&self) -> sys::ALuint { any_source!(self, src => src.as_raw()) }
+	#[inline] fn handle(&self) -> SourceHandle { any_source!(self, src => src.handle()) }
+	#[inline] fn update(&mut self) -> SourceUpdate<'_> { any_source!(self, src => src.update()) }
+
+	#[inline] fn state(&self) -> SourceState { any_source!(self, src => src.state()) }
+	#[inline] fn source_type(&self) -> AltoResult<SourceType> { any_source!(self, src => src.source_type()) }
+	#[inline] fn play(&mut self) { any_source!(self, src => src.play()) }
+	#[inline] fn wait_until(&self, state: SourceState, timeout: Duration, poll: Duration) -> AltoResult<bool> { any_source!(self, src => src.wait_until(state, timeout, poll)) }
+	#[inline] fn wait_until_stopped(&self, timeout: Duration) -> AltoResult<bool> { any_source!(self, src => src.wait_until_stopped(timeout)) }
+	#[inline] fn soft_play_at_time(&mut self, time: i64) -> AltoResult<()> { any_source!(self, src => src.soft_play_at_time(time)) }
+	#[inline] fn pause(&mut self) { any_source!(self, src => src.pause()) }
+	#[inline] fn stop(&mut self) { any_source!(self, src => src.stop()) }
+	#[inline] fn stop_with_fade(&mut self, duration: Duration) -> AltoResult<()> { any_source!(self, src => src.stop_with_fade(duration)) }
+	#[inline] fn rewind(&mut self) { any_source!(self, src => src.rewind()) }
+	#[inline] fn buffer_raw(&self) -> AltoResult<Option<sys::ALuint>> { any_source!(self, src => src.buffer_raw()) }
+
+	#[inline] fn relative(&self) -> bool { any_source!(self, src => src.relative()) }
+	#[inline] fn set_relative(&mut self, value: bool) { any_source!(self, src => src.set_relative(value)) }
+
+	#[inline] fn gain(&self) -> f32 { any_source!(self, src => src.gain()) }
+	#[inline] fn set_gain(&mut self, value: f32) -> AltoResult<()> { any_source!(self, src => src.set_gain(value)) }
+	#[inline] fn gain_step(&mut self, target: f32, dt: f32, rate: f32) -> AltoResult<bool> { any_source!(self, src => src.gain_step(target, dt, rate)) }
+	#[inline] fn fade_gain_to(&mut self, target: f32, duration: Duration) -> AltoResult<FadeHandle> { any_source!(self, src => src.fade_gain_to(target, duration)) }
+	#[inline] fn is_muted(&self) -> bool { any_source!(self, src => src.is_muted()) }
+	#[inline] fn set_muted(&mut self, value: bool) -> AltoResult<()> { any_source!(self, src => src.set_muted(value)) }
+
+	#[inline] fn min_gain(&self) -> f32 { any_source!(self, src => src.min_gain()) }
+	#[inline] fn set_min_gain(&mut self, value: f32) -> AltoResult<()> { any_source!(self, src => src.set_min_gain(value)) }
+
+	#[inline] fn max_gain(&self) -> f32 { any_source!(self, src => src.max_gain()) }
+	#[inline] fn set_max_gain(&mut self, value: f32) -> AltoResult<()> { any_source!(self, src => src.set_max_gain(value)) }
+
+	#[inline] fn reference_distance(&self) -> f32 { any_source!(self, src => src.reference_distance()) }
+	#[inline] fn set_reference_distance(&mut self, value: f32) -> AltoResult<()> { any_source!(self, src => src.set_reference_distance(value)) }
+
+	#[inline] fn rolloff_factor(&self) -> f32 { any_source!(self, src => src.rolloff_factor()) }
+	#[inline] fn set_rolloff_factor(&mut self, value: f32) -> AltoResult<()> { any_source!(self, src => src.set_rolloff_factor(value)) }
+
+	#[inline] fn max_distance(&self) -> f32 { any_source!(self, src => src.max_distance()) }
+	#[inline] fn set_max_distance(&mut self, value: f32) -> AltoResult<()> { any_source!(self, src => src.set_max_distance(value)) }
+
+	#[inline] fn attenuation(&self) -> Attenuation { any_source!(self, src => src.attenuation()) }
+	#[inline] fn set_attenuation(&mut self, value: Attenuation) -> AltoResult<()> { any_source!(self, src => src.set_attenuation(value)) }
+
+	#[inline] fn pitch(&self) -> f32 { any_source!(self, src => src.pitch()) }
+	#[inline] fn set_pitch(&mut self, value: f32) -> AltoResult<()> { any_source!(self, src => src.set_pitch(value)) }
+
+	#[inline] fn position<V: From<[f32; 3]>>(&self) -> V { any_source!(self, src => src.position()) }
+	#[inline] fn set_position<V: AsAlVec3>(&mut self, value: V) -> AltoResult<()> { any_source!(self, src => src.set_position(value)) }
+
+	#[inline] fn velocity<V: From<[f32; 3]>>(&self) -> V { any_source!(self, src => src.velocity()) }
+	#[inline] fn set_velocity<V: AsAlVec3>(&mut self, value: V) -> AltoResult<()> { any_source!(self, src => src.set_velocity(value)) }
+	#[inline] fn update_position_with_velocity<V: AsAlVec3>(&mut self, pos: V, dt: f32) -> AltoResult<()> { any_source!(self, src => src.update_position_with_velocity(pos, dt)) }
+	#[inline] fn set_head_locked<V: AsAlVec3>(&mut self, offset: V) -> AltoResult<()> { any_source!(self, src => src.set_head_locked(offset)) }
+	#[inline] fn detach_to_world<V: AsAlVec3>(&mut self, listener_pos: V, listener_orientation: (V, V)) -> AltoResult<()> { any_source!(self, src => src.detach_to_world(listener_pos, listener_orientation)) }
+
+	#[inline] fn direction<V: From<[f32; 3]>>(&self) -> V { any_source!(self, src => src.direction()) }
+	#[inline] fn set_direction<V: AsAlVec3>(&mut self, value: V) -> AltoResult<()> { any_source!(self, src => src.set_direction(value)) }
+
+	#[inline] fn cone_inner_angle(&self) -> f32 { any_source!(self, src => src.cone_inner_angle()) }
+	#[inline] fn set_cone_inner_angle(&mut self, value: f32) -> AltoResult<()> { any_source!(self, src => src.set_cone_inner_angle(value)) }
+
+	#[inline] fn cone_outer_angle(&self) -> f32 { any_source!(self, src => src.cone_outer_angle()) }
+	#[inline] fn set_cone_outer_angle(&mut self, value: f32) -> AltoResult<()> { any_source!(self, src => src.set_cone_outer_angle(value)) }
+
+	#[inline] fn cone_outer_gain(&self) -> f32 { any_source!(self, src => src.cone_outer_gain()) }
+	#[inline] fn set_cone_outer_gain(&mut self, value: f32) -> AltoResult<()> { any_source!(self, src => src.set_cone_outer_gain(value)) }
+	#[inline] fn cone(&self) -> (Angle, Angle, f32) { any_source!(self, src => src.cone()) }
+	#[inline] fn set_cone(&mut self, inner: Angle, outer: Angle, outer_gain: f32) -> AltoResult<()> { any_source!(self, src => src.set_cone(inner, outer, outer_gain)) }
+
+	#[inline] fn sec_offset(&self) -> f32 { any_source!(self, src => src.sec_offset()) }
+	#[inline] fn set_sec_offset(&mut self, value: f32) -> AltoResult<()> { any_source!(self, src => src.set_sec_offset(value)) }
+
+	#[inline] fn sample_offset(&self) -> sys::ALint { any_source!(self, src => src.sample_offset()) }
+	#[inline] fn set_sample_offset(&mut self, value: sys::ALint) -> AltoResult<()> { any_source!(self, src => src.set_sample_offset(value)) }
+
+	#[inline] fn byte_offset(&self) -> sys::ALint { any_source!(self, src => src.byte_offset()) }
+	#[inline] fn set_byte_offset(&mut self, value: sys::ALint) -> AltoResult<()> { any_source!(self, src => src.set_byte_offset(value)) }
+	#[inline] fn seek(&mut self, pos: SeekPos) -> AltoResult<()> { any_source!(self, src => src.seek(pos)) }
+	#[inline] fn tell(&self) -> SeekPos { any_source!(self, src => src.tell()) }
+	#[inline] fn playback_position(&self) -> AltoResult<Duration> { any_source!(self, src => src.playback_position()) }
+	#[inline] fn snapshot(&self) -> AltoResult<SourceSnapshot> { any_source!(self, src => src.snapshot()) }
+	#[inline] fn apply_snapshot(&mut self, value: &SourceSnapshot) -> AltoResult<()> { any_source!(self, src => src.apply_snapshot(value)) }
+
+	#[inline] fn soft_sec_offset_latency(&self) -> AltoResult<(f64, f64)> { any_source!(self, src => src.soft_sec_offset_latency()) }
+	#[inline] fn soft_position_f64<V: From<[f64; 3]>>(&self) -> AltoResult<V> { any_source!(self, src => src.soft_position_f64()) }
+	#[inline] fn set_soft_position_f64<V: Into<[f64; 3]>>(&mut self, value: V) -> AltoResult<()> { any_source!(self, src => src.set_soft_position_f64(value)) }
+	#[inline] fn soft_velocity_f64<V: From<[f64; 3]>>(&self) -> AltoResult<V> { any_source!(self, src => src.soft_velocity_f64()) }
+	#[inline] fn set_soft_velocity_f64<V: Into<[f64; 3]>>(&mut self, value: V) -> AltoResult<()> { any_source!(self, src => src.set_soft_velocity_f64(value)) }
+
+	#[inline] fn soft_sample_frac_offset_latency(&self) -> AltoResult<(i64, Duration)> { any_source!(self, src => src.soft_sample_frac_offset_latency()) }
+	#[inline] fn soft_sample_fixed_offset_latency(&self) -> AltoResult<(i64, Duration)> { any_source!(self, src => src.soft_sample_fixed_offset_latency()) }
+
+	#[inline] fn soft_sec_length(&self) -> AltoResult<f32> { any_source!(self, src => src.soft_sec_length()) }
+
+	#[inline] fn soft_sample_length(&self) -> AltoResult<sys::ALint> { any_source!(self, src => src.soft_sample_length()) }
+
+	#[inline] fn soft_byte_length(&self) -> AltoResult<sys::ALint> { any_source!(self, src => src.soft_byte_length()) }
+
+	#[inline] fn soft_direct_channels(&self) -> bool { any_source!(self, src => src.soft_direct_channels()) }
+	#[inline] fn set_soft_direct_channels(&mut self, value: bool) -> AltoResult<()> { any_source!(self, src => src.set_soft_direct_channels(value)) }
+
+	#[inline] fn soft_direct_channels_mode(&self) -> AltoResult<SoftDirectChannelsMode> { any_source!(self, src => src.soft_direct_channels_mode()) }
+	#[inline] fn set_soft_direct_channels_mode(&mut self, value: SoftDirectChannelsMode) -> AltoResult<()> { any_source!(self, src => src.set_soft_direct_channels_mode(value)) }
+
+	#[inline] fn distance_model(&self) -> DistanceModel { any_source!(self, src => src.distance_model()) }
+	#[inline] fn set_distance_model(&mut self, value: DistanceModel) -> AltoResult<()> { any_source!(self, src => src.set_distance_model(value)) }
+
+	#[inline] fn soft_spatialization(&self) -> SoftSourceSpatialization { any_source!(self, src => src.soft_spatialization()) }
+	#[inline] fn set_soft_spatialization(&mut self, value: SoftSourceSpatialization) -> AltoResult<()> { any_source!(self, src => src.set_soft_spatialization(value)) }
+
+	#[inline] fn soft_resampler(&self) -> AltoResult<sys::ALint> { any_source!(self, src => src.soft_resampler()) }
+	#[inline] fn set_soft_resampler(&mut self, value: sys::ALint) -> AltoResult<()> { any_source!(self, src => src.set_soft_resampler(value)) }
+
+	#[inline] fn stereo_angles<V: From<[f32; 2]>>(&self) -> AltoResult<V> { any_source!(self, src => src.stereo_angles()) }
+	#[inline] fn set_stereo_angles<V: Into<[f32; 2]>>(&mut self, value: V) -> AltoResult<()> { any_source!(self, src => src.set_stereo_angles(value)) }
+	#[inline] fn pan(&self) -> AltoResult<f32> { any_source!(self, src => src.pan()) }
+	#[inline] fn set_pan(&mut self, value: f32) -> AltoResult<()> { any_source!(self, src => src.set_pan(value)) }
+
+	#[inline] fn radius(&self) -> f32 { any_source!(self, src => src.radius()) }
+	#[inline] fn set_radius(&self, value: f32) -> AltoResult<()> { any_source!(self, src => src.set_radius(value)) }
+
+	#[inline] fn set_direct_filter<F: Filter>(&mut self, value: &F) -> AltoResult<()> { any_source!(self, src => src.set_direct_filter(value)) }
+	#[inline] fn clear_direct_filter(&mut self) { any_source!(self, src => src.clear_direct_filter()) }
+
+	#[inline] fn max_auxiliary_sends(&self) -> AltoResult<sys::ALint> { any_source!(self, src => src.max_auxiliary_sends()) }
+	#[inline] fn set_aux_send(&mut self, send: sys::ALint, slot: &mut AuxEffectSlot) -> AltoResult<()> { any_source!(self, src => src.set_aux_send(send, slot)) }
+	#[inline] fn set_aux_send_filter<F: Filter>(&mut self, send: sys::ALint, slot: &mut AuxEffectSlot, filter: &F) -> AltoResult<()> { any_source!(self, src => src.set_aux_send_filter(send, slot, filter)) }
+	#[inline] fn clear_aux_send(&mut self, send: sys::ALint) { any_source!(self, src => src.clear_aux_send(send)) }
+
+	#[inline] fn air_absorption_factor(&self) -> f32 { any_source!(self, src => src.air_absorption_factor()) }
+	#[inline] fn set_air_absorption_factor(&mut self, value: f32) -> AltoResult<()> { any_source!(self, src => src.set_air_absorption_factor(value)) }
+
+	#[inline] fn room_rolloff_factor(&self) -> f32 { any_source!(self, src => src.room_rolloff_factor()) }
+	#[inline] fn set_room_rolloff_factor(&mut self, value: f32) -> AltoResult<()> { any_source!(self, src => src.set_room_rolloff_factor(value)) }
+
+	#[inline] fn cone_outer_gainhf(&self) -> f32 { any_source!(self, src => src.cone_outer_gainhf()) }
+	#[inline] fn set_cone_outer_gainhf(&mut self, value: f32) -> AltoResult<()> { any_source!(self, src => src.set_cone_outer_gainhf(value)) }
+
+	#[inline] fn direct_filter_gainhf_auto(&self) -> bool { any_source!(self, src => src.direct_filter_gainhf_auto()) }
+	#[inline] fn set_direct_filter_gainhf_auto(&mut self, value: bool) -> AltoResult<()> { any_source!(self, src => src.set_direct_filter_gainhf_auto(value)) }
+}
+
+
+impl From<StaticSource> for AnySource {
+	fn from(src: StaticSource) -> AnySource { AnySource::Static(src) }
+}
+impl From<StreamingSource> for AnySource {
+	fn from(src: StreamingSource) -> AnySource { AnySource::Streaming(src) }
+}
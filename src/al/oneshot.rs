@@ -0,0 +1,385 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use ::{AltoError, AltoResult};
+use sys;
+use al::*;
+
+
+/// Settings for a sound started by `Context::play_once`.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct PlayParams {
+	/// `AL_GAIN`. Defaults to 1.0.
+	pub gain: f32,
+	/// `AL_PITCH`. Defaults to 1.0.
+	pub pitch: f32,
+	/// `AL_POSITION`, or `None` to play at the listener with `AL_SOURCE_RELATIVE`. Defaults to `None`.
+	pub position: Option<[f32; 3]>,
+}
+
+
+/// What `Context::play_once` does when every pooled source is busy and the pool is at its limit.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum PlayOncePolicy {
+	/// Cut off the sound that was started longest ago and reuse its source.
+	StealOldest,
+	/// Fail with `AltoError::VoicesExhausted`.
+	Fail,
+}
+
+
+/// Identifies a sound started by `Context::play_once`, for `Context::stop_once` and `Context::is_playing_once`.
+/// It holds no resources, so it can be dropped if the sound never needs to be stopped early.
+/// Once its sound has finished or been stolen, the token no longer matches anything.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct PlayToken {
+	src: sys::ALuint,
+	serial: u64,
+}
+
+
+/// Limits how much work a call of `Context::reap_within` may do, so that it can run every frame.
+/// Whatever is left over is picked up by the next call. The default is unlimited.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct ReapBudget {
+	/// Most pooled sources and fades to examine.
+	pub max_items: Option<usize>,
+	/// Time after which no further items are examined. Checked between items, so it may be overrun by one.
+	pub max_time: Option<Duration>,
+}
+
+
+/// What a call of `Context::reap` or `Context::reap_within` cleaned up.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct ReapStats {
+	/// Pooled sources of `play_once` whose sounds had finished and were returned to idle.
+	pub sources: usize,
+	/// Gain fades that had completed, or whose source had stopped, and were finalized.
+	pub fades: usize,
+	/// Distinct buffers that no pooled source holds any more once the finished sounds were detached.
+	pub buffers: usize,
+	/// Whether everything was examined, rather than the budget running out first.
+	pub complete: bool,
+}
+
+
+/// The remaining budget of a single reap.
+pub(crate) struct Allowance {
+	items: Option<usize>,
+	deadline: Option<Instant>,
+}
+
+
+/// The pooled sources of a context behind `Context::play_once`.
+/// They are kept as raw names so the pool holds no reference to its own context, and are deleted along with it.
+pub(crate) struct OneShotPool {
+	limit: usize,
+	policy: PlayOncePolicy,
+	next_serial: u64,
+	voices: Vec<OneShot>,
+	/// The voice at which a budgeted reap resumes.
+	cursor: usize,
+}
+
+
+struct OneShot {
+	src: sys::ALuint,
+	/// The buffer playing on the source, or 0 if it is idle.
+	buf: sys::ALuint,
+	serial: u64,
+}
+
+
+impl Default for PlayParams {
+	fn default() -> PlayParams {
+		PlayParams{gain: 1.0, pitch: 1.0, position: None}
+	}
+}
+
+
+impl OneShotPool {
+	pub(crate) fn new() -> OneShotPool {
+		OneShotPool{limit: 16, policy: PlayOncePolicy::StealOldest, next_serial: 0, voices: Vec::new(), cursor: 0}
+	}
+
+
+	/// Detach the buffer from every voice that has stopped, and delete idle sources beyond the limit.
+	fn reap(&mut self, ctx: &Context) -> AltoResult<usize> {
+		self.sweep(ctx, &mut Allowance::unlimited()).map(|stats| stats.sources)
+	}
+
+
+	/// `reap` within `allowance`, resuming from where the last sweep that ran out stopped.
+	/// Fills in `sources`, `buffers` and `complete` of the returned stats.
+	pub(crate) fn sweep(&mut self, ctx: &Context, allowance: &mut Allowance) -> AltoResult<ReapStats> {
+		let mut stats = ReapStats{complete: true, ..ReapStats::default()};
+		if self.voices.iter().all(|v| v.buf == 0) && self.voices.len() <= self.limit {
+			return Ok(stats);
+		}
+
+		let _lock = ctx.make_current(true);
+		let api = &ctx.0.dev.0.alto.0.api;
+		let mut released = Vec::new();
+		let len = self.voices.len();
+		for i in 0..len {
+			let index = (self.cursor + i) % len;
+			if self.voices[index].buf == 0 {
+				continue;
+			}
+			if !allowance.take() {
+				self.cursor = index;
+				stats.complete = false;
+				break;
+			}
+
+			let voice = &mut self.voices[index];
+			let mut state = 0;
+			unsafe { api.alGetSourcei(voice.src, sys::AL_SOURCE_STATE, &mut state); }
+			if state != sys::AL_PLAYING && state != sys::AL_PAUSED {
+				unsafe { api.alSourcei(voice.src, sys::AL_BUFFER, 0); }
+				released.push(voice.buf);
+				voice.buf = 0;
+				stats.sources += 1;
+			}
+		}
+		if stats.complete {
+			self.cursor = 0;
+		}
+
+		released.sort();
+		released.dedup();
+		stats.buffers = released.iter().filter(|&&buf| self.voices.iter().all(|v| v.buf != buf)).count();
+
+		while self.voices.len() > self.limit {
+			let idle = match self.voices.iter().position(|v| v.buf == 0) {
+				Some(idle) => idle,
+				None => break,
+			};
+			let voice = self.voices.swap_remove(idle);
+			unsafe { api.alDeleteSources(1, &voice.src); }
+		}
+		if self.cursor >= self.voices.len() {
+			self.cursor = 0;
+		}
+
+		ctx.get_error().map(|_| stats)
+	}
+
+
+	/// Pick the voice for a new sound, generating a source or stealing one if none is idle.
+	fn acquire(&mut self, ctx: &Context) -> AltoResult<usize> {
+		if let Some(idle) = self.voices.iter().position(|v| v.buf == 0) {
+			return Ok(idle);
+		}
+
+		if self.voices.len() < self.limit {
+			if let Some(src) = exhausted(gen_source(ctx))? {
+				self.voices.push(OneShot{src, buf: 0, serial: 0});
+				return Ok(self.voices.len() - 1);
+			}
+		}
+
+		match self.policy {
+			PlayOncePolicy::StealOldest => {
+				self.voices.iter().enumerate()
+					.min_by_key(|&(_, v)| v.serial)
+					.map(|(i, _)| i)
+					.ok_or(AltoError::VoicesExhausted)
+			},
+			PlayOncePolicy::Fail => Err(AltoError::VoicesExhausted),
+		}
+	}
+
+
+	/// The sources of the pool that are playing, or were until they finished, a sound.
+	pub(crate) fn busy_sources<'a>(&'a self) -> impl Iterator<Item=sys::ALuint> + 'a {
+		self.voices.iter().filter(|v| v.buf != 0).map(|v| v.src)
+	}
+
+
+	fn find(&mut self, token: PlayToken) -> Option<&mut OneShot> {
+		self.voices.iter_mut().find(|v| v.src == token.src && v.serial == token.serial && v.buf != 0)
+	}
+}
+
+
+impl Context {
+	/// `alSourcePlay()`
+	/// Play `buf` once on a source from a pool owned by the context, with no source object to manage.
+	/// Sounds that have finished are reclaimed by a sweep at the start of each call, or by `reap`.
+	/// When every pooled source is busy, another is generated until the limit set by `set_play_once_limit`
+	/// is reached or the implementation runs out, after which the policy decides whether the oldest sound
+	/// is cut off or `AltoError::VoicesExhausted` is returned. The parameters are applied in one deferred batch.
+	///
+	/// The buffer must belong to this context rather than merely share its device, since dropping it stops
+	/// any sound still playing it. The pooled sources are deleted with the context.
+	pub fn play_once(&self, buf: &Buffer, params: PlayParams) -> AltoResult<PlayToken> {
+		if !Arc::ptr_eq(&self.0, &buf.ctx.0) {
+			return Err(AltoError::WrongContext);
+		}
+		self.check_buffer(buf)?;
+		let position_ok = params.position.iter().flatten().all(|c| c.is_finite());
+		if !params.gain.is_finite() || params.gain < 0.0 || !params.pitch.is_finite() || params.pitch <= 0.0 || !position_ok {
+			return Err(AltoError::InvalidValue);
+		}
+
+		let _defer = self.defer_updates();
+		let mut pool = self.0.one_shots.lock();
+		pool.reap(self)?;
+		let index = pool.acquire(self)?;
+		let serial = pool.next_serial;
+		pool.next_serial += 1;
+
+		let voice = &mut pool.voices[index];
+		voice.buf = buf.buf;
+		voice.serial = serial;
+
+		let (relative, position) = match params.position {
+			Some(position) => (sys::AL_FALSE, position),
+			None => (sys::AL_TRUE, [0.0, 0.0, 0.0]),
+		};
+		let _lock = self.make_current(true);
+		let api = &self.0.dev.0.alto.0.api;
+		unsafe {
+			api.alSourceStop(voice.src);
+			api.alSourcei(voice.src, sys::AL_BUFFER, buf.buf as sys::ALint);
+			api.alSourcef(voice.src, sys::AL_GAIN, params.gain);
+			api.alSourcef(voice.src, sys::AL_PITCH, params.pitch);
+			api.alSourcei(voice.src, sys::AL_SOURCE_RELATIVE, relative as sys::ALint);
+			api.alSource3f(voice.src, sys::AL_POSITION, position[0], position[1], position[2]);
+			api.alSourcePlay(voice.src);
+		}
+		self.get_error()?;
+
+		Ok(PlayToken{src: voice.src, serial})
+	}
+
+
+	/// Reclaim the pooled sources of `play_once` whose sounds have finished, and finalize gain fades that
+	/// have completed or whose source has stopped, without advancing any others. `play_once` and `tick`
+	/// do this themselves, so calling it is only needed to release finished buffers and fades sooner.
+	pub fn reap(&self) -> AltoResult<ReapStats> {
+		self.reap_within(ReapBudget::default())
+	}
+	/// `reap` limited by `budget`, so that it can be called every frame. Pooled sources are examined
+	/// before fades, and the next call resumes where this one ran out.
+	pub fn reap_within(&self, budget: ReapBudget) -> AltoResult<ReapStats> {
+		let mut allowance = Allowance::new(budget);
+		let mut stats = self.0.one_shots.lock().sweep(self, &mut allowance)?;
+		if stats.complete {
+			let (fades, complete) = self.finalize_fades(&mut allowance)?;
+			stats.fades = fades;
+			stats.complete = complete;
+		}
+		Ok(stats)
+	}
+
+
+	/// `alSourceStop()`
+	/// Stop a sound started by `play_once` and reclaim its source, returning whether it was still playing.
+	pub fn stop_once(&self, token: PlayToken) -> AltoResult<bool> {
+		let mut pool = self.0.one_shots.lock();
+		let voice = match pool.find(token) {
+			Some(voice) => voice,
+			None => return Ok(false),
+		};
+
+		let _lock = self.make_current(true);
+		let api = &self.0.dev.0.alto.0.api;
+		let mut state = 0;
+		unsafe {
+			api.alGetSourcei(voice.src, sys::AL_SOURCE_STATE, &mut state);
+			api.alSourceStop(voice.src);
+			api.alSourcei(voice.src, sys::AL_BUFFER, 0);
+		}
+		voice.buf = 0;
+		self.get_error().map(|_| state == sys::AL_PLAYING)
+	}
+
+
+	/// Whether a sound started by `play_once` is still playing.
+	pub fn is_playing_once(&self, token: PlayToken) -> bool {
+		let mut pool = self.0.one_shots.lock();
+		let voice = match pool.find(token) {
+			Some(voice) => voice,
+			None => return false,
+		};
+
+		let _lock = self.make_current(true);
+		let mut state = 0;
+		unsafe { self.0.dev.0.alto.0.api.alGetSourcei(voice.src, sys::AL_SOURCE_STATE, &mut state); }
+		state == sys::AL_PLAYING
+	}
+
+
+	/// The most sources `play_once` will keep in its pool, and what it does once they are all busy.
+	pub fn play_once_limit(&self) -> (usize, PlayOncePolicy) {
+		let pool = self.0.one_shots.lock();
+		(pool.limit, pool.policy)
+	}
+	/// Set the pool limit and policy of `play_once`. Defaults to 16 sources and `PlayOncePolicy::StealOldest`.
+	/// Lowering the limit deletes idle sources right away, and busy ones once their sounds finish.
+	pub fn set_play_once_limit(&self, limit: usize, policy: PlayOncePolicy) -> AltoResult<()> {
+		if limit == 0 {
+			return Err(AltoError::InvalidValue);
+		}
+
+		let mut pool = self.0.one_shots.lock();
+		pool.limit = limit;
+		pool.policy = policy;
+		pool.reap(self).map(|_| ())
+	}
+
+
+	/// Stop and reclaim every `play_once` sound playing `buf`, so that the buffer can be deleted.
+	pub(crate) fn release_one_shots(&self, buf: sys::ALuint) {
+		let mut pool = self.0.one_shots.lock();
+		if pool.voices.iter().all(|v| v.buf != buf) {
+			return;
+		}
+
+		let _lock = self.make_current(true);
+		let api = &self.0.dev.0.alto.0.api;
+		for voice in pool.voices.iter_mut().filter(|v| v.buf == buf) {
+			unsafe {
+				api.alSourceStop(voice.src);
+				api.alSourcei(voice.src, sys::AL_BUFFER, 0);
+			}
+			voice.buf = 0;
+		}
+	}
+}
+
+
+impl Allowance {
+	pub(crate) fn new(budget: ReapBudget) -> Allowance {
+		Allowance{items: budget.max_items, deadline: budget.max_time.map(|time| Instant::now() + time)}
+	}
+	pub(crate) fn unlimited() -> Allowance {
+		Allowance{items: None, deadline: None}
+	}
+
+
+	/// Spend one item, returning `false` if the budget has run out.
+	pub(crate) fn take(&mut self) -> bool {
+		if self.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+			return false;
+		}
+		match self.items {
+			Some(0) => false,
+			Some(ref mut items) => {
+				*items -= 1;
+				true
+			},
+			None => true,
+		}
+	}
+}
+
+
+fn gen_source(ctx: &Context) -> AltoResult<sys::ALuint> {
+	let _lock = ctx.make_current(true);
+	let mut src = 0;
+	unsafe { ctx.0.dev.0.alto.0.api.alGenSources(1, &mut src); }
+	ctx.get_error().map(|_| src)
+}
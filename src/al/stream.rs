@@ -0,0 +1,332 @@
+use std::io::{self, Read};
+use std::marker::PhantomData;
+use std::mem;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender, TryRecvError};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use ::{AltoError, AltoResult};
+use sys;
+use al::*;
+
+
+/// A source of sample frames to be played by `StreamingSource::attach_stream`, such as a decoder.
+pub trait SampleStream<F: SampleFrame> {
+	/// Fill the start of `out` with the next frames and return how many were written.
+	/// Returning 0 signals the end of the stream.
+	fn read(&mut self, out: &mut [F]) -> usize;
+	/// Continue the stream from the sample frame `frame`, returning whether this is supported.
+	/// Used to loop the stream and by `StreamHandle::seek`. The default does not support seeking.
+	fn seek(&mut self, _frame: u64) -> bool { false }
+}
+
+
+/// Settings for `StreamingSource::attach_stream`.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct StreamOptions {
+	/// Frequency of the stream's frames. Defaults to 44100.
+	pub frequency: sys::ALint,
+	/// Number of buffers rotated between the feeder thread and the source. Defaults to 4.
+	pub buffers: usize,
+	/// Number of sample frames read from the stream into each buffer. Defaults to 4096.
+	pub frames_per_buffer: usize,
+	/// Seek the stream back to its start when it ends, if it supports seeking. Defaults to `false`.
+	pub looping: bool,
+}
+
+
+/// Layout of the raw interleaved little-endian PCM read by `StreamingSource::feed_reader`.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct PcmFormat {
+	/// Sample type and channel count of each frame.
+	pub format: StandardFormat,
+	/// Frequency of the frames.
+	pub frequency: sys::ALint,
+}
+
+
+/// Controls a stream played by a feeder thread, as started by `StreamingSource::attach_stream`.
+/// Dropping the handle stops playback and waits for the thread to exit.
+pub struct StreamHandle {
+	cmds: Sender<StreamCommand>,
+	finished: Arc<AtomicBool>,
+	thread: Option<JoinHandle<StreamingSource>>,
+}
+
+
+/// Adapts a reader of raw PCM bytes into a `SampleStream`.
+struct PcmReader<R, F> {
+	reader: R,
+	bytes: Vec<u8>,
+	eof: bool,
+	_frame: PhantomData<F>,
+}
+
+
+/// A frame that can be decoded from little-endian PCM bytes.
+trait PcmFrame: SampleFrame {
+	const BYTES: usize;
+
+	fn decode(bytes: &[u8]) -> Self;
+}
+
+
+enum StreamCommand {
+	Pause,
+	Resume,
+	Seek(u64),
+	Stop,
+}
+
+
+impl Default for StreamOptions {
+	fn default() -> StreamOptions {
+		StreamOptions{
+			frequency: 44100,
+			buffers: 4,
+			frames_per_buffer: 4096,
+			looping: false,
+		}
+	}
+}
+
+
+impl StreamingSource {
+	/// Spawn a thread that feeds this source from `stream` through a pool of `opts.buffers` buffers,
+	/// as set up by `with_pool`, and starts playing as soon as the first buffer is queued.
+	/// The thread checks for processed buffers twice per buffer duration and refills them, restarting
+	/// playback from the new audio if the source underran. Commands sent through the handle are
+	/// handled immediately.
+	pub fn attach_stream<F: SampleFrame, S: SampleStream<F> + Send + 'static>(mut self, stream: S, opts: StreamOptions) -> AltoResult<StreamHandle> {
+		if opts.frequency <= 0 {
+			return Err(AltoError::InvalidValue);
+		}
+		self.with_pool(opts.buffers, opts.frames_per_buffer)?;
+		self.set_auto_restart(true);
+
+		let (cmds, recv) = mpsc::channel();
+		let finished = Arc::new(AtomicBool::new(false));
+		let thread_finished = finished.clone();
+		let thread = thread::spawn(move || feed(self, stream, opts, recv, thread_finished));
+
+		Ok(StreamHandle{cmds, finished, thread: Some(thread)})
+	}
+
+
+	/// Play interleaved little-endian PCM read from `reader` through `attach_stream`, using the default
+	/// `StreamOptions` at `format.frequency`.
+	/// Short reads are retried until a buffer is full. When the reader reaches EOF or fails, any trailing
+	/// bytes that do not complete a frame are dropped, and the source stops once the queued audio has played.
+	pub fn feed_reader<R: Read + Send + 'static>(self, reader: R, format: PcmFormat) -> AltoResult<StreamHandle> {
+		let opts = StreamOptions{frequency: format.frequency, ..StreamOptions::default()};
+		match format.format {
+			StandardFormat::MonoU8 => self.attach_stream(PcmReader::<R, Mono<u8>>::new(reader), opts),
+			StandardFormat::MonoI16 => self.attach_stream(PcmReader::<R, Mono<i16>>::new(reader), opts),
+			StandardFormat::StereoU8 => self.attach_stream(PcmReader::<R, Stereo<u8>>::new(reader), opts),
+			StandardFormat::StereoI16 => self.attach_stream(PcmReader::<R, Stereo<i16>>::new(reader), opts),
+		}
+	}
+}
+
+
+impl StreamHandle {
+	/// Pause playback. The feeder keeps the queue full so that playback resumes without a gap.
+	pub fn pause(&self) { let _ = self.cmds.send(StreamCommand::Pause); }
+	/// Resume playback after `pause`.
+	pub fn resume(&self) { let _ = self.cmds.send(StreamCommand::Resume); }
+	/// Discard the queued audio and continue from the sample frame `frame`, keeping the paused state.
+	/// Ignored if the stream does not support seeking.
+	pub fn seek(&self, frame: u64) { let _ = self.cmds.send(StreamCommand::Seek(frame)); }
+
+
+	/// Whether the stream has ended and all of its audio has played. A looping stream never finishes.
+	/// Seeking a finished stream starts it again.
+	pub fn is_finished(&self) -> bool { self.finished.load(Ordering::Acquire) }
+
+
+	/// Stop playback, wait for the feeder thread to exit and return the source with an empty queue,
+	/// or `None` if the thread panicked.
+	pub fn stop(mut self) -> Option<StreamingSource> {
+		self.shutdown()
+	}
+
+
+	fn shutdown(&mut self) -> Option<StreamingSource> {
+		let _ = self.cmds.send(StreamCommand::Stop);
+		self.thread.take().and_then(|t| t.join().ok())
+	}
+}
+
+
+impl Drop for StreamHandle {
+	fn drop(&mut self) {
+		self.shutdown();
+	}
+}
+
+
+impl<R: Read, F: PcmFrame> PcmReader<R, F> {
+	fn new(reader: R) -> PcmReader<R, F> {
+		PcmReader{reader, bytes: Vec::new(), eof: false, _frame: PhantomData}
+	}
+}
+
+
+impl<R: Read, F: PcmFrame> SampleStream<F> for PcmReader<R, F> {
+	fn read(&mut self, out: &mut [F]) -> usize {
+		let want = out.len() * F::BYTES;
+		self.bytes.resize(want, 0);
+
+		let mut len = 0;
+		while len < want && !self.eof {
+			match self.reader.read(&mut self.bytes[len..]) {
+				Ok(0) => self.eof = true,
+				Ok(read) => len += read,
+				Err(ref e) if e.kind() == io::ErrorKind::Interrupted => (),
+				Err(_) => self.eof = true,
+			}
+		}
+
+		let frames = len / F::BYTES;
+		for (frame, bytes) in out.iter_mut().zip(self.bytes[..frames * F::BYTES].chunks(F::BYTES)) {
+			*frame = F::decode(bytes);
+		}
+		frames
+	}
+}
+
+
+impl PcmFrame for Mono<u8> {
+	const BYTES: usize = 1;
+
+	fn decode(bytes: &[u8]) -> Mono<u8> { Mono{center: bytes[0]} }
+}
+
+
+impl PcmFrame for Mono<i16> {
+	const BYTES: usize = 2;
+
+	fn decode(bytes: &[u8]) -> Mono<i16> { Mono{center: i16::from_le_bytes([bytes[0], bytes[1]])} }
+}
+
+
+impl PcmFrame for Stereo<u8> {
+	const BYTES: usize = 2;
+
+	fn decode(bytes: &[u8]) -> Stereo<u8> { Stereo{left: bytes[0], right: bytes[1]} }
+}
+
+
+impl PcmFrame for Stereo<i16> {
+	const BYTES: usize = 4;
+
+	fn decode(bytes: &[u8]) -> Stereo<i16> {
+		Stereo{left: i16::from_le_bytes([bytes[0], bytes[1]]), right: i16::from_le_bytes([bytes[2], bytes[3]])}
+	}
+}
+
+
+fn feed<F: SampleFrame, S: SampleStream<F>>(mut src: StreamingSource, mut stream: S, opts: StreamOptions, recv: Receiver<StreamCommand>, finished: Arc<AtomicBool>) -> StreamingSource {
+	// Frames are plain samples, for which all zeroes is valid silence.
+	let mut frames = vec![unsafe { mem::zeroed::<F>() }; opts.frames_per_buffer];
+	let poll = Duration::from_secs_f64(opts.frames_per_buffer as f64 / opts.frequency as f64 / 2.0).max(Duration::from_millis(1));
+	let mut ended = false;
+	let mut paused = false;
+	let mut next = None;
+
+	loop {
+		loop {
+			let cmd = match next.take() {
+				Some(cmd) => cmd,
+				None => match recv.try_recv() {
+					Ok(cmd) => cmd,
+					Err(TryRecvError::Empty) => break,
+					Err(TryRecvError::Disconnected) => StreamCommand::Stop,
+				},
+			};
+
+			match cmd {
+				StreamCommand::Pause => {
+					paused = true;
+					src.pause();
+				},
+				StreamCommand::Resume => paused = false,
+				StreamCommand::Seek(frame) => {
+					if stream.seek(frame) {
+						src.stop();
+						let _ = src.reclaim();
+						ended = false;
+						finished.store(false, Ordering::Release);
+					}
+				},
+				StreamCommand::Stop => {
+					src.stop();
+					let _ = src.reclaim();
+					return src;
+				},
+			}
+		}
+
+		let streaming = matches!(src.reclaim(), Ok(PoolStatus::Streaming));
+		if ended && !streaming && src.buffers_queued() == 0 && !finished.load(Ordering::Acquire) {
+			src.stop();
+			finished.store(true, Ordering::Release);
+		}
+
+		while !ended {
+			let mut buf = match src.acquire_free() {
+				Some(buf) => buf,
+				None => break,
+			};
+
+			let len = fill(&mut stream, &mut frames, opts.looping, &mut ended);
+			if len > 0 && buf.set_data(&frames[..len], opts.frequency).is_ok() {
+				if src.queue(buf).is_err() {
+					ended = true;
+				}
+			} else {
+				ended = true;
+				if let Some(ref mut pool) = src.pool {
+					pool.free.push(buf.buf);
+				}
+			}
+		}
+
+		// An underrun is restarted by `queue_buffer`, so only a source that is not meant to be playing is started here.
+		if !paused && src.buffers_queued() > 0 {
+			match src.state() {
+				SourceState::Initial | SourceState::Paused => src.play(),
+				SourceState::Stopped if !src.playing => src.play(),
+				_ => (),
+			}
+		}
+
+		match recv.recv_timeout(poll) {
+			Ok(cmd) => next = Some(cmd),
+			Err(RecvTimeoutError::Timeout) => (),
+			Err(RecvTimeoutError::Disconnected) => next = Some(StreamCommand::Stop),
+		}
+	}
+}
+
+
+/// Read from `stream` until `frames` is full or it ends, rewinding it once per end when looping.
+fn fill<F: SampleFrame, S: SampleStream<F>>(stream: &mut S, frames: &mut [F], looping: bool, ended: &mut bool) -> usize {
+	let mut len = 0;
+	let mut rewound = false;
+	while len < frames.len() {
+		let read = stream.read(&mut frames[len..]).min(frames.len() - len);
+		if read > 0 {
+			len += read;
+			rewound = false;
+		} else if looping && !rewound && stream.seek(0) {
+			rewound = true;
+		} else {
+			*ended = true;
+			break;
+		}
+	}
+	len
+}
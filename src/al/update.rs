@@ -0,0 +1,118 @@
+use ::{AltoError, AltoResult};
+use sys;
+use al::*;
+
+
+/// A batch of source properties to apply together, as started by `Source::update`.
+/// Properties that are not set are left unchanged. Nothing is applied until `commit`.
+pub struct SourceUpdate<'s> {
+	src: &'s SourceInner,
+	position: Option<[f32; 3]>,
+	velocity: Option<[f32; 3]>,
+	gain: Option<f32>,
+	pitch: Option<f32>,
+}
+
+
+impl<'s> SourceUpdate<'s> {
+	pub(crate) fn new(src: &'s SourceInner) -> SourceUpdate<'s> {
+		SourceUpdate{src, position: None, velocity: None, gain: None, pitch: None}
+	}
+
+
+	/// `alSourcefv(AL_POSITION)`
+	pub fn position<V: AsAlVec3>(mut self, value: V) -> Self { self.position = Some(value.as_al_vec3()); self }
+	/// `alSourcefv(AL_VELOCITY)`
+	pub fn velocity<V: AsAlVec3>(mut self, value: V) -> Self { self.velocity = Some(value.as_al_vec3()); self }
+	/// `alSourcef(AL_GAIN)`
+	/// Scaled by the source's group, or stashed until unmute if the source is muted, as with `Source::set_gain`.
+	pub fn gain(mut self, value: f32) -> Self { self.gain = Some(value); self }
+	/// `alSourcef(AL_PITCH)`
+	/// Scaled by the source's group, as with `Source::set_pitch`.
+	pub fn pitch(mut self, value: f32) -> Self { self.pitch = Some(value); self }
+
+
+	/// Apply the properties that were set while the context is made current once, so that no mix update
+	/// sees only some of them. With `AL_SOFT_deferred_updates` they are applied under a `DeferLock`, and
+	/// so left for an outer one to process if it is held. Otherwise they are wrapped in `alcSuspendContext()`
+	/// and `alcProcessContext()`, which many implementations ignore, leaving plain sequential sets.
+	///
+	/// Values are validated before anything is applied, failing with `AltoError::InvalidProperty` naming
+	/// the first rejected property. An error raised by OpenAL is reported the same way with the AL error as its
+	/// cause, as checked according to the context's `ErrorChecking` policy, and leaves the properties set before
+	/// it applied. The gain of a muted source is only stashed if the whole batch succeeds.
+	pub fn commit(self) -> AltoResult<()> {
+		if self.gain.is_some_and(|gain| !gain.is_finite() || gain < 0.0) {
+			return Err(invalid("AL_GAIN", AltoError::InvalidValue));
+		}
+		if self.pitch.is_some_and(|pitch| !pitch.is_finite() || pitch <= 0.0) {
+			return Err(invalid("AL_PITCH", AltoError::InvalidValue));
+		}
+		if !self.position.iter().flatten().all(|c| c.is_finite()) {
+			return Err(invalid("AL_POSITION", AltoError::InvalidValue));
+		}
+		if !self.velocity.iter().flatten().all(|c| c.is_finite()) {
+			return Err(invalid("AL_VELOCITY", AltoError::InvalidValue));
+		}
+
+		let src = self.src;
+		let ctx = &src.ctx;
+		let mut muted = src.muted.lock();
+		// A muted source stashes its gain for unmute rather than applying it, once the rest has succeeded.
+		let gain = if muted.is_some() { None } else { self.gain };
+		let mut mixes = ctx.0.mixes.lock();
+		let mut mix = mixes.get_mut(&src.src);
+		let (gain_scale, pitch_scale) = mix.as_ref().map(|mix| (mix.gain, mix.pitch)).unwrap_or((1.0, 1.0));
+
+		let deferred = ctx.0.exts.AL_SOFT_deferred_updates().and_then(|asdu| asdu.alDeferUpdatesSOFT.and(asdu.alProcessUpdatesSOFT)).is_ok();
+		// Declared first so the context is released before the deferred updates are processed.
+		let _defer = ctx.defer_updates();
+		let _lock = ctx.make_current(true);
+		let api = &ctx.0.dev.0.alto.0.api;
+		let check = |name| ctx.get_prop_error().map_err(|e| invalid(name, e));
+		if !deferred {
+			unsafe { api.alcSuspendContext(ctx.0.ctx); }
+		}
+
+		let result = (|| -> AltoResult<()> {
+			if let Some(value) = self.position {
+				unsafe { api.alSourcefv(src.src, sys::AL_POSITION, &value as *const [f32; 3] as *const sys::ALfloat); }
+				check("AL_POSITION")?;
+			}
+			if let Some(value) = self.velocity {
+				unsafe { api.alSourcefv(src.src, sys::AL_VELOCITY, &value as *const [f32; 3] as *const sys::ALfloat); }
+				check("AL_VELOCITY")?;
+			}
+			if let Some(value) = gain {
+				unsafe { api.alSourcef(src.src, sys::AL_GAIN, value * gain_scale); }
+				check("AL_GAIN")?;
+				if let Some(ref mut mix) = mix {
+					mix.base_gain = value;
+				}
+			}
+			if let Some(value) = self.pitch {
+				unsafe { api.alSourcef(src.src, sys::AL_PITCH, value * pitch_scale); }
+				check("AL_PITCH")?;
+				if let Some(ref mut mix) = mix {
+					mix.base_pitch = value;
+				}
+			}
+			Ok(())
+		})();
+
+		if !deferred {
+			unsafe { api.alcProcessContext(ctx.0.ctx); }
+		}
+		result?;
+
+		if let (Some(value), Some(stashed)) = (self.gain, muted.as_mut()) {
+			*stashed = value;
+		}
+		Ok(())
+	}
+}
+
+
+fn invalid(name: &'static str, cause: AltoError) -> AltoError {
+	AltoError::InvalidProperty{name, cause: Box::new(cause)}
+}
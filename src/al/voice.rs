@@ -0,0 +1,271 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use ::{AltoError, AltoResult};
+use al::*;
+
+
+/// Handle to a logical voice registered with a `VoiceManager`.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct VoiceId(usize);
+
+
+/// Plays more logical voices than there are real sources available by assigning a limited pool of
+/// `StaticSource`s to the most important voices on each update, and virtualizing the rest.
+///
+/// Voices are ranked by priority, then by audibility as computed from their gain and the context's
+/// distance model with default source distance parameters, then by the order in which they were started.
+/// Voices that currently hold a real source have their audibility boosted by the hysteresis factor
+/// while ranking, so that voices near the boundary don't repeatedly swap. A virtualized voice keeps advancing its playback offset, so a
+/// looping voice that regains a real source resumes where it would have been.
+/// If the implementation runs out of sources before `max_sources` is reached, the remaining voices stay virtual.
+/// Only buffer playback is virtualized; streaming sources are fed by the application and should be
+/// managed by it directly.
+pub struct VoiceManager {
+	ctx: Context,
+	max_sources: usize,
+	hysteresis: f32,
+	next_id: usize,
+	voices: BTreeMap<VoiceId, Voice>,
+	pool: Vec<StaticSource>,
+	sources_len: usize,
+}
+
+
+struct Voice {
+	buf: Arc<Buffer>,
+	duration: f32,
+	priority: i32,
+	position: [f32; 3],
+	gain: f32,
+	looping: bool,
+	offset: f32,
+	src: Option<StaticSource>,
+}
+
+
+impl VoiceManager {
+	pub(crate) fn new(ctx: Context, max_sources: usize) -> VoiceManager {
+		VoiceManager{
+			ctx,
+			max_sources,
+			hysteresis: 0.1,
+			next_id: 0,
+			voices: BTreeMap::new(),
+			pool: Vec::new(),
+			sources_len: 0,
+		}
+	}
+
+
+	/// The context from which this manager was created.
+	pub fn context(&self) -> &Context { &self.ctx }
+	/// Maximum number of real sources this manager will create.
+	pub fn max_sources(&self) -> usize { self.max_sources }
+
+
+	/// Fraction by which the audibility of voices holding a real source is boosted while ranking.
+	pub fn hysteresis(&self) -> f32 { self.hysteresis }
+	/// Set the hysteresis factor. Defaults to 0.1.
+	pub fn set_hysteresis(&mut self, value: f32) -> AltoResult<()> {
+		if !value.is_finite() || value < 0.0 {
+			return Err(AltoError::InvalidValue);
+		}
+
+		self.hysteresis = value;
+		Ok(())
+	}
+
+
+	/// Register a new voice that plays `buf` from the start.
+	/// The voice is virtual until the next call to `update`.
+	pub fn play(&mut self, buf: Arc<Buffer>, priority: i32, looping: bool) -> AltoResult<VoiceId> {
+		self.ctx.check_buffer(&buf)?;
+
+		let freq = buf.frequency();
+		let duration = if freq > 0 { buf.len as f32 / freq as f32 } else { 0.0 };
+		let id = VoiceId(self.next_id);
+		self.next_id += 1;
+		self.voices.insert(id, Voice{
+			buf,
+			duration,
+			priority,
+			position: [0.0, 0.0, 0.0],
+			gain: 1.0,
+			looping,
+			offset: 0.0,
+			src: None,
+		});
+		Ok(id)
+	}
+
+
+	/// Stop a voice and release its real source, if any.
+	pub fn stop(&mut self, id: VoiceId) {
+		if let Some(voice) = self.voices.remove(&id) {
+			if let Some(src) = voice.src {
+				self.release(src);
+			}
+		}
+	}
+
+
+	/// Whether a voice is still registered. Non-looping voices are removed once they finish.
+	pub fn is_active(&self, id: VoiceId) -> bool { self.voices.contains_key(&id) }
+	/// Whether a voice currently holds a real source.
+	pub fn is_real(&self, id: VoiceId) -> bool {
+		self.voices.get(&id).map(|v| v.src.is_some()).unwrap_or(false)
+	}
+	/// The voices that currently hold a real source, in order of registration.
+	pub fn real_voices(&self) -> Vec<VoiceId> {
+		self.voices.iter().filter(|&(_, v)| v.src.is_some()).map(|(&id, _)| id).collect()
+	}
+
+
+	/// Set the priority of a voice. Higher priorities are always preferred regardless of audibility.
+	pub fn set_priority(&mut self, id: VoiceId, value: i32) -> AltoResult<()> {
+		self.voice_mut(id)?.priority = value;
+		Ok(())
+	}
+
+
+	/// Set the position of a voice, applying it immediately if the voice is real.
+	pub fn set_position<V: AsAlVec3>(&mut self, id: VoiceId, value: V) -> AltoResult<()> {
+		let voice = self.voice_mut(id)?;
+		voice.position = value.as_al_vec3();
+		if let Some(ref mut src) = voice.src {
+			src.set_position(voice.position)?;
+		}
+		Ok(())
+	}
+
+
+	/// Set the gain of a voice, applying it immediately if the voice is real.
+	pub fn set_gain(&mut self, id: VoiceId, value: f32) -> AltoResult<()> {
+		let voice = self.voice_mut(id)?;
+		voice.gain = value;
+		if let Some(ref mut src) = voice.src {
+			src.set_gain(value)?;
+		}
+		Ok(())
+	}
+
+
+	/// Advance virtual voices by `dt` seconds, retire finished voices, and reassign real sources
+	/// to the highest ranked voices. Changes to real sources are applied in a single deferred batch.
+	pub fn update(&mut self, dt: f32) -> AltoResult<()> {
+		if !dt.is_finite() || dt < 0.0 {
+			return Err(AltoError::InvalidValue);
+		}
+
+		let ctx = self.ctx.clone();
+		let _defer = ctx.defer_updates();
+
+		let mut finished = Vec::new();
+		for (&id, voice) in &mut self.voices {
+			let done = match voice.src {
+				Some(ref src) => src.state() == SourceState::Stopped,
+				None => {
+					voice.offset += dt;
+					if voice.looping && voice.duration > 0.0 {
+						voice.offset %= voice.duration;
+						false
+					} else {
+						voice.offset >= voice.duration
+					}
+				},
+			};
+			if done {
+				finished.push(id);
+			}
+		}
+		for id in finished {
+			self.stop(id);
+		}
+
+		let ranked = self.rank();
+		let (keep, evict) = ranked.split_at(ranked.len().min(self.max_sources));
+
+		for id in evict {
+			let voice = self.voices.get_mut(id).unwrap();
+			if let Some(src) = voice.src.take() {
+				voice.offset = src.sec_offset();
+				self.release(src);
+			}
+		}
+
+		for id in keep {
+			if self.voices[id].src.is_some() {
+				continue;
+			}
+
+			let mut src = match self.acquire()? {
+				Some(src) => src,
+				None => break,
+			};
+			let voice = self.voices.get_mut(id).unwrap();
+			src.set_buffer(voice.buf.clone())?;
+			src.set_looping(voice.looping);
+			src.set_position(voice.position)?;
+			src.set_gain(voice.gain)?;
+			src.set_sec_offset(voice.offset)?;
+			src.play();
+			voice.src = Some(src);
+		}
+
+		Ok(())
+	}
+
+
+	fn rank(&self) -> Vec<VoiceId> {
+		let model = self.ctx.distance_model();
+		let listener: [f32; 3] = self.ctx.position();
+		let hysteresis = 1.0 + self.hysteresis;
+
+		let mut ranked: Vec<_> = self.voices.iter().map(|(&id, v)| {
+			let d = [v.position[0] - listener[0], v.position[1] - listener[1], v.position[2] - listener[2]];
+			let distance = (d[0] * d[0] + d[1] * d[1] + d[2] * d[2]).sqrt();
+			let mut audibility = v.gain * model.attenuation(distance, 1.0, f32::MAX, 1.0);
+			if v.src.is_some() {
+				audibility *= hysteresis;
+			}
+			(id, v.priority, audibility)
+		}).collect();
+
+		ranked.sort_by(|a, b| {
+			b.1.cmp(&a.1)
+				.then_with(|| b.2.partial_cmp(&a.2).unwrap_or(Ordering::Equal))
+				.then_with(|| a.0.cmp(&b.0))
+		});
+		ranked.into_iter().map(|(id, _, _)| id).collect()
+	}
+
+
+	fn acquire(&mut self) -> AltoResult<Option<StaticSource>> {
+		if let Some(src) = self.pool.pop() {
+			return Ok(Some(src));
+		}
+		if self.sources_len >= self.max_sources {
+			return Ok(None);
+		}
+
+		let src = self.ctx.try_new_static_source()?;
+		if src.is_some() {
+			self.sources_len += 1;
+		}
+		Ok(src)
+	}
+
+
+	fn release(&mut self, mut src: StaticSource) {
+		src.stop();
+		src.clear_buffer();
+		self.pool.push(src);
+	}
+
+
+	fn voice_mut(&mut self, id: VoiceId) -> AltoResult<&mut Voice> {
+		self.voices.get_mut(&id).ok_or(AltoError::InvalidName)
+	}
+}
@@ -1,11 +1,25 @@
 use std::cmp;
+use std::fmt;
 use std::any::Any;
 use std::ptr;
+use std::io;
 use std::mem;
 use std::ffi::{CString, CStr};
 use std::sync::Arc;
 use std::path::Path;
 use std::marker::PhantomData;
+use std::slice;
+use std::time::{Duration, Instant};
+#[cfg(feature = "async")]
+use std::future::Future;
+#[cfg(feature = "async")]
+use std::pin::Pin;
+#[cfg(feature = "async")]
+use std::task::{self, Poll, Waker};
+#[cfg(feature = "async")]
+use std::thread;
+
+use parking_lot::Mutex;
 
 use ::{AltoError, AltoResult};
 use sys;
@@ -115,17 +129,133 @@ pub enum SoftHrtfStatus {
 }
 
 
+/// The kind of a system event.
+/// Requires `ALC_SOFT_system_events`
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum SoftSystemEventType {
+	/// `ALC_EVENT_TYPE_DEFAULT_DEVICE_CHANGED_SOFT`
+	DefaultDeviceChanged,
+	/// `ALC_EVENT_TYPE_DEVICE_ADDED_SOFT`
+	DeviceAdded,
+	/// `ALC_EVENT_TYPE_DEVICE_REMOVED_SOFT`
+	DeviceRemoved,
+}
+
+
+/// A device change notification, as delivered to the callback registered with `Alto::on_system_event`.
+/// Requires `ALC_SOFT_system_events`
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct SoftSystemEvent {
+	/// What happened.
+	pub event_type: SoftSystemEventType,
+	/// `ALC_CAPTURE_DEVICE_SOFT` if true, otherwise `ALC_PLAYBACK_DEVICE_SOFT`.
+	pub capture: bool,
+	/// Description of the event provided by the implementation.
+	pub message: String,
+}
+
+
+/// A change in the connection of a device, as reported by `ConnectionWatcher`.
+/// Requires `ALC_EXT_disconnect`
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum ConnectionEvent {
+	/// The device has been lost, and will not come back. It must be reopened to continue.
+	Disconnected,
+}
+
+
+/// Turns polling of `ALC_CONNECTED` into a one-time `ConnectionEvent`, for both output and capture devices.
+/// Requires `ALC_EXT_disconnect`
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct ConnectionWatcher {
+	disconnected: bool,
+}
+
+
+/// Which ALC extensions a device supports, as returned by `DeviceObject::extensions`.
+/// Displays as the names of the present extensions, separated by spaces.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct ExtensionSet {
+	/// `ALC_EXT_DEDICATED`
+	pub dedicated: bool,
+	/// `ALC_EXT_disconnect`
+	pub disconnect: bool,
+	/// `ALC_EXT_EFX`
+	pub efx: bool,
+	/// `ALC_SOFT_HRTF`
+	pub soft_hrtf: bool,
+	/// `ALC_SOFT_pause_device`
+	pub soft_pause_device: bool,
+	/// `ALC_SOFT_output_limiter`
+	pub soft_output_limiter: bool,
+	/// `ALC_SOFT_device_clock`
+	pub soft_device_clock: bool,
+	/// `ALC_SOFT_reopen_device`
+	pub soft_reopen_device: bool,
+}
+
+
+/// A device whose connection can be watched with a `ConnectionWatcher`.
+/// Implemented for every `DeviceObject` as well as for `Capture`.
+pub trait WatchConnection {
+	#[doc(hidden)]
+	fn watch_connected(&self) -> AltoResult<bool>;
+}
+
+
+struct SystemEventHandler {
+	events: [sys::ALCenum; 3],
+	capture: sys::ALCenum,
+	cb: Box<dyn FnMut(SoftSystemEvent) + Send>,
+}
+
+
+lazy_static! {
+	static ref SYSTEM_EVENT_HANDLER: Mutex<Option<SystemEventHandler>> = Mutex::new(None);
+}
+
+
 pub(crate) struct AltoInner {
 	pub(crate) api: sys::AlApi,
 	pub(crate) exts: ::ext::AlcNullCache,
+	hints: Mutex<ConfigHints>,
+}
+
+
+/// Settings recorded by `Alto::set_config_hint`, applied whenever a device is opened or a context is created.
+#[derive(Clone, Default)]
+struct ConfigHints {
+	default_device: Option<CString>,
+	frequency: Option<sys::ALCint>,
+	hrtf: Option<bool>,
+	default_hrtf: Option<CString>,
+	output_limiter: Option<bool>,
+	sends: Option<sys::ALCint>,
 }
 
 
 /// This struct is the entry point of the API. Instantiating it will load an OpenAL implementation.
 /// From here, available devices can be queried and opened.
+///
+/// `Alto` is `Send` and `Sync`, and clones share the loaded library, so it can be loaded on one thread
+/// and used from any other. `OutputDevice`, `LoopbackDevice`, `Context`, `Buffer` and the source types are
+/// likewise `Send` and `Sync`. `Capture` is `Send` but not `Sync`, and `Device` is neither.
 pub struct Alto(pub(crate) Arc<AltoInner>);
 
 
+/// Resolves to the result of `Alto::load_default`, which runs on a helper thread.
+/// Returned by `Alto::load_default_async`.
+#[cfg(feature = "async")]
+pub struct LoadDefault(Arc<Mutex<LoadState>>);
+
+
+#[cfg(feature = "async")]
+struct LoadState {
+	result: Option<AltoResult<Alto>>,
+	waker: Option<Waker>,
+}
+
+
 /// Common capabilities expoed by both real and loopback devices.
 pub unsafe trait DeviceObject: Any {
 	/// AltoInner instance from which this device was opened.
@@ -136,12 +266,23 @@ pub unsafe trait DeviceObject: Any {
 	fn as_raw(&self) -> *mut sys::ALCdevice;
 	/// `alcIsExtensionPresent()`
 	fn is_extension_present(&self, ext::Alc) -> bool;
+	/// `alcIsExtensionPresent()`
+	/// The presence of every known extension, read from the device's extension cache in one pass.
+	fn extensions(&self) -> ExtensionSet;
 	/// `alcGetIntegerv(ALC_CONNECTED)`
 	/// Requires `ALC_EXT_disconnect`
 	fn connected(&self) -> AltoResult<bool>;
+	/// `alcGetError()`
+	/// Discard any ALC error pending on this device. Like the AL error of a context, each device has a
+	/// single error slot that keeps the first error until it is read.
+	fn clear_error(&self);
 	/// `alcGetStringiSOFT(ALC_HRTF_SPECIFIER_SOFT)`
 	/// Requires `ALC_SOFT_HRTF`
 	fn enumerate_soft_hrtfs(&self) -> Vec<CString>;
+	/// `alcGetStringiSOFT(ALC_HRTF_SPECIFIER_SOFT)`
+	/// Requires `ALC_SOFT_HRTF`
+	/// Each specifier is paired with the index to supply as `soft_hrtf_id`.
+	fn soft_hrtfs(&self) -> AltoResult<Vec<(sys::ALCint, CString)>>;
 	/// `alcGetIntegerv(ALC_HRTF_STATUS_SOFT)`
 	/// Requires `ALC_SOFT_HRTF`
 	fn soft_hrtf_status(&self) -> SoftHrtfStatus;
@@ -151,6 +292,10 @@ pub unsafe trait DeviceObject: Any {
 	/// `alcGetIntegerv(ALC_MAX_AUXILIARY_SENDS)`
 	/// Requires `ALC_EXT_EFX`
 	fn max_aux_sends(&self) -> sys::ALCint;
+	/// `alcGetInteger64vSOFT(ALC_DEVICE_CLOCK_SOFT)`
+	/// Requires `ALC_SOFT_device_clock`
+	/// Nanoseconds of audio the device has processed, the time base for `Source::soft_play_at_time`.
+	fn soft_clock(&self) -> AltoResult<i64>;
 	/// Return a new handle to this device.
 	fn to_device(&self) -> Device;
 }
@@ -169,6 +314,8 @@ pub struct OutputDevice(pub(crate) Arc<DeviceInner>);
 
 
 /// A sample frame that is supported as a loopback device output format.
+/// Loopback output is described by separate ALC channel and sample type tokens rather than
+/// the AL buffer format returned by `SampleFrame::format`.
 pub unsafe trait LoopbackFrame: SampleFrame {
 	fn channels(&ext::ALC_SOFT_loopback) -> AltoResult<sys::ALint>;
 	fn sample_ty(&ext::ALC_SOFT_loopback) -> AltoResult<sys::ALint>;
@@ -186,27 +333,155 @@ pub struct Device(pub(crate) Arc<DeviceInner>);
 
 /// A capture device from which audio data can be sampled.
 /// This is tyically an audio input as reported by the operating system.
+/// Dropping it closes the device with `alcCaptureCloseDevice()`, as capture devices require;
+/// call `close` instead to learn whether that succeeded.
 pub struct Capture<F: StandardFrame> {
 	alto: Alto,
 	spec: Option<CString>,
 	dev: *mut sys::ALCdevice,
+	exts: ext::AlcCache,
+	overrun: OverrunTracker,
 	marker: PhantomData<F>,
 }
 
 
+/// Detects a full ring buffer on a capture device, and compares the samples the device should have produced
+/// since it was started with those captured and still available, to estimate how many were lost to it.
+struct OverrunTracker {
+	freq: sys::ALCuint,
+	len: sys::ALCsizei,
+	/// When the device was started, and how many samples were already available then.
+	started: Option<(Instant, u64)>,
+	/// Since when the available samples have stayed at this count without being drained.
+	unchanged: Option<(Instant, u64)>,
+	/// The most samples the ring buffer has been seen to hold, once it has been found full.
+	capacity: Option<u64>,
+	captured: u64,
+	lost: u64,
+	pending: Option<u64>,
+	dropped: u64,
+}
+
+
+impl LoopbackFormatChannels {
+	const ALL: [LoopbackFormatChannels; 6] = [
+		LoopbackFormatChannels::Mono,
+		LoopbackFormatChannels::Stereo,
+		LoopbackFormatChannels::Quad,
+		LoopbackFormatChannels::Mc51,
+		LoopbackFormatChannels::Mc61,
+		LoopbackFormatChannels::Mc71,
+	];
+
+
+	fn into_raw(self, asl: &ext::ALC_SOFT_loopback) -> AltoResult<sys::ALCint> {
+		Ok(match self {
+			LoopbackFormatChannels::Mono => asl.ALC_MONO_SOFT?,
+			LoopbackFormatChannels::Stereo => asl.ALC_STEREO_SOFT?,
+			LoopbackFormatChannels::Quad => asl.ALC_QUAD_SOFT?,
+			LoopbackFormatChannels::Mc51 => asl.ALC_5POINT1_SOFT?,
+			LoopbackFormatChannels::Mc61 => asl.ALC_6POINT1_SOFT?,
+			LoopbackFormatChannels::Mc71 => asl.ALC_7POINT1_SOFT?,
+		})
+	}
+}
+
+
+/// The frequencies tried by `LoopbackDevice::supported_frequencies`.
+const PROBED_FREQUENCIES: [sys::ALCint; 6] = [8000, 16000, 22050, 44100, 48000, 96000];
+
+/// How long the available capture samples must stay unchanged before the ring buffer is taken to be full.
+/// Long enough for a backend that delivers samples in periods to have delivered at least one more.
+const CAPTURE_STALL: Duration = Duration::from_millis(50);
+
+
+impl LoopbackFormatType {
+	const ALL: [LoopbackFormatType; 3] = [LoopbackFormatType::U8, LoopbackFormatType::I16, LoopbackFormatType::F32];
+
+
+	fn into_raw(self, asl: &ext::ALC_SOFT_loopback) -> AltoResult<sys::ALCint> {
+		Ok(match self {
+			LoopbackFormatType::U8 => asl.ALC_UNSIGNED_BYTE_SOFT?,
+			LoopbackFormatType::I16 => asl.ALC_SHORT_SOFT?,
+			LoopbackFormatType::F32 => asl.ALC_FLOAT_SOFT?,
+		})
+	}
+}
+
+
+#[cfg(feature = "async")]
+impl Future for LoadDefault {
+	type Output = AltoResult<Alto>;
+
+	fn poll(self: Pin<&mut Self>, cx: &mut task::Context) -> Poll<AltoResult<Alto>> {
+		let mut state = self.0.lock();
+		match state.result.take() {
+			Some(result) => Poll::Ready(result),
+			None => {
+				state.waker = Some(cx.waker().clone());
+				Poll::Pending
+			},
+		}
+	}
+}
+
+
+impl fmt::Display for SoftHrtfStatus {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			SoftHrtfStatus::Disabled => write!(f, "Disabled"),
+			SoftHrtfStatus::Enabled => write!(f, "Enabled"),
+			SoftHrtfStatus::Denied => write!(f, "Denied"),
+			SoftHrtfStatus::Required => write!(f, "Required"),
+			SoftHrtfStatus::HeadphonesDetected => write!(f, "Headphones detected"),
+			SoftHrtfStatus::UnsupportedFormat => write!(f, "Unsupported format"),
+			SoftHrtfStatus::Unknown(s) => write!(f, "Unknown ({})", s),
+		}
+	}
+}
+
+
 impl Alto {
 	/// Load the default OpenAL implementation for the platform.
 	/// This will prefer OpenAL-Soft if it is present, otherwise it will search for a generic implementation.
+	/// If nothing can be loaded, the returned `AltoError::Io` lists each library name tried,
+	/// why it failed, and the loader search path in effect.
+	///
+	/// Loading the library can take tens of milliseconds. Since `Alto` is `Send`, it can be moved off the
+	/// startup path with `thread::spawn(Alto::load_default)` and joined later, or with `load_default_async`.
 	pub fn load_default() -> AltoResult<Alto> {
 		let api = sys::AlApi::load_default()?;
 		let exts = unsafe { ext::AlcNullCache::new(&api, ptr::null_mut()) };
 		Ok(Alto(Arc::new(AltoInner{
 			api: api,
 			exts: exts,
+			hints: Mutex::new(ConfigHints::default()),
 		}))).and_then(|a| a.check_version(ptr::null_mut()).map(|_| a))
 	}
 
 
+	/// Run `load_default` on a new thread, returning a future that resolves once it is done.
+	/// The future does not depend on any particular executor. Requires the `async` feature.
+	#[cfg(feature = "async")]
+	pub fn load_default_async() -> LoadDefault {
+		let state = Arc::new(Mutex::new(LoadState{result: None, waker: None}));
+		let thread_state = state.clone();
+		thread::spawn(move || {
+			let result = Alto::load_default();
+			let waker = {
+				let mut state = thread_state.lock();
+				state.result = Some(result);
+				state.waker.take()
+			};
+			if let Some(waker) = waker {
+				waker.wake();
+			}
+		});
+
+		LoadDefault(state)
+	}
+
+
 	/// Loads a specific OpenAL implementation from a specififed path.
 	pub fn load<P: AsRef<Path>>(path: P) -> AltoResult<Alto> {
 		let api = sys::AlApi::load(path)?;
@@ -214,10 +489,31 @@ impl Alto {
 		Ok(Alto(Arc::new(AltoInner{
 			api: api,
 			exts: exts,
+			hints: Mutex::new(ConfigHints::default()),
 		}))).and_then(|a| a.check_version(ptr::null_mut()).map(|_| a))
 	}
 
 
+	/// Try each path in order and return the first implementation that loads and reports a supported version.
+	/// If every candidate fails, the returned `AltoError::Io` lists each path and why it was rejected.
+	pub fn load_preferred<P: AsRef<Path>>(paths: &[P]) -> AltoResult<Alto> {
+		let mut failures = Vec::with_capacity(paths.len());
+		for path in paths {
+			match Alto::load(path) {
+				Ok(alto) => return Ok(alto),
+				Err(AltoError::Io(e)) => failures.push(format!("  {}: {}", path.as_ref().display(), e)),
+				Err(AltoError::UnsupportedVersion{major, minor}) => failures.push(format!("  {}: unsupported version {}.{}", path.as_ref().display(), major, minor)),
+				Err(e) => failures.push(format!("  {}: {}", path.as_ref().display(), e)),
+			}
+		}
+
+		Err(AltoError::Io(io::Error::new(io::ErrorKind::NotFound, format!(
+			"Could not load an OpenAL implementation. Tried:\n{}",
+			failures.join("\n"),
+		))))
+	}
+
+
 	fn check_version(&self, dev: *mut sys::ALCdevice) -> AltoResult<()> {
 		let mut major = 0;
 		unsafe { self.0.api.alcGetIntegerv(dev, sys::ALC_MAJOR_VERSION, 1, &mut major); }
@@ -238,6 +534,107 @@ impl Alto {
 	pub fn raw_api(&self) -> &sys::AlApi { &self.0.api }
 
 
+	/// Record a configuration hint using the same key names as `alsoft.conf`.
+	/// OpenAL-Soft reads its config file and environment when the library is first used,
+	/// so only settings with a runtime equivalent can be applied this way. They take effect
+	/// for devices opened and contexts created or reset afterwards, and never override an
+	/// explicitly supplied attribute. Supported keys are:
+	///
+	/// - `default-device`: specifier used when `open` is given `None`.
+	/// - `frequency`: `ALC_FREQUENCY` for output contexts.
+	/// - `hrtf`: `true`, `false`, or `auto`, via `ALC_HRTF_SOFT`. Requires `ALC_SOFT_HRTF`
+	/// - `default-hrtf`: name of the HRTF to select, via `ALC_HRTF_ID_SOFT`. Requires `ALC_SOFT_HRTF`
+	/// - `output-limiter`: `true` or `false`, via `ALC_OUTPUT_LIMITER_SOFT`. Requires `ALC_SOFT_output_limiter`
+	/// - `sends`: `ALC_MAX_AUXILIARY_SENDS`. Requires `ALC_EXT_EFX`
+	///
+	/// Settings such as `hrtf-paths` or `drivers` can only be changed through `alsoft.conf` or the
+	/// `ALSOFT_*` environment variables before the library is loaded, and are rejected with `InvalidEnum`.
+	/// A malformed value is rejected with `InvalidValue`.
+	pub fn set_config_hint(&self, key: &str, value: &str) -> AltoResult<()> {
+		fn parse_bool(value: &str) -> AltoResult<bool> {
+			match value {
+				"true" | "1" => Ok(true),
+				"false" | "0" => Ok(false),
+				_ => Err(AltoError::InvalidValue),
+			}
+		}
+		fn parse_int(value: &str) -> AltoResult<sys::ALCint> {
+			value.parse().ok().filter(|v| *v >= 0).ok_or(AltoError::InvalidValue)
+		}
+		fn parse_str(value: &str) -> AltoResult<Option<CString>> {
+			if value.is_empty() {
+				Ok(None)
+			} else {
+				CString::new(value).map(Some).map_err(|_| AltoError::InvalidValue)
+			}
+		}
+
+		let mut hints = self.0.hints.lock();
+		match key {
+			"default-device" => hints.default_device = parse_str(value)?,
+			"frequency" => hints.frequency = Some(parse_int(value)?),
+			"hrtf" => hints.hrtf = if value == "auto" { None } else { Some(parse_bool(value)?) },
+			"default-hrtf" => hints.default_hrtf = parse_str(value)?,
+			"output-limiter" => hints.output_limiter = Some(parse_bool(value)?),
+			"sends" => hints.sends = Some(parse_int(value)?),
+			_ => return Err(AltoError::InvalidEnum),
+		}
+
+		Ok(())
+	}
+
+
+	/// `alcIsExtensionPresent(NULL)`
+	pub fn is_extension_present(&self, ext: ext::AlcNull) -> bool {
+		match ext {
+			ext::AlcNull::EnumerateAll => self.0.exts.ALC_ENUMERATE_ALL_EXT().is_ok(),
+			ext::AlcNull::SoftLoopback => self.0.exts.ALC_SOFT_loopback().is_ok(),
+			ext::AlcNull::ThreadLocalContext => self.0.exts.ALC_EXT_thread_local_context().is_ok(),
+			ext::AlcNull::SoftSystemEvents => self.0.exts.ALC_SOFT_system_events().is_ok(),
+		}
+	}
+
+
+	/// `alcEventCallbackSOFT()` and `alcEventControlSOFT()`
+	/// Requires `ALC_SOFT_system_events`
+	/// Register a callback to be notified when devices are added or removed, or when a default device changes.
+	/// The callback runs on an internal thread of the implementation, and must neither panic nor call back into alto.
+	/// The registration is process-wide, so it replaces any callback previously registered from any `Alto` instance.
+	/// If the extension or event reporting is unavailable, `ExtensionNotPresent` is returned and
+	/// `DeviceObject::connected` should be polled instead.
+	pub fn on_system_event<F: FnMut(SoftSystemEvent) + Send + 'static>(&self, cb: F) -> AltoResult<()> {
+		let asse = self.0.exts.ALC_SOFT_system_events()?;
+		let aecs = asse.alcEventCallbackSOFT?;
+		let aecos = asse.alcEventControlSOFT?;
+		let aeiss = asse.alcEventIsSupportedSOFT?;
+		let supported = asse.ALC_EVENT_SUPPORTED_SOFT?;
+		let playback = asse.ALC_PLAYBACK_DEVICE_SOFT?;
+		let capture = asse.ALC_CAPTURE_DEVICE_SOFT?;
+
+		let events = [
+			asse.ALC_EVENT_TYPE_DEFAULT_DEVICE_CHANGED_SOFT?,
+			asse.ALC_EVENT_TYPE_DEVICE_ADDED_SOFT?,
+			asse.ALC_EVENT_TYPE_DEVICE_REMOVED_SOFT?,
+		];
+		let enabled: Vec<_> = events.iter().cloned()
+			.filter(|&e| unsafe { aeiss(e, playback) == supported || aeiss(e, capture) == supported })
+			.collect();
+		if enabled.is_empty() {
+			return Err(AltoError::ExtensionNotPresent);
+		}
+
+		// The implementation holds its own lock while invoking the callback, so ours must not be held while registering.
+		*SYSTEM_EVENT_HANDLER.lock() = Some(SystemEventHandler{events, capture, cb: Box::new(cb)});
+		unsafe { aecs(Some(system_event_callback), ptr::null_mut()); }
+
+		if unsafe { aecos(enabled.len() as sys::ALCsizei, enabled.as_ptr(), sys::ALC_TRUE) } == sys::ALC_TRUE {
+			Ok(())
+		} else {
+			self.get_error(ptr::null_mut())
+		}
+	}
+
+
 	/// `alcGetString(ALC_DEFAULT_DEVICE_SPECIFIER)`
 	pub fn default_output(&self) -> Option<CString> {
 		let spec = if let Ok(ext::ALC_ENUMERATE_ALL_EXT{ALC_DEFAULT_ALL_DEVICES_SPECIFIER: Ok(dads), ..}) = self.0.exts.ALC_ENUMERATE_ALL_EXT {
@@ -308,7 +705,9 @@ impl Alto {
 
 	/// `alcOpenDevice()`
 	pub fn open(&self, spec: Option<&CStr>) -> AltoResult<OutputDevice> {
-		let spec = spec.map(|s| s.to_owned()).or_else(|| self.default_output());
+		let spec = spec.map(|s| s.to_owned())
+			.or_else(|| self.0.hints.lock().default_device.clone())
+			.or_else(|| self.default_output());
 		let dev = unsafe { self.0.api.alcOpenDevice(spec.as_ref().map(|s| s.as_ptr()).unwrap_or(ptr::null())) };
 
 		if dev == ptr::null_mut() {
@@ -325,6 +724,20 @@ impl Alto {
 	}
 
 
+	/// `alcOpenDevice()`
+	/// Open the first output device, in the order of `enumerate_outputs`, whose specifier contains `substring`.
+	/// Matching ignores case, using Unicode lowercase on both sides, and specifiers that are not valid UTF-8
+	/// are compared with their invalid sequences replaced. An empty substring matches the first device.
+	/// Fails with `AltoError::InvalidDevice` if no specifier matches.
+	pub fn open_matching(&self, substring: &str) -> AltoResult<OutputDevice> {
+		let substring = substring.to_lowercase();
+		let spec = self.enumerate_outputs().into_iter()
+			.find(|s| s.to_string_lossy().to_lowercase().contains(&substring))
+			.ok_or(AltoError::InvalidDevice)?;
+		self.open(Some(&spec))
+	}
+
+
 	/// `alcLoopbackOpenDeviceSOFT()`
 	/// Requires `ALC_SOFT_loopback`
 	pub fn open_loopback<F: LoopbackFrame>(&self, spec: Option<&CStr>) -> AltoResult<LoopbackDevice<F>> {
@@ -351,21 +764,71 @@ impl Alto {
 	}
 
 
+	/// `alcIsRenderFormatSupportedSOFT()`
+	/// Requires `ALC_SOFT_loopback`
+	/// Probe every combination of `LoopbackFormatChannels` and `LoopbackFormatType` at `freq` and return those
+	/// a loopback device can render, in the order the enums declare them. A default loopback device is opened
+	/// for the probe and closed again.
+	pub fn loopback_formats(&self, freq: sys::ALCint) -> AltoResult<Vec<(LoopbackFormatChannels, LoopbackFormatType)>> {
+		let dev = self.open_loopback::<Stereo<i16>>(None)?;
+
+		let mut formats = Vec::new();
+		for &channels in LoopbackFormatChannels::ALL.iter() {
+			for &ty in LoopbackFormatType::ALL.iter() {
+				if dev.is_render_format_supported(freq, channels, ty)? {
+					formats.push((channels, ty));
+				}
+			}
+		}
+		Ok(formats)
+	}
+
+
 	/// `alcCaptureOpenDevice()`
+	/// Fails with `AltoError::CaptureFormatUnsupported` if the device exists but rejected the format or
+	/// frequency, so that another combination can be tried, and with `AltoError::InvalidDevice` otherwise.
 	pub fn open_capture<F: StandardFrame>(&self, spec: Option<&CStr>, freq: sys::ALCuint, len: sys::ALCsizei) -> AltoResult<Capture<F>> {
+		if freq == 0 || len <= 0 {
+			return Err(AltoError::InvalidValue);
+		}
+
 		let spec = spec.map(|s| s.to_owned()).or_else(|| self.default_capture());
-		let dev = unsafe { self.0.api.alcCaptureOpenDevice(spec.as_ref().map(|s| s.as_ptr()).unwrap_or(ptr::null()), freq, F::format().into_raw(None)?, len) };
+		let format = F::format();
+		self.clear_alc_error(None);
+		let dev = unsafe { self.0.api.alcCaptureOpenDevice(spec.as_ref().map(|s| s.as_ptr()).unwrap_or(ptr::null()), freq, format.into_raw(None)?, len) };
 
 		if dev == ptr::null_mut() {
-			Err(AltoError::InvalidDevice)
+			// Read the error before enumerating, which may overwrite it.
+			let err = self.get_error(ptr::null_mut());
+			// A device name that doesn't exist is also reported as an invalid value, so rule that out first.
+			// With no name even after falling back to the default, there is no capture device at all.
+			let known = match spec {
+				Some(ref spec) => self.enumerate_captures().contains(spec),
+				None => false,
+			};
+			match err {
+				Err(AltoError::InvalidValue) | Err(AltoError::InvalidEnum) if known => Err(AltoError::CaptureFormatUnsupported{freq, format}),
+				_ => Err(AltoError::InvalidDevice),
+			}
 		} else {
-			let dev = Capture{alto: Alto(self.0.clone()), spec: spec, dev: dev, marker: PhantomData};
+			let exts = unsafe { ext::AlcCache::new(&self.0.api, dev) };
+			let overrun = OverrunTracker{freq, len, started: None, unchanged: None, capacity: None, captured: 0, lost: 0, pending: None, dropped: 0};
+			let dev = Capture{alto: Alto(self.0.clone()), spec: spec, dev: dev, exts, overrun, marker: PhantomData};
 			//self.check_version(dev.dev).map(|_| dev)
 			Ok(dev)
 		}
 	}
 
 
+	/// `alcGetError()`
+	/// Discard any ALC error pending on `dev`, or on the null device when `None`, which records errors
+	/// from calls that take no device such as enumeration. Each device has a single error slot that
+	/// keeps the first error until it is read, so clearing it first ensures a later error is current.
+	pub fn clear_alc_error(&self, dev: Option<&dyn DeviceObject>) {
+		unsafe { self.0.api.alcGetError(dev.map(|d| d.as_raw()).unwrap_or(ptr::null_mut())); }
+	}
+
+
 	#[doc(hidden)]
 	pub fn get_error(&self, dev: *mut sys::ALCdevice) -> AltoResult<()> {
 		match unsafe { self.0.api.alcGetError(dev)} {
@@ -376,6 +839,68 @@ impl Alto {
 }
 
 
+unsafe extern "C" fn system_event_callback(event_type: sys::ALCenum, device_type: sys::ALCenum, _: *mut sys::ALCdevice, len: sys::ALCsizei, msg: *const sys::ALCchar, _: *mut sys::ALCvoid) {
+	let mut handler = SYSTEM_EVENT_HANDLER.lock();
+	if let Some(ref mut handler) = *handler {
+		let event_type = match handler.events.iter().position(|&e| e == event_type) {
+			Some(0) => SoftSystemEventType::DefaultDeviceChanged,
+			Some(1) => SoftSystemEventType::DeviceAdded,
+			Some(2) => SoftSystemEventType::DeviceRemoved,
+			_ => return,
+		};
+		let message = if msg.is_null() || len <= 0 {
+			String::new()
+		} else {
+			String::from_utf8_lossy(slice::from_raw_parts(msg as *const u8, len as usize)).into_owned()
+		};
+
+		(handler.cb)(SoftSystemEvent{event_type, capture: device_type == handler.capture, message});
+	}
+}
+
+
+impl ConfigHints {
+	fn is_empty(&self) -> bool {
+		self.frequency.is_none() && self.hrtf.is_none() && self.default_hrtf.is_none() && self.output_limiter.is_none() && self.sends.is_none()
+	}
+
+
+	fn soft_hrtf_id(&self, dev: &DeviceInner) -> Option<sys::ALCint> {
+		let name = self.default_hrtf.as_ref()?;
+		dev.soft_hrtfs().ok()?.into_iter().find(|&(_, ref h)| h == name).map(|(i, _)| i)
+	}
+
+
+	fn context_attrs(&self, dev: &DeviceInner, attrs: Option<ContextAttrs>) -> Option<ContextAttrs> {
+		if self.is_empty() {
+			return attrs;
+		}
+
+		let mut attrs = attrs.unwrap_or_default();
+		attrs.frequency = attrs.frequency.or(self.frequency);
+		attrs.soft_hrtf = attrs.soft_hrtf.or(self.hrtf);
+		attrs.soft_hrtf_id = attrs.soft_hrtf_id.or_else(|| self.soft_hrtf_id(dev));
+		attrs.soft_output_limiter = attrs.soft_output_limiter.or(self.output_limiter);
+		attrs.max_aux_sends = attrs.max_aux_sends.or(self.sends);
+		Some(attrs)
+	}
+
+
+	fn loopback_attrs(&self, dev: &DeviceInner, attrs: Option<LoopbackAttrs>) -> Option<LoopbackAttrs> {
+		if self.is_empty() {
+			return attrs;
+		}
+
+		let mut attrs = attrs.unwrap_or_default();
+		attrs.soft_hrtf = attrs.soft_hrtf.or(self.hrtf);
+		attrs.soft_hrtf_id = attrs.soft_hrtf_id.or_else(|| self.soft_hrtf_id(dev));
+		attrs.soft_output_limiter = attrs.soft_output_limiter.or(self.output_limiter);
+		attrs.max_aux_sends = attrs.max_aux_sends.or(self.sends);
+		Some(attrs)
+	}
+}
+
+
 impl Clone for Alto {
 	fn clone(&self) -> Alto { Alto(self.0.clone()) }
 }
@@ -396,10 +921,33 @@ impl DeviceInner {
 			ext::Alc::SoftHrtf => self.exts.ALC_SOFT_HRTF().is_ok(),
 			ext::Alc::SoftOutputLimiter => self.exts.ALC_SOFT_output_limiter().is_ok(),
 			ext::Alc::SoftPauseDevice => self.exts.ALC_SOFT_pause_device().is_ok(),
+			ext::Alc::SoftDeviceClock => self.exts.ALC_SOFT_device_clock().is_ok(),
+			ext::Alc::SoftReopenDevice => self.exts.ALC_SOFT_reopen_device().is_ok(),
 		}
 	}
 
 
+	/// `alcIsExtensionPresent()`
+	pub fn extensions(&self) -> ExtensionSet {
+		ExtensionSet{
+			dedicated: self.exts.ALC_EXT_DEDICATED().is_ok(),
+			disconnect: self.exts.ALC_EXT_DISCONNECT().is_ok(),
+			efx: self.exts.ALC_EXT_EFX().is_ok(),
+			soft_hrtf: self.exts.ALC_SOFT_HRTF().is_ok(),
+			soft_pause_device: self.exts.ALC_SOFT_pause_device().is_ok(),
+			soft_output_limiter: self.exts.ALC_SOFT_output_limiter().is_ok(),
+			soft_device_clock: self.exts.ALC_SOFT_device_clock().is_ok(),
+			soft_reopen_device: self.exts.ALC_SOFT_reopen_device().is_ok(),
+		}
+	}
+
+
+	/// `alcGetError()`
+	pub fn clear_error(&self) {
+		unsafe { self.alto.0.api.alcGetError(self.dev); }
+	}
+
+
 	/// `alcGetIntegerv(ALC_CONNECTED)`
 	/// Requires `ALC_EXT_DISCONNECT`
 	pub fn connected(&self) -> AltoResult<bool> {
@@ -421,8 +969,13 @@ impl DeviceInner {
 
 			for i in 0 .. value {
 				unsafe {
-					let spec = ash.alcGetStringiSOFT?(self.dev, ash.ALC_HRTF_SPECIFIER_SOFT?, i) as *mut _;
-					spec_vec.push(self.alto.get_error(self.dev).map(|_| CStr::from_ptr(spec).to_owned())?);
+					// The string is owned by the implementation, so it must be copied rather than taken.
+					let spec = ash.alcGetStringiSOFT?(self.dev, ash.ALC_HRTF_SPECIFIER_SOFT?, i);
+					self.alto.get_error(self.dev)?;
+					if spec.is_null() {
+						return Err(AltoError::NullError);
+					}
+					spec_vec.push(CStr::from_ptr(spec).to_owned());
 				}
 			}
 
@@ -433,6 +986,29 @@ impl DeviceInner {
 	}
 
 
+	/// `alcGetStringiSOFT(ALC_HRTF_SPECIFIER_SOFT)`
+	/// Requires `ALC_SOFT_HRTF`
+	pub fn soft_hrtfs(&self) -> AltoResult<Vec<(sys::ALCint, CString)>> {
+		let ash = self.exts.ALC_SOFT_HRTF()?;
+		let agss = ash.alcGetStringiSOFT?;
+		let spec_param = ash.ALC_HRTF_SPECIFIER_SOFT?;
+
+		let mut value = 0;
+		unsafe { self.alto.0.api.alcGetIntegerv(self.dev, ash.ALC_NUM_HRTF_SPECIFIERS_SOFT?, 1, &mut value); }
+		self.alto.get_error(self.dev)?;
+
+		(0 .. value).map(|i| {
+			let spec = unsafe { agss(self.dev, spec_param, i) };
+			self.alto.get_error(self.dev)?;
+			if spec.is_null() {
+				Err(AltoError::NullError)
+			} else {
+				Ok((i, unsafe { CStr::from_ptr(spec).to_owned() }))
+			}
+		}).collect()
+	}
+
+
 	/// `alcGetIntegerv(ALC_HRTF_STATUS_SOFT)`
 	/// Requires `ALC_SOFT_HRTF`
 	pub fn soft_hrtf_status(&self) -> SoftHrtfStatus {
@@ -477,18 +1053,58 @@ impl DeviceInner {
 		})();
 		value
 	}
+
+
+	/// `alcGetInteger64vSOFT(ALC_DEVICE_CLOCK_SOFT)`
+	/// Requires `ALC_SOFT_device_clock`
+	pub fn soft_clock(&self) -> AltoResult<i64> {
+		let asdc = self.exts.ALC_SOFT_device_clock()?;
+
+		let mut value = 0;
+		unsafe { asdc.alcGetInteger64vSOFT?(self.dev, asdc.ALC_DEVICE_CLOCK_SOFT?, 1, &mut value); }
+		self.alto.get_error(self.dev).map(|_| value)
+	}
+}
+
+
+impl DeviceInner {
+	/// Close the device now if `inner` is its last handle, leaving nothing for `drop` to do.
+	fn close(inner: Arc<DeviceInner>) -> AltoResult<()> {
+		let mut inner = Arc::try_unwrap(inner).map_err(|_| AltoError::InvalidOperation)?;
+		let dev = mem::replace(&mut inner.dev, ptr::null_mut());
+		if unsafe { inner.alto.0.api.alcCloseDevice(dev) } == sys::ALC_TRUE {
+			Ok(())
+		} else {
+			match inner.alto.get_error(dev) {
+				Ok(..) => Err(AltoError::InvalidDevice),
+				Err(e) => Err(e),
+			}
+		}
+	}
 }
 
 
 impl Drop for DeviceInner {
 	fn drop(&mut self) {
-		unsafe { self.alto.0.api.alcCloseDevice(self.dev); }
+		if !self.dev.is_null() {
+			unsafe { self.alto.0.api.alcCloseDevice(self.dev); }
+		}
 	}
 }
 
 
 impl OutputDevice {
+	/// `alcCloseDevice()`
+	/// Close the device and report whether it closed cleanly, which dropping it does not.
+	/// Fails with `AltoError::InvalidOperation` if other handles to the device, such as its contexts or
+	/// handles from `to_device`, are still alive, in which case it closes once the last of them is dropped.
+	pub fn close(self) -> AltoResult<()> {
+		DeviceInner::close(self.0)
+	}
+
+
 	fn make_attrs_vec(&self, attrs: Option<ContextAttrs>) -> AltoResult<Option<Vec<sys::ALCint>>> {
+		let attrs = self.0.alto.0.hints.lock().clone().context_attrs(&self.0, attrs);
 		let mut attrs_vec = Vec::with_capacity(17);
 		if let Some(attrs) = attrs {
 			if let Some(freq) = attrs.frequency {
@@ -534,9 +1150,11 @@ impl OutputDevice {
 
 
 	/// `alcCreateContext()`
+	/// All contexts of a device share one buffer namespace, so buffers created from any of them may be used
+	/// by the sources of the new context. Sources are not shared.
 	pub fn new_context(&self, attrs: Option<ContextAttrs>) -> AltoResult<Context> {
 		let attrs_vec = self.make_attrs_vec(attrs)?;
-		let ctx = unsafe { self.0.alto.0.api.alcCreateContext(self.0.dev, attrs_vec.map(|a| a.as_slice().as_ptr()).unwrap_or(ptr::null())) };
+		let ctx = unsafe { self.0.alto.0.api.alcCreateContext(self.0.dev, attrs_vec.as_ref().map(|a| a.as_slice().as_ptr()).unwrap_or(ptr::null())) };
 		if ctx == ptr::null_mut() {
 			match self.0.alto.get_error(self.0.dev) {
 				Ok(..) => Err(AltoError::NullError),
@@ -548,6 +1166,24 @@ impl OutputDevice {
 	}
 
 
+	/// `alcCreateContext()`
+	/// Requires `ALC_SOFT_HRTF`
+	/// Create a context with HRTF requested, failing with `AltoError::HrtfUnavailable` unless the device then
+	/// reports HRTF as `Enabled` or `Required`. The context is dropped on failure, so an application that
+	/// cannot work without HRTF never runs silently degraded.
+	pub fn new_context_require_hrtf(&self, attrs: Option<ContextAttrs>) -> AltoResult<Context> {
+		self.0.exts.ALC_SOFT_HRTF()?;
+		let mut attrs = attrs.unwrap_or_default();
+		attrs.soft_hrtf = Some(true);
+
+		let ctx = self.new_context(Some(attrs))?;
+		match self.soft_hrtf_status() {
+			SoftHrtfStatus::Enabled | SoftHrtfStatus::Required => Ok(ctx),
+			status => Err(AltoError::HrtfUnavailable(status)),
+		}
+	}
+
+
 	/// `alcDevicePauseSOFT()`
 	/// Requires `ALC_SOFT_pause_device`
 	pub fn soft_pause(&self) -> AltoResult<()> {
@@ -573,14 +1209,71 @@ impl OutputDevice {
 	}
 
 
-	/// `alcDevicePauseSOFT()`
+	/// `alcReopenDeviceSOFT()`
+	/// Requires `ALC_SOFT_reopen_device`
+	/// Move this device to the output named by `spec`, or the default output if `None`, keeping its contexts,
+	/// sources and buffers. Playback continues on the new output. If reopening fails, the device stays on its
+	/// previous output. `specifier` still reports the name the device was originally opened with.
+	pub fn soft_reopen(&self, spec: Option<&CStr>, attrs: Option<ContextAttrs>) -> AltoResult<()> {
+		let ards = self.0.exts.ALC_SOFT_reopen_device()?.alcReopenDeviceSOFT?;
+		let attrs_vec = self.make_attrs_vec(attrs)?;
+		if unsafe { ards(self.0.dev, spec.map(|s| s.as_ptr()).unwrap_or(ptr::null()), attrs_vec.as_ref().map(|a| a.as_slice().as_ptr()).unwrap_or(ptr::null())) } == sys::ALC_TRUE {
+			Ok(())
+		} else {
+			match self.0.alto.get_error(self.0.dev) {
+				Ok(..) => Err(AltoError::InvalidDevice),
+				Err(e) => Err(e),
+			}
+		}
+	}
+
+
+	/// `alcResetDeviceSOFT()`
 	/// Requires `ALC_SOFT_HRTF`
+	/// Passing `None` resets the device with a null attribute list, unless config hints are set.
 	pub fn soft_reset(&self, attrs: Option<ContextAttrs>) -> AltoResult<()> {
 		let ards = self.0.exts.ALC_SOFT_HRTF()?.alcResetDeviceSOFT?;
 		let attrs_vec = self.make_attrs_vec(attrs.into())?;
-		unsafe { ards(self.0.dev, attrs_vec.map(|a| a.as_slice().as_ptr()).unwrap_or(ptr::null())) };
+		unsafe { ards(self.0.dev, attrs_vec.as_ref().map(|a| a.as_slice().as_ptr()).unwrap_or(ptr::null())) };
 		self.0.alto.get_error(self.0.dev)
 	}
+
+
+	/// `alcResetDeviceSOFT()`
+	/// Requires `ALC_SOFT_HRTF`
+	/// Enable or disable HRTF, resetting the device with its current frequency, refresh rate,
+	/// source counts, output limiter and auxiliary send settings read back first so that only HRTF changes.
+	/// Resetting the device may still cause a brief audible glitch.
+	pub fn set_soft_hrtf(&self, value: bool) -> AltoResult<()> {
+		self.0.exts.ALC_SOFT_HRTF()?;
+
+		let get = |param| {
+			let mut value = 0;
+			unsafe { self.0.alto.0.api.alcGetIntegerv(self.0.dev, param, 1, &mut value); }
+			if value > 0 { Some(value) } else { None }
+		};
+		let attrs = ContextAttrs{
+			frequency: get(sys::ALC_FREQUENCY),
+			refresh: get(sys::ALC_REFRESH),
+			mono_sources: get(sys::ALC_MONO_SOURCES),
+			stereo_sources: get(sys::ALC_STEREO_SOURCES),
+			soft_hrtf: Some(value),
+			soft_hrtf_id: None,
+			soft_output_limiter: if self.0.exts.ALC_SOFT_output_limiter().is_ok() { Some(self.0.soft_output_limiter()) } else { None },
+			max_aux_sends: if self.0.exts.ALC_EXT_EFX().is_ok() { Some(self.0.max_aux_sends()) } else { None },
+		};
+		self.0.alto.get_error(self.0.dev)?;
+
+		self.soft_reset(Some(attrs))
+	}
+
+
+	/// `alcResetDeviceSOFT(NULL)`
+	/// Requires `ALC_SOFT_HRTF`
+	/// Reset the device to the implementation's default attributes, as amended by any config hints.
+	pub fn soft_reset_default(&self) -> AltoResult<()> {
+		self.soft_reset(None)
+	}
 }
 
 
@@ -589,12 +1282,16 @@ unsafe impl DeviceObject for OutputDevice {
 	#[inline] fn specifier(&self) -> Option<&CStr> { self.0.specifier() }
 	#[inline] fn as_raw(&self) -> *mut sys::ALCdevice { self.0.as_raw() }
 	#[inline] fn connected(&self) -> AltoResult<bool> { self.0.connected() }
+	#[inline] fn clear_error(&self) { self.0.clear_error() }
 
 	#[inline] fn is_extension_present(&self, ext: ext::Alc) -> bool { self.0.is_extension_present(ext) }
+	#[inline] fn extensions(&self) -> ExtensionSet { self.0.extensions() }
 	#[inline] fn enumerate_soft_hrtfs(&self) -> Vec<CString> { self.0.enumerate_soft_hrtfs() }
+	#[inline] fn soft_hrtfs(&self) -> AltoResult<Vec<(sys::ALCint, CString)>> { self.0.soft_hrtfs() }
 	#[inline] fn soft_hrtf_status(&self) -> SoftHrtfStatus { self.0.soft_hrtf_status() }
 	#[inline] fn soft_output_limiter(&self) -> bool { self.0.soft_output_limiter() }
 	#[inline] fn max_aux_sends(&self) -> sys::ALCint { self.0.max_aux_sends() }
+	#[inline] fn soft_clock(&self) -> AltoResult<i64> { self.0.soft_clock() }
 	#[inline] fn to_device(&self) -> Device { Device(self.0.clone()) }
 }
 
@@ -612,9 +1309,19 @@ unsafe impl Sync for OutputDevice { }
 
 
 impl<F: LoopbackFrame> LoopbackDevice<F> {
+	/// `alcCloseDevice()`
+	/// Close the device and report whether it closed cleanly, which dropping it does not.
+	/// Fails with `AltoError::InvalidOperation` if other handles to the device, such as its contexts or
+	/// handles from `to_device`, are still alive, in which case it closes once the last of them is dropped.
+	pub fn close(self) -> AltoResult<()> {
+		DeviceInner::close(self.0)
+	}
+
+
 	fn make_attrs_vec(&self, freq: sys::ALCint, attrs: Option<LoopbackAttrs>) -> AltoResult<Vec<sys::ALCint>> {
 		let asl = self.0.alto.0.exts.ALC_SOFT_loopback()?;
 
+		let attrs = self.0.alto.0.hints.lock().clone().loopback_attrs(&self.0, attrs);
 		let mut attrs_vec = Vec::with_capacity(19);
 		attrs_vec.extend(&[sys::ALC_FREQUENCY, freq]);
 		attrs_vec.extend(&[asl.ALC_FORMAT_CHANNELS_SOFT?, F::channels(&asl)?]);
@@ -653,6 +1360,33 @@ impl<F: LoopbackFrame> LoopbackDevice<F> {
 	}
 
 
+	/// `alcIsRenderFormatSupportedSOFT()`
+	/// Whether the device can render `channels` and `ty` at `freq`, whatever its own frame type.
+	/// A frequency of 0 or less is never supported.
+	pub fn is_render_format_supported(&self, freq: sys::ALCint, channels: LoopbackFormatChannels, ty: LoopbackFormatType) -> AltoResult<bool> {
+		if freq <= 0 {
+			return Ok(false);
+		}
+
+		let asl = self.0.alto.0.exts.ALC_SOFT_loopback()?;
+		let supported = unsafe { asl.alcIsRenderFormatSupportedSOFT?(self.0.dev, freq, channels.into_raw(asl)?, ty.into_raw(asl)?) };
+		Ok(supported == sys::ALC_TRUE)
+	}
+
+
+	/// `alcIsRenderFormatSupportedSOFT()`
+	/// The frequencies out of 8000, 16000, 22050, 44100, 48000 and 96000 at which the device can render
+	/// `channels` and `ty`, in ascending order. Only these common rates are probed, so any other frequency
+	/// should be checked with `is_render_format_supported`. A format the implementation does not know
+	/// yields no frequencies.
+	pub fn supported_frequencies(&self, channels: LoopbackFormatChannels, ty: LoopbackFormatType) -> Vec<sys::ALCint> {
+		PROBED_FREQUENCIES.iter()
+			.cloned()
+			.filter(|&freq| self.is_render_format_supported(freq, channels, ty).unwrap_or(false))
+			.collect()
+	}
+
+
 	/// `alcCreateContext()`
 	pub fn new_context(&self, freq: sys::ALCint, attrs: Option<LoopbackAttrs>) -> AltoResult<Context> {
 		let attrs_vec = self.make_attrs_vec(freq, attrs.into())?;
@@ -672,7 +1406,7 @@ impl<F: LoopbackFrame> LoopbackDevice<F> {
 	/// Returns the number of sample frames rendered to the slice.
 	pub fn soft_render_samples<R: AsBufferDataMut<F>>(&mut self, mut data: R) -> usize {
 		let (data, size) = data.as_buffer_data_mut();
-		let len = cmp::min(size / mem::size_of::<F>(), sys::ALCsizei::max_value() as usize);
+		let len = cmp::min(size / F::bytes_per_frame(), sys::ALCsizei::max_value() as usize);
 		if len == 0 {
 			return 0;
 		}
@@ -685,13 +1419,13 @@ impl<F: LoopbackFrame> LoopbackDevice<F> {
 	}
 
 
-	/// `alcDevicePauseSOFT()`
+	/// `alcResetDeviceSOFT()`
 	/// Requires `ALC_SOFT_HRTF`
 	pub fn soft_reset(&self, freq: sys::ALCint, attrs: Option<LoopbackAttrs>) -> AltoResult<()> {
 		let ards = self.0.exts.ALC_SOFT_HRTF()?.alcResetDeviceSOFT?;
 
-		let attrs_vec = self.make_attrs_vec(freq, attrs.into());
-		unsafe { ards(self.0.dev, attrs_vec.map(|a| a.as_slice().as_ptr()).unwrap_or(ptr::null())) };
+		let attrs_vec = self.make_attrs_vec(freq, attrs.into())?;
+		unsafe { ards(self.0.dev, attrs_vec.as_slice().as_ptr()) };
 		self.0.alto.get_error(self.0.dev)
 	}
 }
@@ -702,12 +1436,16 @@ unsafe impl<F: LoopbackFrame> DeviceObject for LoopbackDevice<F> {
 	#[inline] fn specifier(&self) -> Option<&CStr> { self.0.specifier() }
 	#[inline] fn as_raw(&self) -> *mut sys::ALCdevice { self.0.as_raw() }
 	#[inline] fn connected(&self) -> AltoResult<bool> { self.0.connected() }
+	#[inline] fn clear_error(&self) { self.0.clear_error() }
 
 	#[inline] fn is_extension_present(&self, ext: ext::Alc) -> bool { self.0.is_extension_present(ext) }
+	#[inline] fn extensions(&self) -> ExtensionSet { self.0.extensions() }
 	#[inline] fn enumerate_soft_hrtfs(&self) -> Vec<CString> { self.0.enumerate_soft_hrtfs() }
+	#[inline] fn soft_hrtfs(&self) -> AltoResult<Vec<(sys::ALCint, CString)>> { self.0.soft_hrtfs() }
 	#[inline] fn soft_hrtf_status(&self) -> SoftHrtfStatus { self.0.soft_hrtf_status() }
 	#[inline] fn soft_output_limiter(&self) -> bool { self.0.soft_output_limiter() }
 	#[inline] fn max_aux_sends(&self) -> sys::ALCint { self.0.max_aux_sends() }
+	#[inline] fn soft_clock(&self) -> AltoResult<i64> { self.0.soft_clock() }
 	#[inline] fn to_device(&self) -> Device { Device(self.0.clone()) }
 }
 
@@ -729,16 +1467,31 @@ unsafe impl DeviceObject for Device {
 	#[inline] fn specifier(&self) -> Option<&CStr> { self.0.specifier() }
 	#[inline] fn as_raw(&self) -> *mut sys::ALCdevice { self.0.as_raw() }
 	#[inline] fn connected(&self) -> AltoResult<bool> { self.0.connected() }
+	#[inline] fn clear_error(&self) { self.0.clear_error() }
 
 	#[inline] fn is_extension_present(&self, ext: ext::Alc) -> bool { self.0.is_extension_present(ext) }
+	#[inline] fn extensions(&self) -> ExtensionSet { self.0.extensions() }
 	#[inline] fn enumerate_soft_hrtfs(&self) -> Vec<CString> { self.0.enumerate_soft_hrtfs() }
+	#[inline] fn soft_hrtfs(&self) -> AltoResult<Vec<(sys::ALCint, CString)>> { self.0.soft_hrtfs() }
 	#[inline] fn soft_hrtf_status(&self) -> SoftHrtfStatus { self.0.soft_hrtf_status() }
 	#[inline] fn soft_output_limiter(&self) -> bool { self.0.soft_output_limiter() }
 	#[inline] fn max_aux_sends(&self) -> sys::ALCint { self.0.max_aux_sends() }
+	#[inline] fn soft_clock(&self) -> AltoResult<i64> { self.0.soft_clock() }
 	#[inline] fn to_device(&self) -> Device { Device(self.0.clone()) }
 }
 
 
+impl Device {
+	/// `alcCloseDevice()`
+	/// Close the device and report whether it closed cleanly, which dropping it does not.
+	/// Fails with `AltoError::InvalidOperation` if other handles to the device, such as its contexts or
+	/// handles from `to_device`, are still alive, in which case it closes once the last of them is dropped.
+	pub fn close(self) -> AltoResult<()> {
+		DeviceInner::close(self.0)
+	}
+}
+
+
 impl PartialEq for DeviceObject {
 	fn eq(&self, other: &DeviceObject) -> bool {
 		self.as_raw() == other.as_raw()
@@ -756,15 +1509,43 @@ impl<F: StandardFrame> Capture<F> {
 	#[inline] pub fn as_raw(&self) -> *mut sys::ALCdevice { self.dev }
 
 
+	/// `alcCaptureCloseDevice()`
+	/// Close the device and report whether it closed cleanly, which dropping it does not.
+	pub fn close(mut self) -> AltoResult<()> {
+		let dev = mem::replace(&mut self.dev, ptr::null_mut());
+		if unsafe { self.alto.0.api.alcCaptureCloseDevice(dev) } == sys::ALC_TRUE {
+			Ok(())
+		} else {
+			match self.alto.get_error(dev) {
+				Ok(..) => Err(AltoError::InvalidDevice),
+				Err(e) => Err(e),
+			}
+		}
+	}
+
+
+	/// `alcGetIntegerv(ALC_CONNECTED)`
+	/// Requires `ALC_EXT_disconnect`
+	/// A disconnected capture device yields no further samples, and must be reopened.
+	pub fn connected(&self) -> AltoResult<bool> {
+		let mut value = 0;
+		unsafe { self.alto.0.api.alcGetIntegerv(self.dev, self.exts.ALC_EXT_DISCONNECT()?.ALC_CONNECTED?, 1, &mut value); }
+		Ok(value == sys::ALC_TRUE as sys::ALCint)
+	}
+
+
 	/// `alcCaptureStart()`
 	pub fn start(&mut self) {
 		unsafe { self.alto.0.api.alcCaptureStart(self.dev); }
+		let available = self.samples_len().max(0) as u64;
+		self.overrun.start(available);
 	}
 
 
 	/// `alcCaptureStop()`
 	pub fn stop(&mut self) {
 		unsafe { self.alto.0.api.alcCaptureStop(self.dev); }
+		self.overrun.started = None;
 	}
 
 
@@ -780,14 +1561,165 @@ impl<F: StandardFrame> Capture<F> {
 	/// Returns the number of sample-frames captured to the slice.
 	pub fn capture_samples<R: AsBufferDataMut<F>>(&mut self, mut data: R) -> AltoResult<usize> {
 		let (data, size) = data.as_buffer_data_mut();
-		let len = cmp::min(size / mem::size_of::<F>(), self.samples_len() as usize);
+		let available = self.samples_len();
+		self.overrun.check(available.max(0) as u64);
+		let len = cmp::min(size / F::bytes_per_frame(), available as usize);
 		if len == 0 {
 			return Ok(0);
 		}
 
 		unsafe { self.alto.0.api.alcCaptureSamples(self.dev, data, len as sys::ALCsizei); }
+		self.overrun.captured += len as u64;
 		Ok(len as usize)
 	}
+
+
+	/// `alcGetIntegerv(ALC_CAPTURE_SAMPLES)`
+	/// Fail with `AltoError::CaptureOverrun` if samples have been found lost since the last call,
+	/// either now or by `capture_samples`.
+	///
+	/// OpenAL does not report overruns: once the ring buffer is full, new samples are silently discarded
+	/// until the application drains it. Backends often keep a larger ring buffer than `open_capture` asked for,
+	/// so it is only taken to be full once the available samples stop growing for a while between checks, or
+	/// reach the count they last stopped growing at. A loss is then estimated by comparing the samples the device
+	/// should have produced at its frequency since `start` with those captured and still available, and reported
+	/// if it is positive. Detecting an overrun therefore takes two checks at least 50 ms apart while the buffer
+	/// is full. Drain at least as often as the buffer takes to fill to avoid overruns.
+	pub fn check_overrun(&mut self) -> AltoResult<()> {
+		let available = self.samples_len().max(0) as u64;
+		self.overrun.check(available);
+		match self.overrun.pending.take() {
+			Some(lost) => Err(AltoError::CaptureOverrun(lost)),
+			None => Ok(()),
+		}
+	}
+
+
+	/// Estimated total number of sample frames lost to overruns since the device was opened.
+	/// See `check_overrun`.
+	pub fn dropped_samples(&self) -> u64 { self.overrun.dropped }
+}
+
+
+impl OverrunTracker {
+	fn start(&mut self, available: u64) {
+		self.started = Some((Instant::now(), available));
+		self.unchanged = None;
+		self.captured = 0;
+		self.lost = 0;
+	}
+
+
+	fn check(&mut self, available: u64) {
+		let now = Instant::now();
+		// Draining in between resets the count, since `available` is only compared with itself.
+		let since = match self.unchanged {
+			Some((since, count)) if count == available => since,
+			_ => {
+				self.unchanged = Some((now, available));
+				now
+			},
+		};
+		if available < self.len.max(1) as u64 {
+			return;
+		}
+
+		// Startup latency, clock drift and rings larger than requested all put the clock ahead of the samples,
+		// so only a ring that has stopped growing, or has reached the size it stopped growing at, counts as full.
+		let at_capacity = matches!(self.capacity, Some(capacity) if available >= capacity);
+		if !at_capacity && now.duration_since(since) < CAPTURE_STALL {
+			return;
+		}
+		self.capacity = Some(cmp::max(self.capacity.unwrap_or(0), available));
+
+		let lost = match self.started {
+			Some((start, base)) => {
+				let expected = base + (start.elapsed().as_secs_f64() * self.freq as f64) as u64;
+				expected.saturating_sub(self.captured + available + self.lost)
+			},
+			None => 0,
+		};
+		if lost == 0 {
+			return;
+		}
+		self.lost += lost;
+		self.dropped += lost;
+		self.pending = Some(self.pending.unwrap_or(0) + lost);
+	}
+}
+
+
+impl ConnectionWatcher {
+	/// A watcher for a device that has not yet been seen disconnected.
+	pub fn new() -> ConnectionWatcher { ConnectionWatcher{disconnected: false} }
+
+
+	/// `alcGetIntegerv(ALC_CONNECTED)`
+	/// Requires `ALC_EXT_disconnect`
+	/// Check the device, returning `ConnectionEvent::Disconnected` the first time it is found disconnected
+	/// and `None` on every other call.
+	pub fn poll<D: WatchConnection + ?Sized>(&mut self, dev: &D) -> AltoResult<Option<ConnectionEvent>> {
+		if self.disconnected || dev.watch_connected()? {
+			return Ok(None);
+		}
+
+		self.disconnected = true;
+		Ok(Some(ConnectionEvent::Disconnected))
+	}
+
+
+	/// Whether a disconnection has been reported.
+	pub fn is_disconnected(&self) -> bool { self.disconnected }
+}
+
+
+impl<D: DeviceObject + ?Sized> WatchConnection for D {
+	#[inline] fn watch_connected(&self) -> AltoResult<bool> { self.connected() }
+}
+impl<F: StandardFrame> WatchConnection for Capture<F> {
+	#[inline] fn watch_connected(&self) -> AltoResult<bool> { self.connected() }
+}
+
+
+impl ExtensionSet {
+	/// Whether `ext` is present.
+	pub fn contains(&self, ext: ext::Alc) -> bool {
+		match ext {
+			ext::Alc::Dedicated => self.dedicated,
+			ext::Alc::Disconnect => self.disconnect,
+			ext::Alc::Efx => self.efx,
+			ext::Alc::SoftHrtf => self.soft_hrtf,
+			ext::Alc::SoftPauseDevice => self.soft_pause_device,
+			ext::Alc::SoftOutputLimiter => self.soft_output_limiter,
+			ext::Alc::SoftDeviceClock => self.soft_device_clock,
+			ext::Alc::SoftReopenDevice => self.soft_reopen_device,
+		}
+	}
+}
+
+
+impl fmt::Display for ExtensionSet {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let names = [
+			(self.dedicated, "ALC_EXT_DEDICATED"),
+			(self.disconnect, "ALC_EXT_disconnect"),
+			(self.efx, "ALC_EXT_EFX"),
+			(self.soft_hrtf, "ALC_SOFT_HRTF"),
+			(self.soft_pause_device, "ALC_SOFT_pause_device"),
+			(self.soft_output_limiter, "ALC_SOFT_output_limiter"),
+			(self.soft_device_clock, "ALC_SOFT_device_clock"),
+			(self.soft_reopen_device, "ALC_SOFT_reopen_device"),
+		];
+		let mut first = true;
+		for &(_, name) in names.iter().filter(|n| n.0) {
+			if !first {
+				write!(f, " ")?;
+			}
+			write!(f, "{}", name)?;
+			first = false;
+		}
+		Ok(())
+	}
 }
 
 
@@ -800,7 +1732,9 @@ impl<F: StandardFrame> Eq for Capture<F> { }
 
 impl<F: StandardFrame> Drop for Capture<F> {
 	fn drop(&mut self) {
-		unsafe { self.alto.0.api.alcCaptureCloseDevice(self.dev); }
+		if !self.dev.is_null() {
+			unsafe { self.alto.0.api.alcCaptureCloseDevice(self.dev); }
+		}
 	}
 }
 
@@ -203,6 +203,8 @@ pub struct EqualizerEffect {
 
 /// `AL_EFFECT_DEDICATED_LOW_FREQUENCY_EFFECT`
 /// Requires `ALC_EXT_DEDICATED`
+/// Routes the input of the effect slot it is loaded into directly to the LFE channel, scaled by its gain,
+/// bypassing panning. Sources reach it through `Source::set_aux_send`.
 pub struct DedicatedLowFrequencyEffect {
 	ctx: al::Context,
 	effect: sys::ALuint,
@@ -211,6 +213,8 @@ pub struct DedicatedLowFrequencyEffect {
 
 /// `AL_EFFECT_DEDICATED_DIALOGUE`
 /// Requires `ALC_EXT_DEDICATED`
+/// Routes the input of the effect slot it is loaded into directly to the front center channel, scaled by its gain,
+/// bypassing panning. Sources reach it through `Source::set_aux_send`.
 pub struct DedicatedDialogueEffect {
 	ctx: al::Context,
 	effect: sys::ALuint,
@@ -478,6 +482,15 @@ impl AuxEffectSlot {
 		unsafe { efx.alAuxiliaryEffectSloti.unwrap()(self.slot, efx.AL_EFFECTSLOT_EFFECT.unwrap(), value.as_raw() as sys::ALint); }
 		self.ctx.get_error()
 	}
+	/// `alAuxiliaryEffectSloti(AL_EFFECTSLOT_EFFECT)` within `alDeferUpdatesSOFT()`
+	/// Swap the effect as part of a batch of deferred updates, so that it is applied together with
+	/// any other changes made while a `DeferLock` is held, such as fading the send gains of a reverb zone.
+	/// Without `AL_SOFT_deferred_updates` this behaves like `set_effect` and the swap is applied immediately.
+	pub fn set_effect_deferred<E: Effect>(&mut self, value: &E) -> AltoResult<()> {
+		let ctx = self.ctx.clone();
+		let _defer = ctx.defer_updates();
+		self.set_effect(value)
+	}
 	/// `alAuxiliaryEffectSloti(AL_EFFECTSLOT_EFFECT)`
 	pub fn clear_effect(&mut self) {
 		let efx = self.ctx.0.dev.0.exts.ALC_EXT_EFX().unwrap();
@@ -524,11 +537,12 @@ impl AuxEffectSlot {
 impl Drop for AuxEffectSlot {
 	fn drop(&mut self) {
 		let efx = self.ctx.0.dev.0.exts.ALC_EXT_EFX().unwrap();
+		// A source upgraded here may become the last reference if another thread drops its own, and its destructor
+		// takes the context lock, so the sources are declared first to be dropped only after the lock is released.
+		let inputs: Vec<_> = self.inputs.drain(..).filter_map(|src| src.upgrade()).collect();
 		let _lock = self.ctx.make_current(true);
-		for src in self.inputs.drain(..) {
-			if let Some(src) = src.upgrade() {
-				src.clear_aux_effect_slot(self.slot);
-			}
+		for src in &inputs {
+			src.clear_aux_effect_slot(self.slot);
 		}
 
 		let adaes = efx.alDeleteAuxiliaryEffectSlots.unwrap();
@@ -810,10 +824,10 @@ impl EaxReverbEffect {
 		value.into()
 	}
 	/// `alEffectfv(AL_EAXREVERB_REFLECTIONS_PAN)`
-	pub fn set_reflections_pan<V: Into<[f32; 3]>>(&mut self, value: V) -> AltoResult<()> {
+	pub fn set_reflections_pan<V: al::AsAlVec3>(&mut self, value: V) -> AltoResult<()> {
 		let efx = self.ctx.0.dev.0.exts.ALC_EXT_EFX().unwrap();
 		let _lock = self.ctx.make_current(true);
-		unsafe { efx.alEffectfv.unwrap()(self.effect, efx.AL_EAXREVERB_REFLECTIONS_PAN.unwrap(), &mut value.into() as *mut [f32; 3] as *mut f32); }
+		unsafe { efx.alEffectfv.unwrap()(self.effect, efx.AL_EAXREVERB_REFLECTIONS_PAN.unwrap(), &mut value.as_al_vec3() as *mut [f32; 3] as *mut f32); }
 		self.ctx.get_error()
 	}
 
@@ -861,10 +875,10 @@ impl EaxReverbEffect {
 		value.into()
 	}
 	/// `alEffectfv(AL_EAXREVERB_LATE_REVERB_PAN)`
-	pub fn set_late_reverb_pan<V: Into<[f32; 3]>>(&mut self, value: V) -> AltoResult<()> {
+	pub fn set_late_reverb_pan<V: al::AsAlVec3>(&mut self, value: V) -> AltoResult<()> {
 		let efx = self.ctx.0.dev.0.exts.ALC_EXT_EFX().unwrap();
 		let _lock = self.ctx.make_current(true);
-		unsafe { efx.alEffectfv.unwrap()(self.effect, efx.AL_EAXREVERB_LATE_REVERB_PAN.unwrap(), &mut value.into() as *mut [f32; 3] as *mut f32); }
+		unsafe { efx.alEffectfv.unwrap()(self.effect, efx.AL_EAXREVERB_LATE_REVERB_PAN.unwrap(), &mut value.as_al_vec3() as *mut [f32; 3] as *mut f32); }
 		self.ctx.get_error()
 	}
 
@@ -1516,6 +1530,23 @@ impl DistortionEffect {
 	}
 
 
+	/// `alGetEffectf(AL_DISTORTION_GAIN)`
+	pub fn gain(&self) -> f32 {
+		let efx = self.ctx.0.dev.0.exts.ALC_EXT_EFX().unwrap();
+		let _lock = self.ctx.make_current(true);
+		let mut value = 0.0;
+		unsafe { efx.alGetEffectf.unwrap()(self.effect, efx.AL_DISTORTION_GAIN.unwrap(), &mut value); }
+		value
+	}
+	/// `alEffectf(AL_DISTORTION_GAIN)`
+	pub fn set_gain(&mut self, value: f32) -> AltoResult<()> {
+		let efx = self.ctx.0.dev.0.exts.ALC_EXT_EFX().unwrap();
+		let _lock = self.ctx.make_current(true);
+		unsafe { efx.alEffectf.unwrap()(self.effect, efx.AL_DISTORTION_GAIN.unwrap(), value); }
+		self.ctx.get_error()
+	}
+
+
 	/// `alGetEffectf(AL_DISTORTION_LOWPASS_CUTOFF)`
 	pub fn lowpass_cutoff(&self) -> f32 {
 		let efx = self.ctx.0.dev.0.exts.ALC_EXT_EFX().unwrap();
@@ -166,6 +166,11 @@ pub struct ExtensionError;
 pub type ExtResult<T> = ::std::result::Result<T, ExtensionError>;
 
 
+#[doc(hidden)]
+#[allow(non_camel_case_types)]
+pub type ALCEVENTPROCTYPESOFT = unsafe extern "C" fn(eventType: ALCenum, deviceType: ALCenum, device: *mut ALCdevice, length: ALCsizei, message: *const ALCchar, userParam: *mut ALCvoid);
+
+
 #[derive(Copy, Clone, PartialEq, Hash, Eq, Debug)]
 pub enum AlcNull {
 	/// `ALC_ENUMERATE_ALL_EXT`
@@ -174,6 +179,8 @@ pub enum AlcNull {
 	SoftLoopback,
 	/// `ALC_EXT_thread_local_context`
 	ThreadLocalContext,
+	/// `ALC_SOFT_system_events`
+	SoftSystemEvents,
 }
 
 
@@ -191,6 +198,10 @@ pub enum Alc {
 	SoftPauseDevice,
 	/// `ALC_SOFT_output_limiter`
 	SoftOutputLimiter,
+	/// `ALC_SOFT_device_clock`
+	SoftDeviceClock,
+	/// `ALC_SOFT_reopen_device`
+	SoftReopenDevice,
 }
 
 
@@ -222,6 +233,8 @@ pub enum Al {
 	SoftDeferredUpdates,
 	/// `AL_SOFT_direct_channels`
 	SoftDirectChannels,
+	/// `AL_SOFT_direct_channels_remix`
+	SoftDirectChannelsRemix,
 	/// `AL_SOFT_loop_points`
 	SoftLoopPoints,
 	/// `AL_SOFT_MSADPCM`
@@ -242,6 +255,14 @@ pub enum Al {
 	StereoAngles,
 	/// `AL_EXT_SOURCE_RADIUS`
 	SourceRadius,
+	/// `AL_SOFT_source_start_delay`
+	SoftSourceStartDelay,
+	/// `AL_SOFT_events`
+	SoftEvents,
+	/// `AL_SOFT_callback_buffer`
+	SoftCallbackBuffer,
+	/// `AL_SOFT_buffer_length_query`
+	SoftBufferLengthQuery,
 }
 
 
@@ -282,6 +303,21 @@ alc_ext! {
 		pub fn alcSetThreadContext: unsafe extern "C" fn(ctx: *mut ALCcontext) -> ALCboolean,
 		pub fn alcGetThreadContext: unsafe extern "C" fn() -> *mut ALCcontext,
 	}
+
+
+	pub ext ALC_SOFT_system_events {
+		pub const ALC_PLAYBACK_DEVICE_SOFT,
+		pub const ALC_CAPTURE_DEVICE_SOFT,
+		pub const ALC_EVENT_TYPE_DEFAULT_DEVICE_CHANGED_SOFT,
+		pub const ALC_EVENT_TYPE_DEVICE_ADDED_SOFT,
+		pub const ALC_EVENT_TYPE_DEVICE_REMOVED_SOFT,
+		pub const ALC_EVENT_SUPPORTED_SOFT,
+		pub const ALC_EVENT_NOT_SUPPORTED_SOFT,
+
+		pub fn alcEventIsSupportedSOFT: unsafe extern "C" fn(eventType: ALCenum, deviceType: ALCenum) -> ALCenum,
+		pub fn alcEventControlSOFT: unsafe extern "C" fn(count: ALCsizei, events: *const ALCenum, enable: ALCboolean) -> ALCboolean,
+		pub fn alcEventCallbackSOFT: unsafe extern "C" fn(callback: Option<ALCEVENTPROCTYPESOFT>, userParam: *mut ALCvoid),
+	}
 }
 
 
@@ -505,11 +541,37 @@ alc_ext! {
 
 		pub fn alcResetDeviceSOFT: unsafe extern "C" fn(dev: *mut ALCdevice, attrList: *const ALCint) -> ALCboolean,
 	}
+
+
+	pub ext ALC_SOFT_device_clock {
+		pub const ALC_DEVICE_CLOCK_SOFT,
+		pub const ALC_DEVICE_LATENCY_SOFT,
+		pub const ALC_DEVICE_CLOCK_LATENCY_SOFT,
+
+		pub fn alcGetInteger64vSOFT: unsafe extern "C" fn(dev: *mut ALCdevice, pname: ALCenum, size: ALsizei, values: *mut ALCint64SOFT),
+	}
+
+
+	pub ext ALC_SOFT_reopen_device {
+		pub fn alcReopenDeviceSOFT: unsafe extern "C" fn(dev: *mut ALCdevice, deviceName: *const ALCchar, attribs: *const ALCint) -> ALCboolean,
+	}
 }
 
 
 pub type ALint64SOFT = i64;
 pub type ALuint64SOFT = u64;
+pub type ALCint64SOFT = i64;
+pub type ALCuint64SOFT = u64;
+
+
+#[doc(hidden)]
+#[allow(non_camel_case_types)]
+pub type ALEVENTPROCSOFT = unsafe extern "C" fn(eventType: ALenum, object: ALuint, param: ALuint, length: ALsizei, message: *const ALchar, userParam: *mut ALvoid);
+
+
+#[doc(hidden)]
+#[allow(non_camel_case_types)]
+pub type ALBUFFERCALLBACKTYPESOFT = unsafe extern "C" fn(userptr: *mut ALvoid, sampledata: *mut ALvoid, numbytes: ALsizei) -> ALsizei;
 
 
 al_ext! {
@@ -673,6 +735,12 @@ al_ext! {
 	}
 
 
+	pub ext AL_SOFT_direct_channels_remix {
+		pub const AL_DROP_UNMATCHED_SOFT,
+		pub const AL_REMIX_UNMATCHED_SOFT,
+	}
+
+
 	pub ext AL_SOFT_loop_points {
 		pub const AL_LOOP_POINTS_SOFT,
 	}
@@ -744,6 +812,36 @@ al_ext! {
 		pub const AL_SOURCE_SPATIALIZE_SOFT,
 		pub const AL_AUTO_SOFT,
 	}
+
+
+	pub ext AL_SOFT_events {
+		pub const AL_EVENT_CALLBACK_FUNCTION_SOFT,
+		pub const AL_EVENT_CALLBACK_USER_PARAM_SOFT,
+		pub const AL_EVENT_TYPE_BUFFER_COMPLETED_SOFT,
+		pub const AL_EVENT_TYPE_SOURCE_STATE_CHANGED_SOFT,
+		pub const AL_EVENT_TYPE_DISCONNECTED_SOFT,
+
+		pub fn alEventControlSOFT: unsafe extern "C" fn(count: ALsizei, types: *const ALenum, enable: ALboolean),
+		pub fn alEventCallbackSOFT: unsafe extern "C" fn(callback: Option<ALEVENTPROCSOFT>, userParam: *mut ALvoid),
+	}
+
+
+	pub ext AL_SOFT_source_start_delay {
+		pub fn alSourcePlayAtTimeSOFT: unsafe extern "C" fn(source: ALuint, start_time: ALint64SOFT),
+		pub fn alSourcePlayAtTimevSOFT: unsafe extern "C" fn(n: ALsizei, sources: *const ALuint, start_time: ALint64SOFT),
+	}
+
+
+	pub ext AL_SOFT_callback_buffer {
+		pub fn alBufferCallbackSOFT: unsafe extern "C" fn(buffer: ALuint, format: ALenum, freq: ALsizei, callback: Option<ALBUFFERCALLBACKTYPESOFT>, userptr: *mut ALvoid),
+	}
+
+
+	pub ext AL_SOFT_buffer_length_query {
+		pub const AL_BYTE_LENGTH_SOFT,
+		pub const AL_SAMPLE_LENGTH_SOFT,
+		pub const AL_SEC_LENGTH_SOFT,
+	}
 }
 
 
@@ -17,6 +17,8 @@
 extern crate lazy_static;
 extern crate parking_lot;
 extern crate al_sys;
+#[cfg(feature = "bytemuck")]
+extern crate bytemuck;
 
 use std::error::Error as StdError;
 use std::fmt;
@@ -37,6 +39,10 @@ pub mod ext;
 pub mod efx;
 
 
+#[cfg(feature = "wav")]
+pub mod wav;
+
+
 pub mod sys {
 	pub use al_sys::*;
 }
@@ -72,6 +78,34 @@ pub enum AltoError {
 	WrongDevice,
 	/// A resource belongs to another context and is not eligible.
 	WrongContext,
+	/// An orientation vector is degenerate. The message names the offending vector. Alto specific.
+	InvalidOrientation(&'static str),
+	/// HRTF was required but the device reported this status instead of enabling it. Alto specific.
+	HrtfUnavailable(SoftHrtfStatus),
+	/// A seek position lies outside the source's buffer data. Carries the length of that data in the same unit. Alto specific.
+	SeekOutOfRange(SeekPos),
+	/// The named operation requires a stopped or initial source, but the source is playing or paused. Alto specific.
+	SourcePlaying(&'static str),
+	/// A capture device's ring buffer filled up, so captured samples were lost. Carries the estimated
+	/// number of lost sample frames, which is always positive. Alto specific.
+	CaptureOverrun(u64),
+	/// OpenAL reports a source as a different kind than the operation requires. Carries the reported type. Alto specific.
+	SourceTypeMismatch(SourceType),
+	/// Every pooled source of `Context::play_once` is busy, the pool is at its limit, and the policy forbids stealing. Alto specific.
+	VoicesExhausted,
+	/// An operation failed because the context's device has been disconnected. Alto specific.
+	DeviceDisconnected,
+	/// A capture device could not be opened with the requested frequency and format. Alto specific.
+	CaptureFormatUnsupported{freq: sys::ALCuint, format: Format},
+	/// An auxiliary send index is negative or not below the number of sends the source supports. Alto specific.
+	SendOutOfRange{index: sys::ALint, max: sys::ALint},
+	/// A property value was rejected, either before being applied or by OpenAL. Carries the name of the property, such as `AL_GAIN`,
+	/// and the error it was rejected with, which is `InvalidValue` for a value refused before being applied. Alto specific.
+	InvalidProperty{name: &'static str, cause: Box<AltoError>},
+	/// Loop points were not within `0 <= start < end <= len`, where `len` is the length of the buffer in sample frames. Alto specific.
+	LoopPointsOutOfRange{start: sys::ALint, end: sys::ALint, len: sys::ALint},
+	/// The operation can't be performed while the buffer is attached to a source. Alto specific.
+	BufferInUse,
 	/// There was an underlying IO error, usually from a failure when loading the OpenAL dylib. Alto specific.
 	Io(io::Error),
 }
@@ -103,6 +137,16 @@ impl AltoError {
 			e => AltoError::UnknownAlError(e),
 		}
 	}
+
+
+	/// Map an AL error raised by a context whose device reported `connected`, as `Context` does.
+	/// Any error is reported as `AltoError::DeviceDisconnected` once the device is known to be lost.
+	pub(crate) fn from_al_on_device(al: sys::ALenum, connected: AltoResult<bool>) -> AltoError {
+		match connected {
+			Ok(false) => AltoError::DeviceDisconnected,
+			_ => AltoError::from_al(al),
+		}
+	}
 }
 
 
@@ -131,6 +175,19 @@ impl StdError for AltoError {
 			AltoError::NullError => "ALTO ERROR: Return value is NULL with no error code",
 			AltoError::WrongDevice => "ALTO ERROR: Resource used on wrong device",
 			AltoError::WrongContext => "ALTO ERROR: Resource used on wrong device",
+			AltoError::InvalidOrientation(msg) => msg,
+			AltoError::HrtfUnavailable(..) => "ALTO ERROR: HRTF Unavailable",
+			AltoError::SeekOutOfRange(..) => "ALTO ERROR: Seek Out Of Range",
+			AltoError::SourcePlaying(..) => "ALTO ERROR: Source is playing or paused",
+			AltoError::CaptureOverrun(..) => "ALTO ERROR: Capture Overrun",
+			AltoError::SourceTypeMismatch(..) => "ALTO ERROR: Source Type Mismatch",
+			AltoError::VoicesExhausted => "ALTO ERROR: Voices Exhausted",
+			AltoError::DeviceDisconnected => "ALTO ERROR: Device Disconnected",
+			AltoError::CaptureFormatUnsupported{..} => "ALTO ERROR: Capture Format Unsupported",
+			AltoError::SendOutOfRange{..} => "ALTO ERROR: Send Out Of Range",
+			AltoError::InvalidProperty{..} => "ALTO ERROR: Invalid Property",
+			AltoError::LoopPointsOutOfRange{..} => "ALTO ERROR: Loop Points Out Of Range",
+			AltoError::BufferInUse => "ALTO ERROR: Buffer In Use",
 			AltoError::Io(ref io) => io.description(),
 		}
 	}
@@ -149,3 +206,27 @@ impl From<ext::ExtensionError> for AltoError {
 		AltoError::ExtensionNotPresent
 	}
 }
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+
+	#[test]
+	fn from_al_on_device() {
+		// No backend can be made to lose its device on demand, so the mapping `Context` applies is checked directly.
+		match AltoError::from_al_on_device(sys::AL_INVALID_OPERATION, Ok(false)) {
+			AltoError::DeviceDisconnected => (),
+			e => panic!("{:?}", e),
+		}
+		match AltoError::from_al_on_device(sys::AL_INVALID_VALUE, Ok(true)) {
+			AltoError::InvalidValue => (),
+			e => panic!("{:?}", e),
+		}
+		match AltoError::from_al_on_device(sys::AL_INVALID_NAME, Err(AltoError::ExtensionNotPresent)) {
+			AltoError::InvalidName => (),
+			e => panic!("{:?}", e),
+		}
+	}
+}
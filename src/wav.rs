@@ -0,0 +1,158 @@
+//! Minimal WAV file output for rendered loopback audio.
+//! Requires the `wav` feature.
+
+use std::io::{self, Write, Seek, SeekFrom};
+use std::marker::PhantomData;
+use std::mem;
+use std::slice;
+
+use sys;
+use alc::LoopbackFrame;
+
+
+const WAVE_FORMAT_PCM: u16 = 0x0001;
+const WAVE_FORMAT_IEEE_FLOAT: u16 = 0x0003;
+const WAVE_FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+
+const KSDATAFORMAT_SUBTYPE_TAIL: [u8; 14] = [0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x80, 0x00, 0x00, 0xAA, 0x00, 0x38, 0x9B, 0x71];
+
+
+/// Implemented for sample types that can be stored in a WAV file.
+pub trait WavSample: Copy {
+	/// Format tag identifying the sample encoding.
+	fn format_tag() -> u16;
+	/// Write this sample in little endian byte order.
+	fn write_le<W: Write>(self, w: &mut W) -> io::Result<()>;
+}
+
+
+/// Writes sample frames to a seekable sink as a WAV file.
+/// The header is written up front with placeholder sizes, which are filled in
+/// whenever the writer is flushed and when it is dropped.
+pub struct WavWriter<F: LoopbackFrame, W: Write + Seek> where F::Sample: WavSample {
+	sink: Option<W>,
+	start: u64,
+	data_len: u64,
+	marker: PhantomData<F>,
+}
+
+
+impl WavSample for u8 {
+	fn format_tag() -> u16 { WAVE_FORMAT_PCM }
+	fn write_le<W: Write>(self, w: &mut W) -> io::Result<()> { w.write_all(&[self]) }
+}
+impl WavSample for i16 {
+	fn format_tag() -> u16 { WAVE_FORMAT_PCM }
+	fn write_le<W: Write>(self, w: &mut W) -> io::Result<()> { w.write_all(&self.to_le_bytes()) }
+}
+impl WavSample for f32 {
+	fn format_tag() -> u16 { WAVE_FORMAT_IEEE_FLOAT }
+	fn write_le<W: Write>(self, w: &mut W) -> io::Result<()> { w.write_all(&self.to_bits().to_le_bytes()) }
+}
+
+
+impl<F: LoopbackFrame, W: Write + Seek> WavWriter<F, W> where F::Sample: WavSample {
+	/// Write a WAV header for frames of type `F` at the given frequency and return a writer
+	/// positioned at the start of the sample data.
+	pub fn new(mut sink: W, freq: sys::ALCint) -> io::Result<WavWriter<F, W>> {
+		if freq <= 0 {
+			return Err(io::Error::new(io::ErrorKind::InvalidInput, "WAV frequency must be positive"));
+		}
+
+		let start = sink.stream_position()?;
+		let channels = F::CHANNELS as u16;
+		let bits = (mem::size_of::<F::Sample>() * 8) as u16;
+		let block_align = channels * bits / 8;
+		let extensible = channels > 2;
+
+		let mut header = Vec::with_capacity(68);
+		header.extend_from_slice(b"RIFF");
+		header.extend_from_slice(&0u32.to_le_bytes());
+		header.extend_from_slice(b"WAVE");
+		header.extend_from_slice(b"fmt ");
+		header.extend_from_slice(&(if extensible { 40u32 } else { 16u32 }).to_le_bytes());
+		header.extend_from_slice(&(if extensible { WAVE_FORMAT_EXTENSIBLE } else { F::Sample::format_tag() }).to_le_bytes());
+		header.extend_from_slice(&channels.to_le_bytes());
+		header.extend_from_slice(&(freq as u32).to_le_bytes());
+		header.extend_from_slice(&(freq as u32 * block_align as u32).to_le_bytes());
+		header.extend_from_slice(&block_align.to_le_bytes());
+		header.extend_from_slice(&bits.to_le_bytes());
+		if extensible {
+			header.extend_from_slice(&22u16.to_le_bytes());
+			header.extend_from_slice(&bits.to_le_bytes());
+			header.extend_from_slice(&channel_mask(channels).to_le_bytes());
+			header.extend_from_slice(&F::Sample::format_tag().to_le_bytes());
+			header.extend_from_slice(&KSDATAFORMAT_SUBTYPE_TAIL);
+		}
+		header.extend_from_slice(b"data");
+		header.extend_from_slice(&0u32.to_le_bytes());
+		sink.write_all(&header)?;
+
+		Ok(WavWriter{sink: Some(sink), start, data_len: 0, marker: PhantomData})
+	}
+
+
+	/// Append sample frames to the data chunk.
+	pub fn write_frames(&mut self, frames: &[F]) -> io::Result<()> {
+		let samples = unsafe { slice::from_raw_parts(frames.as_ptr() as *const F::Sample, frames.len() * F::len()) };
+		let sink = self.sink.as_mut().unwrap();
+		let mut bytes = Vec::with_capacity(mem::size_of_val(frames));
+		for s in samples {
+			s.write_le(&mut bytes)?;
+		}
+		sink.write_all(&bytes)?;
+		self.data_len += bytes.len() as u64;
+		Ok(())
+	}
+
+
+	/// Number of bytes of sample data written so far.
+	pub fn data_len(&self) -> u64 { self.data_len }
+
+
+	/// Update the RIFF and data chunk sizes in the header, then flush the sink.
+	pub fn flush(&mut self) -> io::Result<()> {
+		let start = self.start;
+		let data_len = self.data_len;
+		let sink = self.sink.as_mut().unwrap();
+		let header_len = if F::len() > 2 { 68 } else { 44 };
+		if data_len + header_len - 8 > u32::MAX as u64 {
+			return Err(io::Error::new(io::ErrorKind::InvalidData, "WAV data exceeds 4GiB"));
+		}
+
+		let end = sink.stream_position()?;
+		sink.seek(SeekFrom::Start(start + 4))?;
+		sink.write_all(&((data_len + header_len - 8) as u32).to_le_bytes())?;
+		sink.seek(SeekFrom::Start(start + header_len - 4))?;
+		sink.write_all(&(data_len as u32).to_le_bytes())?;
+		sink.seek(SeekFrom::Start(end))?;
+		sink.flush()
+	}
+
+
+	/// Finalize the header and return the underlying sink.
+	pub fn into_inner(mut self) -> io::Result<W> {
+		self.flush()?;
+		Ok(self.sink.take().unwrap())
+	}
+}
+
+
+impl<F: LoopbackFrame, W: Write + Seek> Drop for WavWriter<F, W> where F::Sample: WavSample {
+	fn drop(&mut self) {
+		if self.sink.is_some() {
+			let _ = self.flush();
+		}
+	}
+}
+
+
+fn channel_mask(channels: u16) -> u32 {
+	match channels {
+		4 => 0x33,
+		6 => 0x3F,
+		7 => 0x70F,
+		8 => 0x63F,
+		_ => 0,
+	}
+}
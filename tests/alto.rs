@@ -1,8 +1,14 @@
 extern crate alto;
+#[cfg(feature = "bytemuck")]
+extern crate bytemuck;
+#[cfg(feature = "mint")]
+extern crate mint;
 
 use std::ffi::CStr;
+use std::sync::mpsc;
+use std::thread;
 
-use alto::{Alto, Capture, DeviceObject, Stereo};
+use alto::{Alto, AltoError, Capture, DeviceObject, ErrorChecking, Source, Stereo};
 
 type MyCapture = Capture<Stereo<i16>>;
 
@@ -19,6 +25,73 @@ fn load_default() {
     load_alto();
 }
 
+#[test]
+fn load_preferred_reports_all_failures() {
+    match Alto::load_preferred(&["/nonexistent/libfirst.so", "/nonexistent/libsecond.so"]) {
+        Err(AltoError::Io(e)) => {
+            let msg = e.to_string();
+            assert!(msg.contains("libfirst.so") && msg.contains("libsecond.so"));
+        },
+        Err(e) => panic!("{:?}", e),
+        Ok(_) => panic!("loaded a nonexistent library"),
+    }
+}
+
+#[test]
+fn sample_frame_formats() {
+    use alto::{ALawSample, BFormat2D, BFormat3D, Mc51Chn, Mc61Chn, Mc71Chn, McQuad, McRear, Mono, MuLawSample, SampleFrame};
+    use alto::sys;
+
+    fn raw<F: SampleFrame>() -> Result<sys::ALint, AltoError> { F::format().into_raw(None) }
+    fn ext<F: SampleFrame>() {
+        match raw::<F>() {
+            Err(AltoError::ExtensionNotPresent) => (),
+            r => panic!("{:?}", r),
+        }
+    }
+
+    assert_eq!(raw::<Mono<u8>>().unwrap(), sys::AL_FORMAT_MONO8);
+    assert_eq!(raw::<Mono<i16>>().unwrap(), sys::AL_FORMAT_MONO16);
+    assert_eq!(raw::<Stereo<u8>>().unwrap(), sys::AL_FORMAT_STEREO8);
+    assert_eq!(raw::<Stereo<i16>>().unwrap(), sys::AL_FORMAT_STEREO16);
+
+    ext::<Mono<f32>>();
+    ext::<Mono<f64>>();
+    ext::<Mono<ALawSample>>();
+    ext::<Mono<MuLawSample>>();
+    ext::<Stereo<f32>>();
+    ext::<Stereo<f64>>();
+    ext::<Stereo<ALawSample>>();
+    ext::<Stereo<MuLawSample>>();
+    ext::<McRear<i16>>();
+    ext::<McQuad<i16>>();
+    ext::<Mc51Chn<f32>>();
+    ext::<Mc61Chn<u8>>();
+    ext::<Mc71Chn<MuLawSample>>();
+    ext::<BFormat2D<i16>>();
+    ext::<BFormat3D<f32>>();
+}
+
+#[test]
+fn interleaved_frames() {
+    use alto::{Mono, SampleFrame};
+
+    let samples = [1i16, -1, 2, -2, 3, -3];
+    let frames = Stereo::<i16>::from_interleaved(&samples).unwrap();
+    assert_eq!(frames, &[Stereo{left: 1, right: -1}, Stereo{left: 2, right: -2}, Stereo{left: 3, right: -3}]);
+    assert_eq!(Stereo::as_interleaved(frames), &samples);
+    assert_eq!(Mono::<i16>::from_interleaved(&samples).unwrap().len(), 6);
+
+    match Stereo::<i16>::from_interleaved(&samples[..5]) {
+        Err(AltoError::InvalidValue) => (),
+        r => panic!("{:?}", r),
+    }
+
+    let mut samples = [0.0f32; 4];
+    Stereo::<f32>::from_interleaved_mut(&mut samples).unwrap()[1].right = 1.0;
+    assert_eq!(samples, [0.0, 0.0, 0.0, 1.0]);
+}
+
 #[test]
 fn default_output() {
     let a = load_alto();
@@ -65,3 +138,2886 @@ fn specified_input() {
         assert_eq!(dev.specifier().unwrap(), device.as_ref());
     }
 }
+
+#[test]
+fn error_checking_toggle() {
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+    let mut src = ctx.new_static_source().unwrap();
+
+    ctx.set_error_checking(ErrorChecking::Never);
+    assert!(src.set_gain(-1.0).is_ok());
+
+    ctx.set_error_checking(ErrorChecking::Always);
+    assert!(src.set_gain(1.0).is_ok());
+    match src.set_gain(-1.0) {
+        Err(AltoError::InvalidValue) => (),
+        r => panic!("expected InvalidValue, got {:?}", r),
+    }
+}
+
+#[test]
+fn drop_context_current_on_other_thread() {
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+    let raw = ctx.as_raw();
+
+    let (tx, rx) = mpsc::channel();
+    let worker = {
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            let mut src = ctx.new_static_source().unwrap();
+            drop(ctx);
+            tx.send(()).unwrap();
+            for _ in 0 .. 1000 {
+                src.set_gain(0.5).unwrap();
+            }
+        })
+    };
+
+    rx.recv().unwrap();
+    drop(ctx);
+    worker.join().unwrap();
+
+    assert!(unsafe { a.raw_api().alcGetCurrentContext() } != raw);
+}
+
+#[test]
+fn config_hints() {
+    let a = load_alto();
+
+    a.set_config_hint("hrtf", "auto").unwrap();
+    a.set_config_hint("sends", "2").unwrap();
+    match a.set_config_hint("hrtf-paths", "/tmp") {
+        Err(AltoError::InvalidEnum) => (),
+        r => panic!("{:?}", r),
+    }
+    match a.set_config_hint("output-limiter", "maybe") {
+        Err(AltoError::InvalidValue) => (),
+        r => panic!("{:?}", r),
+    }
+
+    let dev = a.open(None).unwrap();
+    let _ctx = dev.new_context(None).unwrap();
+}
+
+#[test]
+fn context_suspended_and_current() {
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+
+    assert!(!ctx.is_suspended());
+    {
+        let _outer = ctx.defer_updates();
+        let _inner = ctx.defer_updates();
+        assert_eq!(ctx.is_suspended(), ctx.is_extension_present(alto::ext::Al::SoftDeferredUpdates));
+    }
+    assert!(!ctx.is_suspended());
+
+    ctx.new_static_source().unwrap();
+    assert!(ctx.is_current());
+}
+
+#[test]
+fn context_current_with_multiple_contexts() {
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx1 = dev.new_context(None).unwrap();
+    let ctx2 = dev.new_context(None).unwrap();
+
+    ctx1.new_static_source().unwrap();
+    assert!(ctx1.is_current());
+    assert!(!ctx2.is_current());
+
+    ctx2.new_static_source().unwrap();
+    assert!(!ctx1.is_current());
+    assert!(ctx2.is_current());
+}
+
+#[test]
+fn auto_doppler() {
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+    let src = ctx.new_static_source().unwrap();
+
+    let mut dt = ctx.begin_auto_doppler();
+    dt.set_teleport_distance(10.0).unwrap();
+
+    dt.update_listener([0.0, 0.0, 0.0], 0.5).unwrap();
+    dt.update_source(&src, [1.0, 0.0, 0.0], 0.5).unwrap();
+    assert_eq!(src.velocity::<[f32; 3]>(), [0.0, 0.0, 0.0]);
+
+    dt.update_source(&src, [2.0, 0.0, 0.0], 0.5).unwrap();
+    assert_eq!(src.velocity::<[f32; 3]>(), [2.0, 0.0, 0.0]);
+    assert_eq!(src.position::<[f32; 3]>(), [2.0, 0.0, 0.0]);
+
+    dt.update_source(&src, [100.0, 0.0, 0.0], 0.5).unwrap();
+    assert_eq!(src.velocity::<[f32; 3]>(), [0.0, 0.0, 0.0]);
+
+    dt.update_listener([0.0, 1.0, 0.0], 0.25).unwrap();
+    assert_eq!(ctx.velocity::<[f32; 3]>(), [0.0, 4.0, 0.0]);
+
+    match dt.update_listener([0.0, 0.0, 0.0], 0.0) {
+        Err(AltoError::InvalidValue) => (),
+        r => panic!("{:?}", r),
+    }
+}
+
+#[cfg(feature = "bytemuck")]
+#[test]
+fn bytemuck_frames() {
+    use alto::{Mc51Chn, MuLawSample};
+
+    let bytes = [1u8, 0, 2, 0, 3, 0, 4, 0];
+    let frames: &[Stereo<i16>] = bytemuck::cast_slice(&bytes);
+    assert_eq!(frames, &[Stereo{left: 1i16.to_le(), right: 2i16.to_le()}, Stereo{left: 3i16.to_le(), right: 4i16.to_le()}]);
+    assert_eq!(bytemuck::cast_slice::<_, u8>(frames), &bytes);
+
+    let silence: Mc51Chn<f32> = bytemuck::Zeroable::zeroed();
+    assert_eq!(silence.low_freq, 0.0);
+    let law: &[alto::Mono<MuLawSample>] = bytemuck::cast_slice(&bytes[..2]);
+    assert_eq!(law[1].center, MuLawSample(0));
+}
+
+#[cfg(feature = "wav")]
+#[test]
+fn wav_writer_header() {
+    use std::io::Cursor;
+    use alto::wav::WavWriter;
+
+    let mut w = WavWriter::<Stereo<i16>, _>::new(Cursor::new(Vec::new()), 48000).unwrap();
+    w.write_frames(&[Stereo{left: 1, right: -1}; 10]).unwrap();
+    let bytes = w.into_inner().unwrap().into_inner();
+
+    assert_eq!(bytes.len(), 44 + 40);
+    assert_eq!(&bytes[0 .. 4], b"RIFF");
+    assert_eq!(&bytes[4 .. 8], &(36u32 + 40).to_le_bytes());
+    assert_eq!(&bytes[22 .. 24], &2u16.to_le_bytes());
+    assert_eq!(&bytes[24 .. 28], &48000u32.to_le_bytes());
+    assert_eq!(&bytes[36 .. 40], b"data");
+    assert_eq!(&bytes[40 .. 44], &40u32.to_le_bytes());
+    assert_eq!(&bytes[44 .. 48], &[1, 0, 0xFF, 0xFF]);
+}
+
+#[test]
+fn distance_attenuation() {
+    use alto::DistanceModel;
+
+    fn close(a: f32, b: f32) -> bool { (a - b).abs() < 1e-6 }
+
+    assert_eq!(DistanceModel::None.attenuation(100.0, 1.0, 10.0, 1.0), 1.0);
+
+    assert!(close(DistanceModel::Inverse.attenuation(1.0, 1.0, 10.0, 1.0), 1.0));
+    assert!(close(DistanceModel::Inverse.attenuation(4.0, 1.0, 10.0, 1.0), 0.25));
+    assert!(close(DistanceModel::Inverse.attenuation(20.0, 1.0, 10.0, 1.0), 0.05));
+    assert!(close(DistanceModel::InverseClamped.attenuation(20.0, 1.0, 10.0, 1.0), 0.1));
+    assert!(close(DistanceModel::InverseClamped.attenuation(0.5, 1.0, 10.0, 1.0), 1.0));
+
+    assert!(close(DistanceModel::Linear.attenuation(5.5, 1.0, 10.0, 1.0), 0.5));
+    assert!(close(DistanceModel::Linear.attenuation(20.0, 1.0, 10.0, 1.0), 0.0));
+    assert!(close(DistanceModel::LinearClamped.attenuation(0.0, 1.0, 10.0, 1.0), 1.0));
+
+    assert!(close(DistanceModel::Exponent.attenuation(4.0, 1.0, 10.0, 2.0), 1.0 / 16.0));
+    assert!(close(DistanceModel::ExponentClamped.attenuation(100.0, 1.0, 10.0, 1.0), 0.1));
+}
+
+#[test]
+fn system_events() {
+    use alto::ext::AlcNull;
+
+    let a = load_alto();
+    match a.on_system_event(|e| println!("{:?}", e)) {
+        Ok(()) => assert!(a.is_extension_present(AlcNull::SoftSystemEvents)),
+        Err(AltoError::ExtensionNotPresent) => (),
+        Err(e) => panic!("{:?}", e),
+    }
+}
+
+#[test]
+fn listener_orientation_validation() {
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+
+    for &(at, up) in &[
+        ([0.0, 0.0, -1.0], [0.0, ::std::f32::NAN, 0.0]),
+        ([0.0, 0.0, 0.0], [0.0, 1.0, 0.0]),
+        ([0.0, 0.0, -1.0], [0.0, 0.0, 2.0]),
+    ] {
+        match ctx.set_orientation((at, up)) {
+            Err(AltoError::InvalidOrientation(..)) => (),
+            r => panic!("{:?}", r),
+        }
+    }
+
+    ctx.set_orientation_normalized(([0.0, 0.0, -2.0], [0.0, 1.0, 1.0])).unwrap();
+    let (at, up): ([f32; 3], [f32; 3]) = ctx.orientation();
+    assert_eq!(at, [0.0, 0.0, -1.0]);
+    assert_eq!(up, [0.0, 1.0, 0.0]);
+}
+
+#[test]
+fn units_per_meter() {
+    use alto::UnitsPerMeter;
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+
+    assert_eq!(ctx.units_per_meter(), UnitsPerMeter::Coherent(1.0));
+    ctx.set_units_per_meter(100.0).unwrap();
+    match ctx.units_per_meter() {
+        UnitsPerMeter::Coherent(u) => assert!((u - 100.0).abs() < 0.01),
+        r => panic!("{:?}", r),
+    }
+    assert!((ctx.speed_of_sound() - 34330.0).abs() < 0.01);
+
+    for &bad in &[0.0, -1.0, ::std::f32::NAN] {
+        match ctx.set_units_per_meter(bad) {
+            Err(AltoError::InvalidValue) => (),
+            r => panic!("{:?}", r),
+        }
+    }
+}
+
+#[test]
+fn soft_reset_default() {
+    use alto::ContextAttrs;
+    use alto::ext::Alc;
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    if !dev.is_extension_present(Alc::SoftHrtf) {
+        return;
+    }
+    let _ctx = dev.new_context(None).unwrap();
+
+    dev.soft_reset(Some(ContextAttrs{soft_hrtf: Some(false), .. ContextAttrs::default()})).unwrap();
+    dev.soft_reset_default().unwrap();
+}
+
+#[test]
+fn soft_reopen() {
+    use alto::ext::Alc;
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+    let src = ctx.new_static_source().unwrap();
+    if !dev.is_extension_present(Alc::SoftReopenDevice) {
+        match dev.soft_reopen(None, None) {
+            Err(AltoError::ExtensionNotPresent) => (),
+            r => panic!("{:?}", r),
+        }
+        return;
+    }
+
+    dev.soft_reopen(None, None).unwrap();
+    assert!(dev.extensions().contains(Alc::SoftReopenDevice));
+    assert_eq!(src.gain(), 1.0);
+}
+
+#[test]
+fn set_soft_hrtf() {
+    use alto::ext::Alc;
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    if !dev.is_extension_present(Alc::SoftHrtf) {
+        return;
+    }
+    let _ctx = dev.new_context(None).unwrap();
+
+    dev.set_soft_hrtf(false).unwrap();
+    assert!(dev.soft_hrtf_status() != alto::SoftHrtfStatus::Enabled);
+}
+
+#[test]
+fn source_group() {
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+    let mut src = ctx.new_static_source().unwrap();
+    src.set_gain(0.5).unwrap();
+
+    {
+        let mut sfx = ctx.new_source_group();
+        sfx.add(&src).unwrap();
+        sfx.set_gain(0.5).unwrap();
+        assert_eq!(src.gain(), 0.5);
+
+        src.set_gain(0.8).unwrap();
+        assert_eq!(src.gain(), 0.8);
+
+        let mut ui = ctx.new_source_group();
+        match ui.add(&src) {
+            Err(AltoError::InvalidOperation) => (),
+            r => panic!("{:?}", r),
+        }
+        assert_eq!(sfx.len(), 1);
+    }
+
+    let mut raw = 0.0;
+    unsafe { a.raw_api().alGetSourcef(src.as_raw(), alto::sys::AL_GAIN, &mut raw); }
+    assert_eq!(raw, 0.8);
+}
+
+#[test]
+fn soft_hrtfs() {
+    use alto::ext::Alc;
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    if !dev.is_extension_present(Alc::SoftHrtf) {
+        assert!(dev.soft_hrtfs().is_err());
+        return;
+    }
+
+    let hrtfs = dev.soft_hrtfs().unwrap();
+    let specs = dev.enumerate_soft_hrtfs();
+    assert_eq!(hrtfs.len(), specs.len());
+    for (i, &(id, ref spec)) in hrtfs.iter().enumerate() {
+        assert_eq!(id, i as i32);
+        assert_eq!(spec, &specs[i]);
+    }
+}
+
+#[test]
+fn voice_manager_priorities() {
+    use std::sync::Arc;
+    use alto::Mono;
+    use alto::ext::AlcNull;
+
+    let a = load_alto();
+    if !a.is_extension_present(AlcNull::SoftLoopback) {
+        return;
+    }
+    let mut dev = a.open_loopback::<Stereo<i16>>(None).unwrap();
+    let ctx = dev.new_context(44100, None).unwrap();
+    let buf = Arc::new(ctx.new_buffer(vec![Mono{center: 0i16}; 44100], 44100).unwrap());
+
+    let mut vm = ctx.new_voice_manager(2);
+    let low = vm.play(buf.clone(), 0, true).unwrap();
+    let high = vm.play(buf.clone(), 3, true).unwrap();
+    let mid = vm.play(buf.clone(), 1, true).unwrap();
+    let top = vm.play(buf.clone(), 5, true).unwrap();
+    vm.update(0.0).unwrap();
+    assert_eq!(vm.real_voices(), vec![high, top]);
+
+    let mut out = vec![Stereo{left: 0i16, right: 0}; 1024];
+    dev.soft_render_samples(&mut out[..]);
+
+    vm.set_priority(mid, 4).unwrap();
+    vm.update(0.1).unwrap();
+    assert_eq!(vm.real_voices(), vec![mid, top]);
+    assert!(!vm.is_real(low));
+    assert!(!vm.is_real(high));
+
+    vm.stop(top);
+    vm.update(0.1).unwrap();
+    assert_eq!(vm.real_voices(), vec![high, mid]);
+}
+
+#[test]
+fn source_radius_widens_loopback_output() {
+    use std::sync::Arc;
+    use alto::Mono;
+    use alto::ext::{Al, AlcNull};
+
+    let a = load_alto();
+    if !a.is_extension_present(AlcNull::SoftLoopback) {
+        return;
+    }
+    let mut dev = a.open_loopback::<Stereo<f32>>(None).unwrap();
+    let ctx = dev.new_context(44100, None).unwrap();
+    let mut src = ctx.new_static_source().unwrap();
+
+    if !ctx.is_extension_present(Al::SourceRadius) {
+        match src.set_radius(1.0) {
+            Err(AltoError::ExtensionNotPresent) => return,
+            r => panic!("{:?}", r),
+        }
+    }
+
+    for &bad in &[-1.0, f32::NAN, f32::INFINITY] {
+        match src.set_radius(bad) {
+            Err(AltoError::InvalidValue) => (),
+            r => panic!("{:?}", r),
+        }
+    }
+
+    let wave: Vec<_> = (0..44100).map(|i| Mono{center: if (i / 50) % 2 == 0 { 0.5f32 } else { -0.5 }}).collect();
+    src.set_buffer(Arc::new(ctx.new_buffer(wave, 44100).unwrap())).unwrap();
+    src.set_looping(true);
+    src.set_position([0.5, 0.0, 0.0]).unwrap();
+    src.play();
+
+    let balance = |dev: &mut alto::LoopbackDevice<Stereo<f32>>| {
+        let mut out = vec![Stereo{left: 0.0f32, right: 0.0}; 4096];
+        dev.soft_render_samples(&mut out[..]);
+        dev.soft_render_samples(&mut out[..]);
+        let left: f32 = out.iter().map(|f| f.left * f.left).sum();
+        let right: f32 = out.iter().map(|f| f.right * f.right).sum();
+        left / right
+    };
+
+    let point = balance(&mut dev);
+    src.set_radius(10.0).unwrap();
+    assert_eq!(src.radius(), 10.0);
+    let wide = balance(&mut dev);
+    assert!(wide > point, "{} <= {}", wide, point);
+}
+
+#[test]
+fn batch_source_playback() {
+    use std::sync::Arc;
+    use alto::{Mono, SourceObject, SourceState};
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+    let buf = Arc::new(ctx.new_buffer(vec![Mono{center: 0i16}; 44100], 44100).unwrap());
+
+    let mut first = ctx.new_static_source().unwrap();
+    let mut second = ctx.new_static_source().unwrap();
+    first.set_buffer(buf.clone()).unwrap();
+    second.set_buffer(buf).unwrap();
+
+    ctx.play_all(&mut [&mut first, &mut second]).unwrap();
+    assert_eq!(first.state(), SourceState::Playing);
+    assert_eq!(second.state(), SourceState::Playing);
+    ctx.pause_all(&mut [&mut first, &mut second]).unwrap();
+    assert_eq!(first.state(), SourceState::Paused);
+    ctx.stop_all(&mut [&mut first, &mut second]).unwrap();
+    assert_eq!(second.state(), SourceState::Stopped);
+    ctx.rewind_all(&mut [&mut first, &mut second]).unwrap();
+    assert_eq!(first.state(), SourceState::Initial);
+
+    let mut stream = ctx.new_streaming_source().unwrap();
+    stream.queue_buffer(ctx.new_buffer(vec![Mono{center: 0i16}; 4410], 44100).unwrap()).unwrap();
+    ctx.play_all(&mut [&mut first as &mut dyn SourceObject, &mut stream]).unwrap();
+    assert_eq!(stream.state(), SourceState::Playing);
+    ctx.stop_all(&mut [&mut first as &mut dyn SourceObject, &mut stream]).unwrap();
+    assert_eq!(first.state(), SourceState::Stopped);
+    assert_eq!(stream.state(), SourceState::Stopped);
+    ctx.rewind_all(&mut [&mut first]).unwrap();
+
+    let other = dev.new_context(None).unwrap();
+    let mut foreign = other.new_static_source().unwrap();
+    match ctx.play_all(&mut [&mut first, &mut foreign]) {
+        Err(AltoError::WrongContext) => (),
+        r => panic!("{:?}", r),
+    }
+    assert_eq!(first.state(), SourceState::Initial);
+}
+
+#[test]
+fn wait_until_stopped() {
+    use std::sync::Arc;
+    use std::time::Duration;
+    use alto::{Mono, SourceState};
+    use alto::ext::AlcNull;
+
+    let a = load_alto();
+    if !a.is_extension_present(AlcNull::SoftLoopback) {
+        return;
+    }
+    let mut dev = a.open_loopback::<Stereo<i16>>(None).unwrap();
+    let ctx = dev.new_context(44100, None).unwrap();
+    let buf = Arc::new(ctx.new_buffer(vec![Mono{center: 0i16}; 441], 44100).unwrap());
+    let mut src = ctx.new_static_source().unwrap();
+    src.set_buffer(buf).unwrap();
+
+    match src.wait_until(SourceState::Stopped, Duration::from_millis(10), Duration::from_secs(0)) {
+        Err(AltoError::InvalidValue) => (),
+        r => panic!("{:?}", r),
+    }
+
+    src.set_looping(true);
+    src.play();
+    let mut out = vec![Stereo{left: 0i16, right: 0}; 4410];
+    dev.soft_render_samples(&mut out[..]);
+    assert!(!src.wait_until(SourceState::Stopped, Duration::from_millis(20), Duration::from_millis(5)).unwrap());
+
+    src.set_looping(false);
+    dev.soft_render_samples(&mut out[..]);
+    assert!(src.wait_until_stopped(Duration::from_secs(1)).unwrap());
+}
+
+#[test]
+fn soft_play_at_time() {
+    use std::sync::Arc;
+    use alto::{Mono, SourceState};
+    use alto::ext::{Al, Alc};
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+    let mut src = ctx.new_static_source().unwrap();
+
+    if !ctx.is_extension_present(Al::SoftSourceStartDelay) {
+        match src.soft_play_at_time(0) {
+            Err(AltoError::ExtensionNotPresent) => return,
+            r => panic!("{:?}", r),
+        }
+    }
+    if !dev.is_extension_present(Alc::SoftDeviceClock) {
+        return;
+    }
+
+    let buf = Arc::new(ctx.new_buffer(vec![Mono{center: 0i16}; 44100], 44100).unwrap());
+    src.set_buffer(buf.clone()).unwrap();
+    let now = dev.soft_clock().unwrap();
+    src.soft_play_at_time(now - 1_000_000_000).unwrap();
+    assert_eq!(src.state(), SourceState::Playing);
+
+    let mut first = ctx.new_static_source().unwrap();
+    let mut second = ctx.new_static_source().unwrap();
+    first.set_buffer(buf.clone()).unwrap();
+    second.set_buffer(buf).unwrap();
+    ctx.soft_play_all_at_time(&mut [&mut first, &mut second], dev.soft_clock().unwrap() + 50_000_000).unwrap();
+    assert_eq!(first.state(), SourceState::Playing);
+    assert_eq!(second.state(), SourceState::Playing);
+}
+
+#[test]
+fn gain_step() {
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+    let mut src = ctx.new_static_source().unwrap();
+
+    assert!(!src.gain_step(0.0, 0.25, 2.0).unwrap());
+    assert!((src.gain() - 0.5).abs() < 1e-6);
+    assert!(src.gain_step(0.0, 0.5, 2.0).unwrap());
+    assert_eq!(src.gain(), 0.0);
+    assert!(src.gain_step(0.0, 0.1, 2.0).unwrap());
+
+    match src.gain_step(1.0, 0.1, 0.0) {
+        Err(AltoError::InvalidValue) => (),
+        r => panic!("{:?}", r),
+    }
+}
+
+#[test]
+fn standard_effects() {
+    use alto::ext::Alc;
+    use alto::efx::{ChorusEffect, DistortionEffect, EchoEffect, EqualizerEffect, FlangerEffect};
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+
+    if !dev.is_extension_present(Alc::Efx) {
+        match ctx.new_effect::<ChorusEffect>() {
+            Err(AltoError::ExtensionNotPresent) => return,
+            r => panic!("{:?}", r.map(|_| ())),
+        }
+    }
+
+    let mut chorus = ctx.new_effect::<ChorusEffect>().unwrap();
+    chorus.set_depth(0.5).unwrap();
+    assert_eq!(chorus.depth(), 0.5);
+    let mut distortion = ctx.new_effect::<DistortionEffect>().unwrap();
+    distortion.set_gain(0.5).unwrap();
+    assert_eq!(distortion.gain(), 0.5);
+    let mut echo = ctx.new_effect::<EchoEffect>().unwrap();
+    echo.set_damping(0.25).unwrap();
+    assert_eq!(echo.damping(), 0.25);
+    let mut flanger = ctx.new_effect::<FlangerEffect>().unwrap();
+    flanger.set_feedback(-0.5).unwrap();
+    assert_eq!(flanger.feedback(), -0.5);
+    let mut eq = ctx.new_effect::<EqualizerEffect>().unwrap();
+    eq.set_low_gain(2.0).unwrap();
+    assert_eq!(eq.low_gain(), 2.0);
+
+    match distortion.set_gain(2.0) {
+        Err(AltoError::InvalidValue) => (),
+        r => panic!("{:?}", r),
+    }
+    match echo.set_spread(-2.0) {
+        Err(AltoError::InvalidValue) => (),
+        r => panic!("{:?}", r),
+    }
+}
+
+#[test]
+fn aux_slot_deferred_effect_swap() {
+    use alto::ext::Alc;
+    use alto::efx::{EchoEffect, ReverbEffect};
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+    if !dev.is_extension_present(Alc::Efx) {
+        return;
+    }
+
+    let mut slot = ctx.new_aux_effect_slot().unwrap();
+    let reverb = ctx.new_effect::<ReverbEffect>().unwrap();
+    let echo = ctx.new_effect::<EchoEffect>().unwrap();
+    slot.set_effect(&reverb).unwrap();
+    {
+        let _defer = ctx.defer_updates();
+        slot.set_effect_deferred(&echo).unwrap();
+        slot.set_gain(0.5).unwrap();
+    }
+    slot.set_effect_deferred(&reverb).unwrap();
+    assert!(!ctx.is_suspended());
+}
+
+#[test]
+fn dedicated_effects() {
+    use alto::ext::Alc;
+    use alto::efx::{DedicatedDialogueEffect, DedicatedLowFrequencyEffect};
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+
+    if !dev.is_extension_present(Alc::Efx) || !dev.is_extension_present(Alc::Dedicated) {
+        match ctx.new_effect::<DedicatedDialogueEffect>() {
+            Err(AltoError::ExtensionNotPresent) => return,
+            r => panic!("{:?}", r.map(|_| ())),
+        }
+    }
+
+    let mut dialogue = ctx.new_effect::<DedicatedDialogueEffect>().unwrap();
+    dialogue.set_gain(0.5).unwrap();
+    assert_eq!(dialogue.gain(), 0.5);
+    let mut lfe = ctx.new_effect::<DedicatedLowFrequencyEffect>().unwrap();
+    lfe.set_gain(2.0).unwrap();
+    assert_eq!(lfe.gain(), 2.0);
+
+    let mut slot = ctx.new_aux_effect_slot().unwrap();
+    slot.set_effect(&dialogue).unwrap();
+    let mut src = ctx.new_static_source().unwrap();
+    src.set_aux_send(0, &mut slot).unwrap();
+    slot.set_effect(&lfe).unwrap();
+}
+
+#[test]
+fn soft_offset_latency() {
+    use alto::ext::Al;
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+    let src = ctx.new_static_source().unwrap();
+
+    match src.soft_sample_frac_offset_latency() {
+        Ok((offset, _)) => assert_eq!(offset, 0),
+        Err(AltoError::ExtensionNotPresent) => assert!(!ctx.is_extension_present(Al::SoftSourceLatency)),
+        Err(e) => panic!("{:?}", e),
+    }
+    match src.soft_sec_offset_latency() {
+        Ok((offset, latency)) => assert!(offset == 0.0 && latency >= 0.0),
+        Err(AltoError::ExtensionNotPresent) => assert!(!ctx.is_extension_present(Al::SoftSourceLatency)),
+        Err(e) => panic!("{:?}", e),
+    }
+
+    // The fixed point cursor keeps the fraction that the frame offset drops.
+    let mut dev = match a.open_loopback::<Stereo<f32>>(None) {
+        Ok(dev) => dev,
+        Err(AltoError::ExtensionNotPresent) => return,
+        Err(e) => panic!("{:?}", e),
+    };
+    let ctx = dev.new_context(44100, None).unwrap();
+    if !ctx.is_extension_present(Al::SoftSourceLatency) {
+        return;
+    }
+    let mut src = ctx.new_static_source().unwrap();
+    src.set_buffer(std::sync::Arc::new(ctx.new_buffer(vec![alto::Mono{center: 0i16}; 44100], 44100).unwrap())).unwrap();
+    src.set_pitch(1.25).unwrap();
+    src.play();
+    let mut out = vec![Stereo{left: 0.0f32, right: 0.0}; 101];
+    dev.soft_render_samples(&mut out[..]);
+    let (frames, _) = src.soft_sample_frac_offset_latency().unwrap();
+    let (fixed, _) = src.soft_sample_fixed_offset_latency().unwrap();
+    assert_eq!(fixed >> 32, frames);
+    assert!(fixed & 0xffff_ffff != 0 && (frames - 126).abs() <= 1, "{}", fixed);
+}
+
+#[test]
+fn source_velocity_from_position() {
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+    let mut src = ctx.new_streaming_source().unwrap();
+
+    src.update_position_with_velocity([1.0, 0.0, 0.0], 0.5).unwrap();
+    assert_eq!(src.velocity::<[f32; 3]>(), [0.0, 0.0, 0.0]);
+    src.update_position_with_velocity([1.0, 0.0, 2.0], 0.5).unwrap();
+    assert_eq!(src.velocity::<[f32; 3]>(), [0.0, 0.0, 4.0]);
+    assert_eq!(src.position::<[f32; 3]>(), [1.0, 0.0, 2.0]);
+}
+
+#[test]
+fn soft_direct_channels_streaming() {
+    use alto::ext::Al;
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+    let mut src = ctx.new_streaming_source().unwrap();
+
+    if !ctx.is_extension_present(Al::SoftDirectChannels) {
+        match src.set_soft_direct_channels(true) {
+            Err(AltoError::ExtensionNotPresent) => return,
+            r => panic!("{:?}", r),
+        }
+    }
+
+    src.set_soft_direct_channels(true).unwrap();
+    assert!(src.soft_direct_channels());
+
+    let buf = ctx.new_buffer(vec![Stereo{left: 0i16, right: 0}; 4410], 44100).unwrap();
+    src.queue_buffer(buf).unwrap();
+    src.play();
+    src.set_soft_direct_channels(false).unwrap();
+    assert!(!src.soft_direct_channels());
+}
+
+#[test]
+fn soft_spatialization_with_radius() {
+    use std::sync::Arc;
+    use alto::ext::Al;
+    use alto::SoftSourceSpatialization;
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+    let mut src = ctx.new_static_source().unwrap();
+
+    if !ctx.is_extension_present(Al::SoftSourceSpatialize) {
+        match src.set_soft_spatialization(SoftSourceSpatialization::Enabled) {
+            Err(AltoError::ExtensionNotPresent) => return,
+            r => panic!("{:?}", r),
+        }
+    }
+
+    assert_eq!(src.soft_spatialization(), SoftSourceSpatialization::Auto);
+
+    let buf = ctx.new_buffer(vec![Stereo{left: 0i16, right: 0}; 4410], 44100).unwrap();
+    src.set_buffer(Arc::new(buf)).unwrap();
+    src.set_soft_spatialization(SoftSourceSpatialization::Enabled).unwrap();
+    assert_eq!(src.soft_spatialization(), SoftSourceSpatialization::Enabled);
+
+    if ctx.is_extension_present(Al::SourceRadius) {
+        src.set_radius(2.0).unwrap();
+        assert_eq!(src.radius(), 2.0);
+        assert_eq!(src.soft_spatialization(), SoftSourceSpatialization::Enabled);
+    }
+
+    src.set_soft_spatialization(SoftSourceSpatialization::Disabled).unwrap();
+    assert_eq!(src.soft_spatialization(), SoftSourceSpatialization::Disabled);
+}
+
+#[test]
+fn stereo_angles() {
+    use std::f32::consts::PI;
+    use std::sync::Arc;
+    use alto::ext::Al;
+    use alto::Mono;
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+    let mut src = ctx.new_static_source().unwrap();
+
+    if !ctx.is_extension_present(Al::StereoAngles) {
+        match src.set_stereo_angles([PI / 2.0, -PI / 2.0]) {
+            Err(AltoError::ExtensionNotPresent) => return,
+            r => panic!("{:?}", r),
+        }
+    }
+
+    src.set_stereo_angles([PI / 2.0, -PI / 2.0]).unwrap();
+    let angles: [f32; 2] = src.stereo_angles().unwrap();
+    assert!((angles[0] - PI / 2.0).abs() < 1e-6 && (angles[1] + PI / 2.0).abs() < 1e-6);
+
+    match src.set_stereo_angles([f32::NAN, 0.0]) {
+        Err(AltoError::InvalidValue) => (),
+        r => panic!("{:?}", r),
+    }
+
+    let buf = ctx.new_buffer(vec![Mono{center: 0i16}; 4410], 44100).unwrap();
+    src.set_buffer(Arc::new(buf)).unwrap();
+    src.play();
+    src.set_stereo_angles([PI / 3.0, -PI / 3.0]).unwrap();
+}
+
+#[test]
+fn soft_direct_channels_mode() {
+    use alto::ext::Al;
+    use alto::SoftDirectChannelsMode;
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+    let mut src = ctx.new_static_source().unwrap();
+
+    if !ctx.is_extension_present(Al::SoftDirectChannels) {
+        match src.set_soft_direct_channels_mode(SoftDirectChannelsMode::DropUnmatched) {
+            Err(AltoError::ExtensionNotPresent) => return,
+            r => panic!("{:?}", r),
+        }
+    }
+
+    assert_eq!(src.soft_direct_channels_mode().unwrap(), SoftDirectChannelsMode::Off);
+    src.set_soft_direct_channels_mode(SoftDirectChannelsMode::DropUnmatched).unwrap();
+    assert_eq!(src.soft_direct_channels_mode().unwrap(), SoftDirectChannelsMode::DropUnmatched);
+    assert!(src.soft_direct_channels());
+
+    if ctx.is_extension_present(Al::SoftDirectChannelsRemix) {
+        src.set_soft_direct_channels_mode(SoftDirectChannelsMode::RemixUnmatched).unwrap();
+        assert_eq!(src.soft_direct_channels_mode().unwrap(), SoftDirectChannelsMode::RemixUnmatched);
+    } else {
+        match src.set_soft_direct_channels_mode(SoftDirectChannelsMode::RemixUnmatched) {
+            Err(AltoError::ExtensionNotPresent) => (),
+            r => panic!("{:?}", r),
+        }
+    }
+
+    src.set_soft_direct_channels_mode(SoftDirectChannelsMode::Off).unwrap();
+    assert!(!src.soft_direct_channels());
+}
+
+#[test]
+fn listener_velocity_from_position() {
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+
+    ctx.update_listener_position_with_velocity([0.0, 0.0, 0.0], 0.1).unwrap();
+    ctx.update_listener_position_with_velocity([1.0, 0.0, 0.0], 0.5).unwrap();
+    assert_eq!(ctx.velocity::<[f32; 3]>(), [2.0, 0.0, 0.0]);
+
+    ctx.update_listener_position_with_velocity([5.0, 0.0, 0.0], 0.0).unwrap();
+    assert_eq!(ctx.velocity::<[f32; 3]>(), [2.0, 0.0, 0.0]);
+    assert_eq!(ctx.position::<[f32; 3]>(), [5.0, 0.0, 0.0]);
+}
+
+
+#[test]
+fn source_snapshot() {
+    use std::sync::Arc;
+    use alto::ext::Al;
+    use alto::Mono;
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+    let buf = Arc::new(ctx.new_buffer(vec![Mono{center: 0i16}; 4410], 44100).unwrap());
+
+    let mut src = ctx.new_static_source().unwrap();
+    src.set_buffer(buf.clone()).unwrap();
+    src.set_looping(true);
+    src.set_relative(true);
+    src.set_gain(0.5).unwrap();
+    src.set_pitch(1.5).unwrap();
+    src.set_position([1.0, 2.0, 3.0]).unwrap();
+    src.set_cone_outer_gain(0.25).unwrap();
+    src.set_sample_offset(100).unwrap();
+    if ctx.is_extension_present(Al::SourceDistanceModel) {
+        src.set_distance_model(alto::DistanceModel::Linear).unwrap();
+    }
+
+    let snapshot = src.snapshot().unwrap();
+    assert!(snapshot.looping && snapshot.relative);
+    assert_eq!(snapshot.position, [1.0, 2.0, 3.0]);
+    assert_eq!(snapshot.sample_offset, 100);
+    assert_eq!(snapshot.distance_model.is_some(), ctx.is_extension_present(Al::SourceDistanceModel));
+    assert_eq!(snapshot.stereo_angles.is_some(), ctx.is_extension_present(Al::StereoAngles));
+
+    let mut other = ctx.new_static_source().unwrap();
+    other.set_buffer(buf).unwrap();
+    other.apply_snapshot(&snapshot).unwrap();
+    assert_eq!(other.snapshot().unwrap(), snapshot);
+
+    let mut stream = ctx.new_streaming_source().unwrap();
+    let mut partial = snapshot.clone();
+    partial.looping = false;
+    partial.sample_offset = 0;
+    partial.distance_model = None;
+    stream.apply_snapshot(&partial).unwrap();
+    assert_eq!(stream.gain(), 0.5);
+    assert_eq!(stream.pitch(), 1.5);
+}
+
+
+#[test]
+fn source_buffer_raw() {
+    use std::sync::Arc;
+    use alto::Mono;
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+    let buf = Arc::new(ctx.new_buffer(vec![Mono{center: 0i16}; 4410], 44100).unwrap());
+
+    let mut src = ctx.new_static_source().unwrap();
+    assert_eq!(src.buffer_raw().unwrap(), None);
+    src.set_buffer(buf.clone()).unwrap();
+    assert_eq!(src.buffer_raw().unwrap(), Some(buf.as_raw()));
+    assert_eq!(src.buffer().map(|b| b.as_raw()), Some(buf.as_raw()));
+    src.clear_buffer();
+    assert_eq!(src.buffer_raw().unwrap(), None);
+
+    let mut stream = ctx.new_streaming_source().unwrap();
+    let queued = ctx.new_buffer(vec![Mono{center: 0i16}; 4410], 44100).unwrap();
+    let name = queued.as_raw();
+    stream.queue_buffer(queued).unwrap();
+    assert_eq!(stream.buffer_raw().unwrap(), Some(name));
+}
+
+
+#[test]
+fn source_pan() {
+    use std::sync::Arc;
+    use alto::ext::Al;
+    use alto::Mono;
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+
+    let mut src = ctx.new_static_source().unwrap();
+    src.set_buffer(Arc::new(ctx.new_buffer(vec![Mono{center: 0i16}; 4410], 44100).unwrap())).unwrap();
+    for &pan in &[-1.0, -0.5, 0.0, 0.25, 1.0] {
+        src.set_pan(pan).unwrap();
+        assert!((src.pan().unwrap() - pan).abs() < 1e-5);
+    }
+    assert!(src.relative());
+    src.set_pan(3.0).unwrap();
+    assert!((src.pan().unwrap() - 1.0).abs() < 1e-5);
+    match src.set_pan(f32::NAN) {
+        Err(AltoError::InvalidValue) => (),
+        r => panic!("{:?}", r),
+    }
+
+    let mut stereo = ctx.new_static_source().unwrap();
+    stereo.set_buffer(Arc::new(ctx.new_buffer(vec![Stereo{left: 0i16, right: 0}; 4410], 44100).unwrap())).unwrap();
+    stereo.set_pan(-0.5).unwrap();
+    assert!((stereo.pan().unwrap() + 0.5).abs() < 1e-5);
+    assert_eq!(stereo.relative(), !ctx.is_extension_present(Al::StereoAngles));
+}
+
+
+#[test]
+fn try_new_source_exhaustion() {
+    use alto::ContextAttrs;
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(Some(ContextAttrs{mono_sources: Some(4), stereo_sources: Some(0), .. ContextAttrs::default()})).unwrap();
+
+    let mut srcs = Vec::new();
+    while let Some(src) = ctx.try_new_static_source().unwrap() {
+        srcs.push(src);
+        assert!(srcs.len() < 4096);
+    }
+    assert!(!srcs.is_empty());
+    assert!(ctx.try_new_streaming_source().unwrap().is_none());
+
+    srcs.pop();
+    assert!(ctx.try_new_streaming_source().unwrap().is_some());
+}
+
+
+#[test]
+fn fade_gain_to() {
+    use std::thread;
+    use std::time::Duration;
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+    let mut src = ctx.new_static_source().unwrap();
+
+    let fade = src.fade_gain_to(0.0, Duration::from_millis(100)).unwrap();
+    ctx.tick(Duration::from_millis(50)).unwrap();
+    assert!((src.gain() - 0.5).abs() < 1e-5);
+    assert!(!fade.is_finished());
+    ctx.tick(Duration::from_millis(60)).unwrap();
+    assert_eq!(src.gain(), 0.0);
+    assert!(fade.is_finished());
+
+    let first = src.fade_gain_to(1.0, Duration::from_millis(100)).unwrap();
+    let second = src.fade_gain_to(0.5, Duration::from_millis(100)).unwrap();
+    assert!(first.is_finished());
+    ctx.tick(Duration::from_millis(100)).unwrap();
+    assert_eq!(src.gain(), 0.5);
+    assert!(second.is_finished());
+
+    let cancelled = src.fade_gain_to(1.0, Duration::from_millis(100)).unwrap();
+    ctx.tick(Duration::from_millis(50)).unwrap();
+    drop(cancelled);
+    ctx.tick(Duration::from_millis(50)).unwrap();
+    assert!((src.gain() - 0.75).abs() < 1e-5);
+
+    match src.fade_gain_to(f32::NAN, Duration::from_millis(100)) {
+        Err(AltoError::InvalidValue) => (),
+        r => panic!("{:?}", r.map(|_| ())),
+    }
+
+    ctx.set_fade_timer(Some(Duration::from_millis(5))).unwrap();
+    src.fade_gain_to(0.0, Duration::from_millis(20)).unwrap().detach();
+    thread::sleep(Duration::from_millis(200));
+    assert_eq!(src.gain(), 0.0);
+    ctx.set_fade_timer(None).unwrap();
+}
+
+
+#[test]
+fn soft_hrtf_status_display() {
+    use alto::SoftHrtfStatus;
+
+    assert_eq!(SoftHrtfStatus::Enabled.to_string(), "Enabled");
+    assert_eq!(SoftHrtfStatus::HeadphonesDetected.to_string(), "Headphones detected");
+    assert_eq!(SoftHrtfStatus::UnsupportedFormat.to_string(), "Unsupported format");
+    assert_eq!(SoftHrtfStatus::Unknown(42).to_string(), "Unknown (42)");
+    assert_eq!(format!("{:?}", SoftHrtfStatus::Unknown(42)), "Unknown(42)");
+}
+
+
+#[test]
+fn stop_with_fade() {
+    use std::sync::Arc;
+    use std::time::Duration;
+    use alto::{Mono, SourceState};
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+    let mut src = ctx.new_static_source().unwrap();
+    src.set_buffer(Arc::new(ctx.new_buffer(vec![Mono{center: 0i16}; 4410], 44100).unwrap())).unwrap();
+    src.set_looping(true);
+
+    src.play();
+    src.stop_with_fade(Duration::from_millis(100)).unwrap();
+    ctx.tick(Duration::from_millis(50)).unwrap();
+    assert!((src.gain() - 0.5).abs() < 1e-5);
+    assert_eq!(src.state(), SourceState::Playing);
+    ctx.tick(Duration::from_millis(60)).unwrap();
+    assert_eq!(src.state(), SourceState::Stopped);
+    assert_eq!(src.gain(), 1.0);
+
+    src.play();
+    src.stop_with_fade(Duration::from_millis(100)).unwrap();
+    ctx.tick(Duration::from_millis(50)).unwrap();
+    src.play();
+    assert_eq!(src.gain(), 1.0);
+    ctx.tick(Duration::from_millis(100)).unwrap();
+    assert_eq!(src.state(), SourceState::Playing);
+
+    src.stop_with_fade(Duration::from_millis(100)).unwrap();
+    ctx.tick(Duration::from_millis(50)).unwrap();
+    src.stop();
+    ctx.tick(Duration::from_millis(10)).unwrap();
+    assert_eq!(src.gain(), 1.0);
+}
+
+
+#[test]
+fn new_context_require_hrtf() {
+    use alto::ext::Alc;
+    use alto::SoftHrtfStatus;
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+
+    match dev.new_context_require_hrtf(None) {
+        Ok(_ctx) => match dev.soft_hrtf_status() {
+            SoftHrtfStatus::Enabled | SoftHrtfStatus::Required => (),
+            s => panic!("{:?}", s),
+        },
+        Err(AltoError::HrtfUnavailable(s)) => assert!(s != SoftHrtfStatus::Enabled && s != SoftHrtfStatus::Required),
+        Err(AltoError::ExtensionNotPresent) => assert!(!dev.is_extension_present(Alc::SoftHrtf)),
+        Err(e) => panic!("{:?}", e),
+    }
+}
+
+
+#[test]
+fn angle_conversions() {
+    use std::f32::consts::PI;
+    use alto::Angle;
+
+    assert!((Angle::radians(PI).to_degrees() - 180.0).abs() < 1e-4);
+    assert!((Angle::degrees(90.0).to_radians() - PI / 2.0).abs() < 1e-6);
+    assert!(Angle::degrees(30.0) < Angle::radians(PI));
+}
+
+
+#[test]
+fn source_cone() {
+    use alto::Angle;
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+    let mut src = ctx.new_static_source().unwrap();
+
+    src.set_cone(Angle::degrees(45.0), Angle::degrees(90.0), 0.25).unwrap();
+    let (inner, outer, gain) = src.cone();
+    assert_eq!((inner.to_degrees(), outer.to_degrees(), gain), (45.0, 90.0, 0.25));
+    assert_eq!(src.cone_inner_angle(), 45.0);
+
+    for &(inner, outer, gain) in &[(90.0, 45.0, 0.5), (0.0, 361.0, 0.5), (-1.0, 90.0, 0.5), (0.0, 90.0, 1.5), (f32::NAN, 90.0, 0.5)] {
+        match src.set_cone(Angle::degrees(inner), Angle::degrees(outer), gain) {
+            Err(AltoError::InvalidValue) => (),
+            r => panic!("{:?}", r),
+        }
+    }
+    assert_eq!(src.cone_outer_angle(), 90.0);
+}
+
+
+#[test]
+fn buffer_format_supported() {
+    use alto::ext::Al;
+    use alto::{ExtFloat32Format, Format, StandardFormat};
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+
+    assert!(ctx.is_buffer_format_supported(Format::Standard(StandardFormat::StereoI16).into_raw(Some(&ctx)).unwrap()));
+    assert!(!ctx.is_buffer_format_supported(0));
+    if ctx.is_extension_present(Al::Float32) {
+        assert!(ctx.is_buffer_format_supported(Format::ExtFloat32(ExtFloat32Format::Stereo).into_raw(Some(&ctx)).unwrap()));
+    }
+}
+
+#[test]
+fn detach_to_world_keeps_loopback_output() {
+    use std::sync::Arc;
+    use alto::Mono;
+    use alto::ext::AlcNull;
+
+    let a = load_alto();
+    if !a.is_extension_present(AlcNull::SoftLoopback) {
+        return;
+    }
+    let mut dev = a.open_loopback::<Stereo<f32>>(None).unwrap();
+    let ctx = dev.new_context(44100, None).unwrap();
+    let listener_pos = [5.0, 0.0, 0.0];
+    let listener_ori = ([1.0, 0.0, 0.0], [0.0, 1.0, 0.0]);
+    ctx.set_position(listener_pos).unwrap();
+    ctx.set_orientation(listener_ori).unwrap();
+
+    let mut src = ctx.new_static_source().unwrap();
+    let wave: Vec<_> = (0..44100).map(|i| Mono{center: if (i / 50) % 2 == 0 { 0.5f32 } else { -0.5 }}).collect();
+    src.set_buffer(Arc::new(ctx.new_buffer(wave, 44100).unwrap())).unwrap();
+    src.set_looping(true);
+    src.set_head_locked([1.0, 0.0, 0.5]).unwrap();
+    assert!(src.relative());
+    src.play();
+
+    let energy = |dev: &mut alto::LoopbackDevice<Stereo<f32>>| {
+        let mut out = vec![Stereo{left: 0.0f32, right: 0.0}; 4000];
+        dev.soft_render_samples(&mut out[..]);
+        dev.soft_render_samples(&mut out[..]);
+        (out.iter().map(|f| f.left * f.left).sum::<f32>(), out.iter().map(|f| f.right * f.right).sum::<f32>())
+    };
+
+    let locked = energy(&mut dev);
+    src.detach_to_world(listener_pos, listener_ori).unwrap();
+    assert!(!src.relative());
+    let pos: [f32; 3] = src.position();
+    assert!((pos[0] - 4.5).abs() < 1e-5 && pos[1].abs() < 1e-5 && (pos[2] - 1.0).abs() < 1e-5, "{:?}", pos);
+    let world = energy(&mut dev);
+
+    assert!((locked.0 - world.0).abs() <= locked.0 * 0.01, "{:?} != {:?}", locked, world);
+    assert!((locked.1 - world.1).abs() <= locked.1 * 0.01, "{:?} != {:?}", locked, world);
+    assert!(locked.1 > locked.0);
+}
+
+#[test]
+fn adopt_raw_source() {
+    use std::sync::Arc;
+    use alto::{AnySource, Mono, SourceKind};
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+    let api = a.raw_api();
+    ctx.set_gain(1.0).unwrap();
+
+    let mut id = 0;
+    unsafe { api.alGenSources(1, &mut id); }
+    let mut src = match unsafe { ctx.adopt_raw_source(id, SourceKind::Static, false) }.unwrap() {
+        AnySource::Static(src) => src,
+        AnySource::Streaming(..) => panic!(),
+    };
+    assert_eq!(src.as_raw(), id);
+    let buf = Arc::new(ctx.new_buffer(vec![Mono{center: 0i16}; 441], 44100).unwrap());
+    src.set_buffer(buf.clone()).unwrap();
+    drop(src);
+    assert!(unsafe { api.alIsSource(id) } != alto::sys::AL_FALSE);
+
+    match unsafe { ctx.adopt_raw_source(id, SourceKind::Streaming, true) } {
+        Err(AltoError::SourceTypeMismatch(alto::SourceType::Static)) => (),
+        Err(e) => panic!("{:?}", e),
+        Ok(..) => panic!(),
+    }
+    match unsafe { ctx.adopt_raw_source(id, SourceKind::Static, true) }.unwrap() {
+        AnySource::Static(src) => drop(src),
+        AnySource::Streaming(..) => panic!(),
+    }
+    assert!(unsafe { api.alIsSource(id) } == alto::sys::AL_FALSE);
+
+    match unsafe { ctx.adopt_raw_source(id, SourceKind::Static, true) } {
+        Err(AltoError::InvalidName) => (),
+        Err(e) => panic!("{:?}", e),
+        Ok(..) => panic!(),
+    }
+}
+
+#[test]
+fn context_with_current() {
+    use std::panic::{self, AssertUnwindSafe};
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx1 = dev.new_context(None).unwrap();
+    let ctx2 = dev.new_context(None).unwrap();
+    ctx2.set_gain(0.75).unwrap();
+    ctx1.set_gain(0.25).unwrap();
+    assert!(ctx1.is_current());
+
+    let gain = ctx2.with_current(|| {
+        let mut value = 0.0;
+        unsafe { a.raw_api().alGetListenerf(alto::sys::AL_GAIN, &mut value); }
+        value
+    }).unwrap();
+    assert_eq!(gain, 0.75);
+    assert!(ctx1.is_current());
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| ctx2.with_current(|| panic!("unwind"))));
+    assert!(result.is_err());
+    assert!(ctx1.is_current());
+}
+
+#[test]
+fn connection_watcher() {
+    use alto::{ConnectionWatcher, Mono};
+    use alto::ext::Alc;
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    if !dev.is_extension_present(Alc::Disconnect) {
+        return;
+    }
+
+    let mut watcher = ConnectionWatcher::new();
+    assert_eq!(watcher.poll(&dev).unwrap(), None);
+    assert_eq!(watcher.poll(&dev as &dyn DeviceObject).unwrap(), None);
+    assert!(!watcher.is_disconnected());
+
+    if let Ok(cap) = a.open_capture::<Mono<i16>>(None, 44100, 1024) {
+        let mut watcher = ConnectionWatcher::new();
+        assert_eq!(watcher.poll(&cap).unwrap(), None);
+        assert!(cap.connected().unwrap());
+    }
+}
+
+#[test]
+fn mixed_source_collection() {
+    use std::sync::Arc;
+    use alto::{AnySource, Mono, SourceObject, SourceState, StaticSource, StreamingSource};
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+    let buf = Arc::new(ctx.new_buffer(vec![Mono{center: 0i16}; 4410], 44100).unwrap());
+
+    let mut stat = ctx.new_static_source().unwrap();
+    stat.set_buffer(buf.clone()).unwrap();
+    let mut sources: Vec<AnySource> = vec![stat.into(), ctx.new_streaming_source().unwrap().into()];
+    for src in &mut sources {
+        src.set_gain(0.5).unwrap();
+        src.set_position([1.0, 2.0, 3.0]).unwrap();
+        src.set_pitch(1.5).unwrap();
+    }
+    sources[1].as_streaming_mut().unwrap().queue_buffer(ctx.new_buffer(vec![Mono{center: 0i16}; 4410], 44100).unwrap()).unwrap();
+    for src in &mut sources {
+        src.play();
+        assert_eq!(src.gain(), 0.5);
+        assert_eq!(src.pitch(), 1.5);
+        assert_eq!(src.position::<[f32; 3]>(), [1.0, 2.0, 3.0]);
+        assert_eq!(src.state(), SourceState::Playing);
+    }
+    assert!(sources[0].as_static().unwrap().buffer().is_some());
+    assert!(sources[0].as_streaming().is_none());
+    assert!(sources[1].as_static().is_none());
+    assert_eq!(sources[1].as_streaming().unwrap().buffers_queued(), 1);
+
+    let mut stat = ctx.new_static_source().unwrap();
+    stat.set_buffer(buf).unwrap();
+    let mut objects: Vec<Box<dyn SourceObject>> = vec![Box::new(stat), Box::new(ctx.new_streaming_source().unwrap())];
+    {
+        let mut refs: Vec<&mut dyn SourceObject> = objects.iter_mut().map(|s| &mut **s).collect();
+        ctx.stop_all(&mut refs).unwrap();
+    }
+    assert!(objects[0].as_any().downcast_ref::<StaticSource>().is_some());
+    assert!(objects[0].as_any().downcast_ref::<StreamingSource>().is_none());
+    let stream = objects[1].as_any_mut().downcast_mut::<StreamingSource>().unwrap();
+    stream.queue_buffer(ctx.new_buffer(vec![Mono{center: 0i16}; 4410], 44100).unwrap()).unwrap();
+    assert_eq!(stream.buffers_queued(), 1);
+}
+
+#[test]
+fn source_seek() {
+    use std::sync::Arc;
+    use alto::{Mono, SeekPos, SourceState};
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+
+    let mut src = ctx.new_static_source().unwrap();
+    match src.seek(SeekPos::Samples(0)) {
+        Err(AltoError::SeekOutOfRange(SeekPos::Samples(0))) => (),
+        r => panic!("{:?}", r),
+    }
+    src.set_buffer(Arc::new(ctx.new_buffer(vec![Mono{center: 0i16}; 44100], 44100).unwrap())).unwrap();
+    match src.seek(SeekPos::Samples(44100)) {
+        Err(AltoError::SeekOutOfRange(SeekPos::Samples(44100))) => (),
+        r => panic!("{:?}", r),
+    }
+    match src.seek(SeekPos::Bytes(-2)) {
+        Err(AltoError::SeekOutOfRange(SeekPos::Bytes(88200))) => (),
+        r => panic!("{:?}", r),
+    }
+    match src.seek(SeekPos::Seconds(2.0)) {
+        Err(AltoError::SeekOutOfRange(SeekPos::Seconds(secs))) => assert_eq!(secs, 1.0),
+        r => panic!("{:?}", r),
+    }
+
+    src.play();
+    src.pause();
+    src.seek(SeekPos::Samples(22050)).unwrap();
+    assert_eq!(src.tell(), SeekPos::Samples(22050));
+    src.seek(SeekPos::Seconds(0.25)).unwrap();
+    assert_eq!(src.tell(), SeekPos::Samples(11025));
+
+    src.stop();
+    src.seek(SeekPos::Bytes(2000)).unwrap();
+    assert_eq!(src.state(), SourceState::Initial);
+    src.play();
+    src.pause();
+    match src.tell() {
+        SeekPos::Samples(offset) => assert!(offset >= 1000),
+        pos => panic!("{:?}", pos),
+    }
+
+    let mut stream = ctx.new_streaming_source().unwrap();
+    stream.queue_buffer(ctx.new_buffer(vec![Mono{center: 0i16}; 4410], 44100).unwrap()).unwrap();
+    stream.queue_buffer(ctx.new_buffer(vec![Mono{center: 0i16}; 4410], 44100).unwrap()).unwrap();
+    match stream.seek(SeekPos::Samples(8820)) {
+        Err(AltoError::SeekOutOfRange(SeekPos::Samples(8820))) => (),
+        r => panic!("{:?}", r),
+    }
+    stream.play();
+    stream.pause();
+    stream.seek(SeekPos::Samples(6000)).unwrap();
+    assert_eq!(stream.tell(), SeekPos::Samples(6000));
+}
+
+#[test]
+fn open_matching() {
+    let a = load_alto();
+    let outputs = a.enumerate_outputs();
+    if let Some(spec) = outputs.first() {
+        let name = spec.to_str().unwrap().to_uppercase();
+        let dev = a.open_matching(&name).unwrap();
+        assert!(outputs.iter().any(|s| Some(&**s) == dev.specifier()));
+        assert!(a.open_matching("").is_ok());
+    }
+    match a.open_matching("no such device \u{1F50A}") {
+        Err(AltoError::InvalidDevice) => (),
+        r => panic!("{:?}", r.map(|_| ())),
+    }
+}
+
+#[test]
+fn unqueue_processed_into() {
+    use std::time::Duration;
+    use alto::Mono;
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+    let mut stream = ctx.new_streaming_source().unwrap();
+
+    let mut out = Vec::new();
+    assert_eq!(stream.unqueue_processed_into(&mut out).unwrap(), 0);
+    for _ in 0..3 {
+        stream.queue_buffer(ctx.new_buffer(vec![Mono{center: 0i16}; 64], 44100).unwrap()).unwrap();
+    }
+    assert_eq!(stream.unqueue_processed_into(&mut out).unwrap(), 0);
+    assert!(out.is_empty());
+
+    stream.play();
+    stream.wait_until_stopped(Duration::from_secs(1)).unwrap();
+    assert_eq!(stream.unqueue_processed_into(&mut out).unwrap(), 3);
+    assert_eq!(out.len(), 3);
+    assert_eq!(stream.buffers_queued(), 0);
+
+    stream.queue_buffer(out.pop().unwrap()).unwrap();
+    assert_eq!(stream.buffers_queued(), 1);
+}
+
+#[test]
+fn soft_position_f64() {
+    use alto::ext::Al;
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+    let mut src = ctx.new_static_source().unwrap();
+
+    match src.set_soft_position_f64([1.0e6, 2.5, -3.0]) {
+        Ok(()) => {
+            let pos: [f64; 3] = src.soft_position_f64().unwrap();
+            assert_eq!(pos, [1.0e6, 2.5, -3.0]);
+            assert_eq!(src.position::<[f32; 3]>(), [1.0e6, 2.5, -3.0]);
+        },
+        Err(AltoError::ExtensionNotPresent) => {
+            assert!(!ctx.is_extension_present(Al::SoftSourceLatency));
+            return;
+        },
+        Err(e) => panic!("{:?}", e),
+    }
+
+    src.set_soft_velocity_f64([0.5, 0.0, 0.0]).unwrap();
+    let vel: [f64; 3] = src.soft_velocity_f64().unwrap();
+    assert_eq!(vel, [0.5, 0.0, 0.0]);
+    match src.set_soft_position_f64([f64::NAN, 0.0, 0.0]) {
+        Err(AltoError::InvalidValue) => (),
+        r => panic!("{:?}", r),
+    }
+}
+
+#[test]
+fn streaming_buffer_pool() {
+    use std::time::Duration;
+    use alto::{Mono, PoolStatus};
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+    let mut stream = ctx.new_streaming_source().unwrap();
+
+    assert!(stream.acquire_free().is_none());
+    match stream.reclaim() {
+        Err(AltoError::InvalidOperation) => (),
+        r => panic!("{:?}", r),
+    }
+    stream.with_pool(2, 64).unwrap();
+    assert_eq!(stream.reclaim().unwrap(), PoolStatus::Idle);
+
+    for _ in 0..2 {
+        let mut buf = stream.acquire_free().unwrap();
+        assert_eq!(buf.capacity(), 64);
+        match buf.set_data(vec![Mono{center: 0i16}; 65], 44100) {
+            Err(AltoError::InvalidValue) => (),
+            r => panic!("{:?}", r),
+        }
+        buf.set_data(vec![Mono{center: 0i16}; 64], 44100).unwrap();
+        stream.queue(buf).unwrap();
+    }
+    assert!(stream.acquire_free().is_none());
+    assert_eq!(stream.buffers_queued(), 2);
+
+    stream.play();
+    stream.wait_until_stopped(Duration::from_secs(1)).unwrap();
+    assert_eq!(stream.reclaim().unwrap(), PoolStatus::Underrun);
+    assert_eq!(stream.buffers_queued(), 0);
+    assert!(stream.acquire_free().is_some());
+
+    stream.stop();
+    assert_eq!(stream.reclaim().unwrap(), PoolStatus::Idle);
+}
+
+#[test]
+fn extension_set_display() {
+    use alto::ExtensionSet;
+    use alto::ext::Alc;
+
+    assert_eq!(ExtensionSet::default().to_string(), "");
+    let set = ExtensionSet{efx: true, soft_hrtf: true, ..ExtensionSet::default()};
+    assert_eq!(set.to_string(), "ALC_EXT_EFX ALC_SOFT_HRTF");
+    assert!(set.contains(Alc::Efx));
+    assert!(!set.contains(Alc::Disconnect));
+}
+
+#[test]
+fn device_extensions() {
+    use alto::ext::Alc;
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let set = dev.extensions();
+    for &ext in &[Alc::Dedicated, Alc::Disconnect, Alc::Efx, Alc::SoftHrtf, Alc::SoftPauseDevice, Alc::SoftOutputLimiter, Alc::SoftDeviceClock] {
+        assert_eq!(set.contains(ext), dev.is_extension_present(ext));
+    }
+}
+
+#[test]
+fn buffers_shared_across_contexts() {
+    use std::sync::Arc;
+    use alto::Mono;
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx1 = dev.new_context(None).unwrap();
+    let ctx2 = dev.new_context(None).unwrap();
+    assert!(ctx1.shares_buffers_with(&ctx2));
+
+    let buf = Arc::new(ctx1.new_buffer(vec![Mono{center: 0i16}; 4410], 44100).unwrap());
+    ctx2.check_buffer(&buf).unwrap();
+    let mut src = ctx2.new_static_source().unwrap();
+    src.set_buffer(buf.clone()).unwrap();
+    assert_eq!(src.buffer_raw().unwrap(), Some(buf.as_raw()));
+
+    let mut vm = ctx2.new_voice_manager(1);
+    vm.play(buf.clone(), 0, false).unwrap();
+
+    if let Ok(dev2) = a.open(None) {
+        let ctx3 = dev2.new_context(None).unwrap();
+        assert!(!ctx3.shares_buffers_with(&ctx1));
+        match ctx3.check_buffer(&buf) {
+            Err(AltoError::WrongDevice) => (),
+            r => panic!("{:?}", r),
+        }
+    }
+}
+
+#[test]
+fn streaming_underrun() {
+    use std::time::Duration;
+    use alto::{Mono, SourceState};
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+    let mut stream = ctx.new_streaming_source().unwrap();
+
+    stream.queue_buffer(ctx.new_buffer(vec![Mono{center: 0i16}; 64], 44100).unwrap()).unwrap();
+    assert!(!stream.check_underrun().unwrap());
+    stream.play();
+    stream.wait_until_stopped(Duration::from_secs(1)).unwrap();
+    assert!(stream.check_underrun().unwrap());
+    assert!(stream.check_underrun().unwrap());
+    assert_eq!(stream.underruns(), 1);
+
+    stream.queue_buffer(ctx.new_buffer(vec![Mono{center: 0i16}; 64], 44100).unwrap()).unwrap();
+    assert_eq!(stream.state(), SourceState::Stopped);
+    stream.set_auto_restart(true);
+    stream.queue_buffer(ctx.new_buffer(vec![Mono{center: 0i16}; 44100], 44100).unwrap()).unwrap();
+    assert_ne!(stream.state(), SourceState::Stopped);
+    assert!(!stream.check_underrun().unwrap());
+
+    stream.stop();
+    assert!(!stream.check_underrun().unwrap());
+    assert_eq!(stream.underruns(), 1);
+}
+
+#[test]
+fn clear_error() {
+    use std::f32;
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+
+    ctx.set_error_checking(ErrorChecking::Never);
+    let mut src = ctx.new_static_source().unwrap();
+    src.set_position([f32::NAN, 0.0, 0.0]).unwrap();
+    match ctx.set_doppler_factor(1.0) {
+        Err(AltoError::InvalidValue) => (),
+        r => panic!("{:?}", r),
+    }
+
+    src.set_position([f32::NAN, 0.0, 0.0]).unwrap();
+    ctx.clear_error();
+    ctx.set_doppler_factor(1.0).unwrap();
+
+    dev.clear_error();
+    a.clear_alc_error(Some(&dev));
+    a.clear_alc_error(None);
+}
+
+#[test]
+fn streaming_drain() {
+    use std::time::Duration;
+    use alto::{Mono, SourceState};
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+    let mut stream = ctx.new_streaming_source().unwrap();
+    assert!(stream.drain(None).unwrap());
+
+    stream.queue_buffer(ctx.new_buffer(vec![Mono{center: 0i16}; 44100], 44100).unwrap()).unwrap();
+    assert!(!stream.drain(None).unwrap());
+    stream.play();
+    assert!(!stream.drain(Some(Duration::from_millis(20))).unwrap());
+    stream.stop();
+    assert!(stream.drain(None).unwrap());
+
+    stream.unqueue_buffer().unwrap();
+    for _ in 0..2 {
+        stream.queue_buffer(ctx.new_buffer(vec![Mono{center: 0i16}; 2205], 44100).unwrap()).unwrap();
+    }
+    stream.play();
+    assert!(stream.drain(Some(Duration::from_secs(2))).unwrap());
+    assert_eq!(stream.state(), SourceState::Stopped);
+    assert_eq!(stream.buffers_processed(), 2);
+    assert!(!stream.check_underrun().unwrap());
+}
+
+struct ConstStream {
+    pos: u64,
+    len: u64,
+}
+
+impl alto::SampleStream<alto::Mono<f32>> for ConstStream {
+    fn read(&mut self, out: &mut [alto::Mono<f32>]) -> usize {
+        let n = std::cmp::min(out.len() as u64, self.len - self.pos) as usize;
+        for f in &mut out[..n] {
+            f.center = 0.5;
+        }
+        self.pos += n as u64;
+        n
+    }
+
+    fn seek(&mut self, frame: u64) -> bool {
+        self.pos = std::cmp::min(frame, self.len);
+        true
+    }
+}
+
+#[test]
+fn attach_stream_gapless_loopback() {
+    use std::time::Duration;
+    use alto::StreamOptions;
+    use alto::ext::AlcNull;
+
+    let a = load_alto();
+    if !a.is_extension_present(AlcNull::SoftLoopback) {
+        return;
+    }
+    let mut dev = a.open_loopback::<Stereo<f32>>(None).unwrap();
+    let ctx = dev.new_context(44100, None).unwrap();
+    let stream = ctx.new_streaming_source().unwrap();
+    let opts = StreamOptions{frames_per_buffer: 1024, ..StreamOptions::default()};
+    let handle = stream.attach_stream(ConstStream{pos: 0, len: 20000}, opts).unwrap();
+    thread::sleep(Duration::from_millis(50));
+
+    let mut rendered = Vec::new();
+    let mut chunk = vec![Stereo{left: 0.0f32, right: 0.0}; 256];
+    for _ in 0..120 {
+        dev.soft_render_samples(&mut chunk[..]);
+        rendered.extend(chunk.iter().map(|f| f.left));
+        thread::sleep(Duration::from_millis(5));
+    }
+
+    let first = rendered.iter().position(|s| s.abs() > 0.05).unwrap();
+    let last = rendered.iter().rposition(|s| s.abs() > 0.05).unwrap();
+    assert!(rendered[first..last].iter().all(|s| s.abs() > 0.05), "gap in streamed output");
+    assert!(((last - first) as i64 - 20000).abs() < 512, "{}", last - first);
+    assert!(handle.is_finished());
+
+    let stream = handle.stop().unwrap();
+    assert_eq!(stream.buffers_queued(), 0);
+}
+
+#[test]
+fn attach_stream_commands() {
+    use std::time::Duration;
+    use alto::{SourceState, StreamOptions};
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+    let stream = ctx.new_streaming_source().unwrap();
+    let opts = StreamOptions{looping: true, ..StreamOptions::default()};
+    let handle = stream.attach_stream(ConstStream{pos: 0, len: 1000}, opts).unwrap();
+
+    thread::sleep(Duration::from_millis(50));
+    handle.pause();
+    handle.seek(500);
+    thread::sleep(Duration::from_millis(50));
+    handle.resume();
+    thread::sleep(Duration::from_millis(50));
+    assert!(!handle.is_finished());
+
+    let stream = handle.stop().unwrap();
+    assert_eq!(stream.state(), SourceState::Stopped);
+    assert_eq!(stream.buffers_queued(), 0);
+
+    let handle = ctx.new_streaming_source().unwrap().attach_stream(ConstStream{pos: 0, len: 1000}, StreamOptions::default()).unwrap();
+    drop(handle);
+    match stream.attach_stream(ConstStream{pos: 0, len: 1000}, StreamOptions{frequency: 0, ..StreamOptions::default()}) {
+        Err(AltoError::InvalidValue) => (),
+        r => panic!("{:?}", r.map(|_| ())),
+    }
+}
+
+#[test]
+fn queued_buffers() {
+    use alto::Mono;
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+    let mut stream = ctx.new_streaming_source().unwrap();
+    assert_eq!(stream.queued_buffers().count(), 0);
+
+    let bufs: Vec<_> = (0..3).map(|_| ctx.new_buffer(vec![Mono{center: 0i16}; 64], 44100).unwrap()).collect();
+    let names: Vec<_> = bufs.iter().map(|b| b.as_raw()).collect();
+    for buf in bufs {
+        stream.queue_buffer(buf).unwrap();
+    }
+    assert_eq!(stream.queued_buffers().map(|b| b.as_raw()).collect::<Vec<_>>(), names);
+
+    stream.play();
+    stream.stop();
+    stream.unqueue_buffer().unwrap();
+    assert_eq!(stream.queued_buffers().map(|b| b.as_raw()).collect::<Vec<_>>(), &names[1..]);
+}
+
+struct TrickleReader {
+    data: Vec<u8>,
+    pos: usize,
+}
+
+impl std::io::Read for TrickleReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = std::cmp::min(std::cmp::min(buf.len(), 3), self.data.len() - self.pos);
+        buf[..n].copy_from_slice(&self.data[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+#[test]
+fn feed_reader() {
+    use std::time::{Duration, Instant};
+    use alto::{PcmFormat, SourceState, StandardFormat};
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+
+    let mut data = Vec::new();
+    for i in 0..2000i16 {
+        data.extend_from_slice(&(i * 8).to_le_bytes());
+        data.extend_from_slice(&(-i * 8).to_le_bytes());
+    }
+    data.push(0x7f);
+    let reader = TrickleReader{data, pos: 0};
+    let handle = ctx.new_streaming_source().unwrap().feed_reader(reader, PcmFormat{format: StandardFormat::StereoI16, frequency: 44100}).unwrap();
+
+    let start = Instant::now();
+    while !handle.is_finished() && start.elapsed() < Duration::from_secs(2) {
+        thread::sleep(Duration::from_millis(10));
+    }
+    assert!(handle.is_finished());
+
+    let stream = handle.stop().unwrap();
+    assert_eq!(stream.state(), SourceState::Stopped);
+    assert_eq!(stream.buffers_queued(), 0);
+}
+
+#[test]
+fn chain_source_loopback() {
+    use std::sync::Arc;
+    use alto::{AltoError, Mono};
+    use alto::ext::AlcNull;
+
+    let a = load_alto();
+    if !a.is_extension_present(AlcNull::SoftLoopback) {
+        return;
+    }
+    let mut dev = a.open_loopback::<Stereo<f32>>(None).unwrap();
+    let ctx = dev.new_context(44100, None).unwrap();
+    let intro = Arc::new(ctx.new_buffer(vec![Mono{center: 0.25f32}; 1000], 44100).unwrap());
+    let body = Arc::new(ctx.new_buffer(vec![Mono{center: 0.5f32}; 300], 44100).unwrap());
+    let next = Arc::new(ctx.new_buffer(vec![Mono{center: -0.5f32}; 300], 44100).unwrap());
+
+    match ctx.new_streaming_source().unwrap().into_chain(vec![intro.clone()], Some(1)) {
+        Err(AltoError::InvalidValue) => (),
+        r => panic!("{:?}", r.map(|_| ())),
+    }
+
+    let mut chain = ctx.new_streaming_source().unwrap().into_chain(vec![intro.clone(), body.clone()], Some(1)).unwrap();
+    assert_eq!(chain.loop_point(), Some(1));
+    chain.play();
+
+    let mut rendered = Vec::new();
+    let mut chunk = vec![Stereo{left: 0.0f32, right: 0.0}; 128];
+    for _ in 0..40 {
+        dev.soft_render_samples(&mut chunk[..]);
+        rendered.extend(chunk.iter().map(|f| f.left));
+        chain.update().unwrap();
+    }
+    assert!(rendered.iter().all(|s| s.abs() > 0.05), "gap in chained output");
+    assert_eq!(chain.playing().unwrap().map(|b| b.as_raw()), Some(body.as_raw()));
+
+    chain.set_chain(vec![next.clone()], Some(0)).unwrap();
+    rendered.clear();
+    for _ in 0..40 {
+        dev.soft_render_samples(&mut chunk[..]);
+        rendered.extend(chunk.iter().map(|f| f.left));
+        chain.update().unwrap();
+    }
+    assert!(rendered.iter().all(|s| s.abs() > 0.05), "gap in chained output");
+    assert!(*rendered.last().unwrap() < 0.0);
+
+    let stream = chain.into_inner().unwrap();
+    assert_eq!(stream.buffers_queued(), 0);
+}
+
+#[test]
+fn context_creation_stress() {
+    use std::sync::Arc;
+
+    let a = load_alto();
+    let dev = Arc::new(a.open(None).unwrap());
+
+    let workers: Vec<_> = (0..4).map(|t| {
+        let dev = dev.clone();
+        thread::spawn(move || {
+            let gain = 0.1 + t as f32 * 0.2;
+            for _ in 0..50 {
+                let ctx = dev.new_context(None).unwrap();
+                let mut src = ctx.new_static_source().unwrap();
+                src.set_gain(gain).unwrap();
+                assert_eq!(src.gain(), gain);
+                assert!(ctx.with_current(|| ctx.as_raw()).unwrap() == ctx.as_raw());
+            }
+        })
+    }).collect();
+
+    for worker in workers {
+        worker.join().unwrap();
+    }
+}
+
+#[test]
+fn convert_static_streaming() {
+    use std::sync::Arc;
+    use alto::{Mono, SourceState};
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+    let buf = Arc::new(ctx.new_buffer(vec![Mono{center: 0i16}; 4410], 44100).unwrap());
+
+    let mut src = ctx.new_static_source().unwrap();
+    src.set_buffer(buf.clone()).unwrap();
+    src.set_relative(true);
+    src.set_gain(0.5).unwrap();
+    src.set_pitch(1.5).unwrap();
+    src.set_position([1.0, 2.0, 3.0]).unwrap();
+    src.set_cone_outer_gain(0.25).unwrap();
+    src.play();
+    let raw = src.as_raw();
+    let mut snapshot = src.snapshot().unwrap();
+    snapshot.sample_offset = 0;
+
+    let mut stream = src.into_streaming().unwrap();
+    assert_eq!(stream.as_raw(), raw);
+    assert_eq!(stream.state(), SourceState::Stopped);
+    assert_eq!(stream.snapshot().unwrap(), snapshot);
+    stream.queue_buffer(ctx.new_buffer(vec![Mono{center: 0i16}; 441], 44100).unwrap()).unwrap();
+    assert_eq!(stream.buffers_queued(), 1);
+
+    let src = stream.into_static(buf.clone()).unwrap();
+    assert_eq!(src.as_raw(), raw);
+    assert_eq!(src.buffer().map(|b| b.as_raw()), Some(buf.as_raw()));
+    assert_eq!(src.snapshot().unwrap(), snapshot);
+}
+
+#[test]
+fn source_mute() {
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+    let mut src = ctx.new_static_source().unwrap();
+
+    src.set_gain(0.5).unwrap();
+    assert!(!src.is_muted());
+    src.set_muted(true).unwrap();
+    src.set_muted(true).unwrap();
+    assert!(src.is_muted());
+    assert_eq!(src.gain(), 0.5);
+    assert_eq!(src.snapshot().unwrap().gain, 0.5);
+
+    src.set_gain(0.75).unwrap();
+    match src.set_gain(-1.0) {
+        Err(AltoError::InvalidValue) => (),
+        r => panic!("{:?}", r),
+    }
+    src.set_muted(false).unwrap();
+    assert!(!src.is_muted());
+    assert_eq!(src.gain(), 0.75);
+}
+
+#[test]
+fn set_buffer_states() {
+    use std::sync::Arc;
+    use alto::{Mono, SourceState};
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+    let buf = Arc::new(ctx.new_buffer(vec![Mono{center: 0i16}; 44100], 44100).unwrap());
+    let other = Arc::new(ctx.new_buffer(vec![Mono{center: 0i16}; 4410], 44100).unwrap());
+
+    let mut src = ctx.new_static_source().unwrap();
+    assert_eq!(src.state(), SourceState::Initial);
+    src.set_buffer(buf.clone()).unwrap();
+
+    src.play();
+    match src.set_buffer(other.clone()) {
+        Err(AltoError::SourcePlaying("set_buffer")) => (),
+        r => panic!("{:?}", r),
+    }
+    assert_eq!(src.buffer().map(|b| b.as_raw()), Some(buf.as_raw()));
+
+    src.pause();
+    match src.set_buffer(other.clone()) {
+        Err(AltoError::SourcePlaying(..)) => (),
+        r => panic!("{:?}", r),
+    }
+
+    src.stop();
+    src.set_buffer(other.clone()).unwrap();
+
+    src.play();
+    src.set_buffer_stopping(buf.clone(), true).unwrap();
+    assert_eq!(src.buffer().map(|b| b.as_raw()), Some(buf.as_raw()));
+    assert_eq!(src.state(), SourceState::Playing);
+    src.set_buffer_stopping(other.clone(), false).unwrap();
+    assert_eq!(src.state(), SourceState::Stopped);
+}
+
+#[test]
+fn callback_buffer() {
+    use std::sync::Arc;
+    use alto::Mono;
+    use alto::ext::{Al, AlcNull};
+
+    let a = load_alto();
+    if !a.is_extension_present(AlcNull::SoftLoopback) {
+        return;
+    }
+    let mut dev = a.open_loopback::<Stereo<f32>>(None).unwrap();
+    let ctx = dev.new_context(44100, None).unwrap();
+    if !ctx.is_extension_present(Al::SoftCallbackBuffer) {
+        match ctx.new_callback_buffer(44100, |_: &mut [Mono<f32>]| 0) {
+            Err(AltoError::ExtensionNotPresent) => (),
+            r => panic!("{:?}", r.map(|_| ())),
+        }
+        return;
+    }
+
+    let mut remaining = 2000;
+    let buf = Arc::new(ctx.new_callback_buffer(44100, move |out: &mut [Mono<f32>]| {
+        let n = std::cmp::min(out.len(), remaining);
+        for f in &mut out[..n] {
+            f.center = 0.5;
+        }
+        remaining -= n;
+        n
+    }).unwrap());
+
+    let mut src = ctx.new_static_source().unwrap();
+    src.set_callback_buffer(buf.clone()).unwrap();
+    assert!(buf.is_attached());
+    let mut other = ctx.new_static_source().unwrap();
+    match other.set_callback_buffer(buf.clone()) {
+        Err(AltoError::InvalidOperation) => (),
+        r => panic!("{:?}", r),
+    }
+
+    src.play();
+    let mut chunk = vec![Stereo{left: 0.0f32, right: 0.0}; 4096];
+    dev.soft_render_samples(&mut chunk[..]);
+    let audible = chunk.iter().filter(|f| f.left.abs() > 0.05).count();
+    assert!((audible as i64 - 2000).abs() < 256, "{}", audible);
+
+    src.stop();
+    src.clear_buffer();
+    assert!(!buf.is_attached());
+    other.set_callback_buffer(buf.clone()).unwrap();
+    drop(other);
+    assert!(!buf.is_attached());
+
+    let api = a.raw_api();
+    let mut id = 0;
+    unsafe { api.alGenSources(1, &mut id); }
+    let mut src = match unsafe { ctx.adopt_raw_source(id, alto::SourceKind::Static, false) }.unwrap() {
+        alto::AnySource::Static(src) => src,
+        alto::AnySource::Streaming(..) => panic!(),
+    };
+    src.set_callback_buffer(buf).unwrap();
+    src.play();
+    drop(src);
+    let mut value = 0;
+    unsafe { api.alGetSourcei(id, alto::sys::AL_BUFFER, &mut value); }
+    assert_eq!(value, 0);
+    dev.soft_render_samples(&mut chunk[..]);
+    unsafe { api.alDeleteSources(1, &mut id); }
+}
+
+#[test]
+fn capture_overrun() {
+    use std::time::Duration;
+    use alto::Mono;
+
+    let a = load_alto();
+    if a.default_capture().is_none() {
+        return;
+    }
+    let mut cap = match a.open_capture::<Mono<i16>>(None, 44100, 441) {
+        Ok(cap) => cap,
+        Err(_) => return,
+    };
+    cap.check_overrun().unwrap();
+
+    // Never drained, the ring buffer fills up however much larger than requested the backend made it,
+    // and is found full once it has stopped growing between two checks.
+    cap.start();
+    let mut lost = None;
+    for _ in 0..100 {
+        thread::sleep(Duration::from_millis(50));
+        match cap.check_overrun() {
+            Ok(()) => (),
+            Err(AltoError::CaptureOverrun(n)) => {
+                lost = Some(n);
+                break;
+            },
+            Err(e) => panic!("{:?}", e),
+        }
+    }
+    if cap.samples_len() <= 0 {
+        return;
+    }
+    assert!(lost.unwrap() > 0);
+
+    let mut data = vec![Mono{center: 0i16}; 441];
+    cap.capture_samples(&mut data[..]).unwrap();
+    cap.stop();
+    assert!(cap.dropped_samples() > 0);
+}
+
+#[test]
+fn send_sync() {
+    use alto::{Buffer, Context, LoopbackDevice, OutputDevice, StaticSource, StreamingSource};
+
+    fn assert_send<T: Send>() { }
+    fn assert_sync<T: Sync>() { }
+
+    assert_send::<Alto>();
+    assert_sync::<Alto>();
+    assert_send::<OutputDevice>();
+    assert_sync::<OutputDevice>();
+    assert_send::<LoopbackDevice<Stereo<i16>>>();
+    assert_sync::<LoopbackDevice<Stereo<i16>>>();
+    assert_send::<MyCapture>();
+    assert_send::<Context>();
+    assert_sync::<Context>();
+    assert_send::<Buffer>();
+    assert_sync::<Buffer>();
+    assert_send::<StaticSource>();
+    assert_sync::<StaticSource>();
+    assert_send::<StreamingSource>();
+    assert_sync::<StreamingSource>();
+}
+
+#[derive(Copy, Clone)]
+struct Vec3 {
+    x: f32,
+    y: f32,
+    z: f32,
+}
+
+impl alto::AsAlVec3 for Vec3 {
+    fn as_al_vec3(&self) -> [f32; 3] { [self.x, self.y, self.z] }
+}
+
+#[derive(Clone)]
+struct HeapVec3(Vec<f32>);
+
+impl From<HeapVec3> for [f32; 3] {
+    fn from(v: HeapVec3) -> [f32; 3] { [v.0[0], v.0[1], v.0[2]] }
+}
+
+#[test]
+fn as_al_vec3() {
+    use alto::AsAlVec3;
+
+    assert_eq!([1.0f32, 2.0, 3.0].as_al_vec3(), [1.0, 2.0, 3.0]);
+    assert_eq!((1.0f32, 2.0f32, 3.0f32).as_al_vec3(), [1.0, 2.0, 3.0]);
+    assert_eq!(Vec3{x: 1.0, y: 2.0, z: 3.0}.as_al_vec3(), [1.0, 2.0, 3.0]);
+    assert_eq!(HeapVec3(vec![1.0, 2.0, 3.0]).as_al_vec3(), [1.0, 2.0, 3.0]);
+}
+
+#[cfg(feature = "mint")]
+#[test]
+fn as_al_vec3_mint() {
+    use alto::AsAlVec3;
+
+    assert_eq!(mint::Vector3{x: 1.0f32, y: 2.0, z: 3.0}.as_al_vec3(), [1.0, 2.0, 3.0]);
+    assert_eq!(mint::Point3{x: 1.0f32, y: 2.0, z: 3.0}.as_al_vec3(), [1.0, 2.0, 3.0]);
+}
+
+#[test]
+fn spatial_setters_accept_vectors() {
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+    let mut src = ctx.new_static_source().unwrap();
+
+    src.set_position((1.0, 2.0, 3.0)).unwrap();
+    assert_eq!(src.position::<[f32; 3]>(), [1.0, 2.0, 3.0]);
+    src.set_velocity(Vec3{x: 4.0, y: 5.0, z: 6.0}).unwrap();
+    assert_eq!(src.velocity::<[f32; 3]>(), [4.0, 5.0, 6.0]);
+    ctx.set_position((0.0, 1.0, 0.0)).unwrap();
+    ctx.set_orientation((Vec3{x: 0.0, y: 0.0, z: -1.0}, Vec3{x: 0.0, y: 1.0, z: 0.0})).unwrap();
+}
+
+#[test]
+fn on_buffers_processed() {
+    use std::sync::mpsc;
+    use std::time::Duration;
+    use alto::{Mono, Source};
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+    let mut stream = ctx.new_streaming_source().unwrap();
+    let (tx, rx) = mpsc::channel();
+    match stream.on_buffers_processed(move |n| { let _ = tx.send(n); }) {
+        Ok(()) => (),
+        Err(AltoError::ExtensionNotPresent) => return,
+        Err(e) => panic!("{:?}", e),
+    }
+
+    for _ in 0..2 {
+        stream.queue_buffer(ctx.new_buffer(vec![Mono{center: 0i16}; 2205], 44100).unwrap()).unwrap();
+    }
+    stream.play();
+    let mut completed = 0;
+    while completed < 2 {
+        completed += rx.recv_timeout(Duration::from_secs(2)).unwrap();
+    }
+    assert!(stream.drain(Some(Duration::from_secs(2))).unwrap());
+    assert_eq!(stream.buffers_processed(), 2);
+
+    stream.clear_buffers_processed();
+    assert!(rx.recv_timeout(Duration::from_millis(10)).is_err());
+}
+
+#[test]
+fn loopback_formats() {
+    use alto::{LoopbackFormatChannels, LoopbackFormatType, Stereo};
+
+    let a = load_alto();
+    let formats = match a.loopback_formats(44100) {
+        Ok(formats) => formats,
+        Err(AltoError::ExtensionNotPresent) => return,
+        Err(e) => panic!("{:?}", e),
+    };
+    assert!(formats.contains(&(LoopbackFormatChannels::Stereo, LoopbackFormatType::I16)));
+
+    let dev = a.open_loopback::<Stereo<i16>>(None).unwrap();
+    for &(channels, ty) in &formats {
+        assert!(dev.is_render_format_supported(44100, channels, ty).unwrap());
+    }
+}
+
+#[test]
+fn loopback_frequencies() {
+    use alto::{LoopbackFormatChannels, LoopbackFormatType, Stereo};
+    use alto::ext::AlcNull;
+
+    let a = load_alto();
+    if !a.is_extension_present(AlcNull::SoftLoopback) {
+        return;
+    }
+    let dev = a.open_loopback::<Stereo<i16>>(None).unwrap();
+    assert!(!dev.is_render_format_supported(0, LoopbackFormatChannels::Stereo, LoopbackFormatType::I16).unwrap());
+
+    let freqs = dev.supported_frequencies(LoopbackFormatChannels::Stereo, LoopbackFormatType::I16);
+    assert!(freqs.contains(&44100));
+    assert!(freqs.windows(2).all(|w| w[0] < w[1]));
+    for &freq in &freqs {
+        assert!(dev.is_render_format_supported(freq, LoopbackFormatChannels::Stereo, LoopbackFormatType::I16).unwrap());
+    }
+}
+
+#[test]
+fn source_type() {
+    use std::sync::Arc;
+    use alto::{Mono, Source, SourceType};
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+    let buf = Arc::new(ctx.new_buffer(vec![Mono{center: 0i16}; 441], 44100).unwrap());
+
+    let mut src = ctx.new_static_source().unwrap();
+    assert_eq!(src.source_type().unwrap(), SourceType::Undetermined);
+    src.set_buffer(buf.clone()).unwrap();
+    assert_eq!(src.source_type().unwrap(), SourceType::Static);
+
+    let mut stream = src.into_streaming().unwrap();
+    assert_eq!(stream.source_type().unwrap(), SourceType::Undetermined);
+    stream.queue_buffer(ctx.new_buffer(vec![Mono{center: 0i16}; 441], 44100).unwrap()).unwrap();
+    assert_eq!(stream.source_type().unwrap(), SourceType::Streaming);
+
+    let src = stream.into_static(buf).unwrap();
+    assert_eq!(src.source_type().unwrap(), SourceType::Static);
+}
+
+#[test]
+fn play_once() {
+    use std::time::Duration;
+    use alto::{Mono, PlayOncePolicy, PlayParams};
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+    let long = ctx.new_buffer(vec![Mono{center: 0i16}; 44100], 44100).unwrap();
+    let short = ctx.new_buffer(vec![Mono{center: 0i16}; 441], 44100).unwrap();
+    assert_eq!(ctx.play_once_limit(), (16, PlayOncePolicy::StealOldest));
+
+    ctx.set_play_once_limit(2, PlayOncePolicy::StealOldest).unwrap();
+    let first = ctx.play_once(&long, PlayParams::default()).unwrap();
+    let second = ctx.play_once(&long, PlayParams{gain: 0.5, pitch: 2.0, position: Some([1.0, 0.0, 0.0])}).unwrap();
+    assert!(ctx.is_playing_once(first));
+    let third = ctx.play_once(&long, PlayParams::default()).unwrap();
+    assert!(!ctx.is_playing_once(first));
+    assert!(ctx.is_playing_once(second));
+    assert!(ctx.is_playing_once(third));
+
+    ctx.set_play_once_limit(2, PlayOncePolicy::Fail).unwrap();
+    match ctx.play_once(&short, PlayParams::default()) {
+        Err(AltoError::VoicesExhausted) => (),
+        r => panic!("{:?}", r),
+    }
+    assert!(ctx.stop_once(second).unwrap());
+    assert!(!ctx.stop_once(second).unwrap());
+    let token = ctx.play_once(&short, PlayParams::default()).unwrap();
+    assert!(ctx.is_playing_once(token));
+
+    match ctx.play_once(&short, PlayParams{gain: -1.0, ..PlayParams::default()}) {
+        Err(AltoError::InvalidValue) => (),
+        r => panic!("{:?}", r),
+    }
+    let other = dev.new_context(None).unwrap();
+    let shared = other.new_buffer(vec![Mono{center: 0i16}; 441], 44100).unwrap();
+    match ctx.play_once(&shared, PlayParams::default()) {
+        Err(AltoError::WrongContext) => (),
+        r => panic!("{:?}", r),
+    }
+
+    // Dropping a buffer stops the sounds playing it.
+    drop(long);
+    assert!(!ctx.is_playing_once(third));
+
+    ::std::thread::sleep(Duration::from_millis(50));
+    assert_eq!(ctx.reap().unwrap().sources, 1);
+}
+
+#[test]
+fn device_connected() {
+    use std::sync::Arc;
+    use alto::{Mono, Source, Stereo};
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+    match ctx.device_connected() {
+        Ok(connected) => assert!(connected),
+        Err(AltoError::ExtensionNotPresent) => return,
+        Err(e) => panic!("{:?}", e),
+    }
+
+    // A loopback device has no hardware to lose, so it stays connected while it renders.
+    let dev = match a.open_loopback::<Stereo<i16>>(None) {
+        Ok(dev) => dev,
+        Err(AltoError::ExtensionNotPresent) => return,
+        Err(e) => panic!("{:?}", e),
+    };
+    let ctx = dev.new_context(44100, None).unwrap();
+    let mut src = ctx.new_static_source().unwrap();
+    src.set_buffer(Arc::new(ctx.new_buffer(vec![Mono{center: 0i16}; 441], 44100).unwrap())).unwrap();
+    src.play();
+    assert!(ctx.device_connected().unwrap());
+
+    // Errors on a connected device keep their AL meaning.
+    match src.set_gain(-1.0) {
+        Err(AltoError::InvalidValue) => (),
+        r => panic!("{:?}", r),
+    }
+}
+
+#[test]
+fn buffer_length() {
+    use alto::{Mono, Stereo};
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+    let mono = ctx.new_buffer(vec![Mono{center: 0i16}; 441], 44100).unwrap();
+    assert_eq!(mono.sample_length(), 441);
+    assert!((mono.sec_length() - 0.01).abs() < 1e-4);
+
+    let stereo = ctx.new_buffer(vec![Stereo{left: 0u8, right: 0u8}; 22050], 22050).unwrap();
+    assert_eq!(stereo.sample_length(), 22050);
+    assert!((stereo.sec_length() - 1.0).abs() < 1e-4);
+}
+
+#[test]
+fn static_loop_region() {
+    use std::sync::Arc;
+    use alto::{AltoError, Mono, Source};
+    use alto::ext::{Al, AlcNull};
+
+    let a = load_alto();
+    if !a.is_extension_present(AlcNull::SoftLoopback) {
+        return;
+    }
+    let mut dev = a.open_loopback::<Stereo<f32>>(None).unwrap();
+    let ctx = dev.new_context(44100, None).unwrap();
+    if !ctx.is_extension_present(Al::SoftLoopPoints) {
+        return;
+    }
+
+    // An intro of 100 frames, a loop region of 200 frames, and a tail that must never be heard.
+    let mut data = vec![Mono{center: 0.25f32}; 100];
+    data.extend(vec![Mono{center: 0.5f32}; 200]);
+    data.extend(vec![Mono{center: -0.5f32}; 700]);
+    let buf = Arc::new(ctx.new_buffer(data, 44100).unwrap());
+
+    let mut src = ctx.new_static_source().unwrap();
+    match src.set_loop_region(100, 300) {
+        Err(AltoError::InvalidOperation) => (),
+        r => panic!("{:?}", r),
+    }
+    src.set_buffer(buf.clone()).unwrap();
+    assert_eq!(src.loop_region(), None);
+    match src.set_loop_region(300, 100) {
+        Err(AltoError::InvalidValue) => (),
+        r => panic!("{:?}", r),
+    }
+    match src.set_loop_region(0, 1001) {
+        Err(AltoError::InvalidValue) => (),
+        r => panic!("{:?}", r),
+    }
+    src.set_loop_region(100, 300).unwrap();
+    assert_eq!(src.loop_region(), Some((100, 300)));
+    assert!(src.looping());
+    src.play();
+
+    let mut chunk = vec![Stereo{left: 0.0f32, right: 0.0}; 1000];
+    dev.soft_render_samples(&mut chunk[..]);
+    let intro = chunk[50].left;
+    let body = chunk[200].left;
+    assert!(body > intro * 1.5);
+    // The loop wraps from frame 300 back to frame 100, so the loop level continues past it.
+    for (i, frame) in chunk.iter().enumerate().skip(110) {
+        assert!((frame.left - body).abs() < 0.01, "frame {} left the loop region", i);
+    }
+
+    // Changing the region while playing keeps the source playing.
+    src.set_loop_region(0, 300).unwrap();
+    assert_eq!(src.state(), alto::SourceState::Playing);
+    src.clear_loop_region().unwrap();
+    assert_eq!(src.loop_region(), None);
+
+    // A refused change leaves the source playing where it was.
+    let mut other = ctx.new_static_source().unwrap();
+    other.set_buffer(buf.clone()).unwrap();
+    dev.soft_render_samples(&mut chunk[..]);
+    let offset = src.sample_offset();
+    match src.set_loop_region(100, 300) {
+        Err(AltoError::BufferInUse) => (),
+        r => panic!("{:?}", r),
+    }
+    assert_eq!(src.state(), alto::SourceState::Playing);
+    assert_eq!(src.sample_offset(), offset);
+}
+
+#[test]
+fn source_attenuation() {
+    use alto::{Attenuation, Source};
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+    let mut src = ctx.new_static_source().unwrap();
+
+    src.set_attenuation(Attenuation::Vehicle).unwrap();
+    assert_eq!(src.attenuation(), Attenuation::Custom{reference: 4.0, rolloff: 1.0, max: 200.0});
+    assert_eq!(src.attenuation().values(), Attenuation::Vehicle.values());
+
+    let custom = Attenuation::Custom{reference: 2.0, rolloff: 0.25, max: 30.0};
+    src.set_attenuation(custom).unwrap();
+    assert_eq!(src.attenuation(), custom);
+    let tooling = src.attenuation();
+    src.set_attenuation(Attenuation::SmallProp).unwrap();
+    src.set_attenuation(tooling).unwrap();
+    assert_eq!(src.attenuation(), custom);
+
+    match src.set_attenuation(Attenuation::Custom{reference: 10.0, rolloff: 1.0, max: 5.0}) {
+        Err(AltoError::InvalidValue) => (),
+        r => panic!("{:?}", r),
+    }
+    match src.set_attenuation(Attenuation::Custom{reference: 1.0, rolloff: -1.0, max: 5.0}) {
+        Err(AltoError::InvalidValue) => (),
+        r => panic!("{:?}", r),
+    }
+    assert_eq!(src.attenuation(), custom);
+}
+
+#[test]
+fn capture_open_errors() {
+    use std::ffi::CString;
+    use alto::Mono;
+
+    let a = load_alto();
+    match a.open_capture::<Mono<i16>>(None, 0, 1024) {
+        Err(AltoError::InvalidValue) => (),
+        r => panic!("{:?}", r.map(|_| ())),
+    }
+    let missing = CString::new("no such capture device").unwrap();
+    match a.open_capture::<Mono<i16>>(Some(&missing), 44100, 1024) {
+        Err(AltoError::InvalidDevice) => (),
+        r => panic!("{:?}", r.map(|_| ())),
+    }
+    if a.default_capture().is_none() {
+        match a.open_capture::<Mono<i16>>(None, 44100, 1024) {
+            Err(AltoError::InvalidDevice) => (),
+            r => panic!("{:?}", r.map(|_| ())),
+        }
+    }
+
+    // Whatever the backend supports, a refused format names the attempted parameters.
+    for &freq in &[48000, 16000, 8000] {
+        match a.open_capture::<Mono<i16>>(None, freq, 1024) {
+            Ok(..) | Err(AltoError::InvalidDevice) => (),
+            Err(AltoError::CaptureFormatUnsupported{freq: f, format}) => {
+                assert_eq!(f, freq);
+                assert_eq!(format, alto::Format::Standard(alto::StandardFormat::MonoI16));
+            },
+            Err(e) => panic!("{:?}", e),
+        }
+    }
+}
+
+#[test]
+fn load_default_on_thread() {
+    use std::thread;
+
+    let a = thread::spawn(Alto::load_default).join().unwrap().unwrap();
+    let b = a.clone();
+    thread::spawn(move || drop(b.open(None))).join().unwrap();
+    a.open(None).unwrap();
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn load_default_async() {
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake};
+    use std::thread::{self, Thread};
+
+    struct Unpark(Thread);
+    impl Wake for Unpark {
+        fn wake(self: Arc<Self>) { self.0.unpark(); }
+    }
+
+    let waker = Arc::new(Unpark(thread::current())).into();
+    let mut cx = Context::from_waker(&waker);
+    let mut load = Alto::load_default_async();
+    let a = loop {
+        match Pin::new(&mut load).poll(&mut cx) {
+            Poll::Ready(a) => break a.unwrap(),
+            Poll::Pending => thread::park(),
+        }
+    };
+    a.open(None).unwrap();
+}
+
+#[test]
+fn playback_position() {
+    use std::sync::Arc;
+    use std::time::Duration;
+    use alto::{Mono, Source};
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+    let mut src = ctx.new_static_source().unwrap();
+    assert_eq!(src.playback_position().unwrap(), Duration::from_secs(0));
+
+    src.set_buffer(Arc::new(ctx.new_buffer(vec![Mono{center: 0i16}; 44100], 44100).unwrap())).unwrap();
+    src.play();
+    src.pause();
+    src.set_sample_offset(22050).unwrap();
+    assert_eq!(src.playback_position().unwrap(), Duration::from_millis(500));
+
+    let mut stream = ctx.new_streaming_source().unwrap();
+    stream.queue_buffer(ctx.new_buffer(vec![Mono{center: 0i16}; 4410], 44100).unwrap()).unwrap();
+    stream.queue_buffer(ctx.new_buffer(vec![Mono{center: 0i16}; 2205], 22050).unwrap()).unwrap();
+    stream.play();
+    assert!(stream.drain(Some(Duration::from_secs(2))).unwrap());
+    stream.unqueue_buffer().unwrap();
+    stream.unqueue_buffer().unwrap();
+    assert_eq!(stream.playback_position().unwrap(), Duration::from_millis(200));
+
+    stream.reset_playback_position();
+    assert_eq!(stream.playback_position().unwrap(), Duration::from_secs(0));
+}
+
+#[test]
+fn max_auxiliary_sends() {
+    use alto::Source;
+    use alto::ext::Alc;
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+    let mut src = ctx.new_static_source().unwrap();
+    if !dev.is_extension_present(Alc::Efx) {
+        assert!(matches!(src.max_auxiliary_sends(), Err(AltoError::ExtensionNotPresent)));
+        return;
+    }
+
+    let max = src.max_auxiliary_sends().unwrap();
+    assert_eq!(max, ctx.max_aux_sends().unwrap());
+    let mut slot = ctx.new_aux_effect_slot().unwrap();
+    for send in 0..max {
+        src.set_aux_send(send, &mut slot).unwrap();
+    }
+    match src.set_aux_send(max, &mut slot) {
+        Err(AltoError::SendOutOfRange{index, max: m}) => assert_eq!((index, m), (max, max)),
+        r => panic!("{:?}", r),
+    }
+    assert!(matches!(src.set_aux_send(-1, &mut slot), Err(AltoError::SendOutOfRange{..})));
+}
+
+#[test]
+fn sample_frame_sizes() {
+    use std::mem;
+    use alto::{ALawSample, BFormat2D, BFormat3D, Mc51Chn, Mc61Chn, Mc71Chn, McQuad, McRear, Mono, MuLawSample, SampleFrame};
+    use alto::{ExtALawFormat, ExtBFormat, ExtDoubleFormat, ExtFloat32Format, ExtMcFormat, ExtMuLawBFormat, ExtMuLawFormat, ExtMuLawMcFormat, Format, StandardFormat};
+
+    // Channel counts of the format tokens, independent of the frame types that resolve to them.
+    fn format_channels(format: Format) -> usize {
+        match format {
+            Format::Standard(StandardFormat::MonoU8) | Format::Standard(StandardFormat::MonoI16) => 1,
+            Format::Standard(StandardFormat::StereoU8) | Format::Standard(StandardFormat::StereoI16) => 2,
+            Format::ExtALaw(ExtALawFormat::Mono) | Format::ExtDouble(ExtDoubleFormat::Mono) => 1,
+            Format::ExtFloat32(ExtFloat32Format::Mono) | Format::ExtMuLaw(ExtMuLawFormat::Mono) => 1,
+            Format::ExtALaw(ExtALawFormat::Stereo) | Format::ExtDouble(ExtDoubleFormat::Stereo) => 2,
+            Format::ExtFloat32(ExtFloat32Format::Stereo) | Format::ExtMuLaw(ExtMuLawFormat::Stereo) => 2,
+            Format::ExtMcFormats(ExtMcFormat::RearU8) | Format::ExtMcFormats(ExtMcFormat::RearI16) => 2,
+            Format::ExtMcFormats(ExtMcFormat::RearF32) | Format::ExtMuLawMcFormats(ExtMuLawMcFormat::Rear) => 2,
+            Format::ExtMcFormats(ExtMcFormat::QuadU8) | Format::ExtMcFormats(ExtMcFormat::QuadI16) => 4,
+            Format::ExtMcFormats(ExtMcFormat::QuadF32) | Format::ExtMuLawMcFormats(ExtMuLawMcFormat::Quad) => 4,
+            Format::ExtMcFormats(ExtMcFormat::Mc51ChnU8) | Format::ExtMcFormats(ExtMcFormat::Mc51ChnI16) => 6,
+            Format::ExtMcFormats(ExtMcFormat::Mc51ChnF32) | Format::ExtMuLawMcFormats(ExtMuLawMcFormat::Mc51Chn) => 6,
+            Format::ExtMcFormats(ExtMcFormat::Mc61ChnU8) | Format::ExtMcFormats(ExtMcFormat::Mc61ChnI16) => 7,
+            Format::ExtMcFormats(ExtMcFormat::Mc61ChnF32) | Format::ExtMuLawMcFormats(ExtMuLawMcFormat::Mc61Chn) => 7,
+            Format::ExtMcFormats(ExtMcFormat::Mc71ChnU8) | Format::ExtMcFormats(ExtMcFormat::Mc71ChnI16) => 8,
+            Format::ExtMcFormats(ExtMcFormat::Mc71ChnF32) | Format::ExtMuLawMcFormats(ExtMuLawMcFormat::Mc71Chn) => 8,
+            Format::ExtBFormat(ExtBFormat::B2DU8) | Format::ExtBFormat(ExtBFormat::B2DI16) => 3,
+            Format::ExtBFormat(ExtBFormat::B2DF32) | Format::ExtMuLawBFormat(ExtMuLawBFormat::B2D) => 3,
+            Format::ExtBFormat(ExtBFormat::B3DU8) | Format::ExtBFormat(ExtBFormat::B3DI16) => 4,
+            Format::ExtBFormat(ExtBFormat::B3DF32) | Format::ExtMuLawBFormat(ExtMuLawBFormat::B3D) => 4,
+            f => panic!("no frame type resolves to {:?}", f),
+        }
+    }
+    fn check<F: SampleFrame>() {
+        assert_eq!(F::CHANNELS, format_channels(F::format()), "{:?}", F::format());
+        assert_eq!(F::len(), F::CHANNELS);
+        assert_eq!(F::bytes_per_frame(), F::CHANNELS * mem::size_of::<F::Sample>());
+        assert_eq!(mem::size_of::<F>(), F::bytes_per_frame());
+    }
+    macro_rules! check_all {
+        ($($frame:ident),*) => {
+            $(
+                check::<$frame<u8>>();
+                check::<$frame<i16>>();
+                check::<$frame<f32>>();
+                check::<$frame<MuLawSample>>();
+            )*
+        };
+    }
+
+    check_all!(Mono, Stereo, McRear, McQuad, Mc51Chn, Mc61Chn, Mc71Chn, BFormat2D, BFormat3D);
+    check::<Mono<f64>>();
+    check::<Mono<ALawSample>>();
+    check::<Stereo<f64>>();
+    check::<Stereo<ALawSample>>();
+    assert_eq!(McRear::<i16>::bytes_per_frame(), 4);
+}
+
+#[test]
+fn source_handle() {
+    use std::thread;
+    use alto::Source;
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+    let mut src = ctx.new_static_source().unwrap();
+    let handle = src.handle();
+    assert!(handle.is_alive());
+    assert!(matches!(handle.set_gain(-1.0), Err(AltoError::InvalidValue)));
+
+    let remote = handle.clone();
+    thread::spawn(move || {
+        remote.set_gain(0.25).unwrap();
+        remote.set_gain(0.5).unwrap();
+        remote.set_position([1.0, 2.0, 3.0]).unwrap();
+    }).join().unwrap();
+    assert_eq!(src.gain(), 1.0);
+    assert_eq!(ctx.apply_pending(), 3);
+    assert_eq!(src.gain(), 0.5);
+    assert_eq!(src.position::<[f32; 3]>(), [1.0, 2.0, 3.0]);
+    assert_eq!(ctx.apply_pending(), 0);
+
+    src.set_gain(1.0).unwrap();
+    handle.set_gain(0.75).unwrap();
+    drop(src);
+    assert!(!handle.is_alive());
+    assert_eq!(ctx.apply_pending(), 0);
+    handle.stop();
+    assert_eq!(ctx.apply_pending(), 0);
+}
+
+#[test]
+fn device_close() {
+    use alto::{DeviceObject, Mono};
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+    match dev.to_device().close() {
+        Err(AltoError::InvalidOperation) => (),
+        r => panic!("{:?}", r),
+    }
+    drop(ctx);
+    dev.close().unwrap();
+
+    if let Ok(capture) = a.open_capture::<Mono<i16>>(None, 44100, 1024) {
+        capture.close().unwrap();
+    }
+}
+
+#[test]
+fn stop_all_sources() {
+    use std::sync::Arc;
+    use alto::{Mono, Source, SourceState};
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+    let buf = Arc::new(ctx.new_buffer(vec![Mono{center: 0i16}; 44100], 44100).unwrap());
+    let mut playing = ctx.new_static_source().unwrap();
+    playing.set_buffer(buf.clone()).unwrap();
+    playing.set_gain(0.5).unwrap();
+    playing.play();
+    let idle = ctx.new_static_source().unwrap();
+    let dropped = ctx.new_static_source().unwrap();
+    drop(dropped);
+
+    let mut infos = Vec::new();
+    ctx.for_each_source(|info| infos.push(info));
+    assert_eq!(infos.len(), 2);
+    let info = infos.iter().find(|i| i.id == playing.as_raw()).unwrap();
+    assert_eq!((info.state, info.gain), (SourceState::Playing, 0.5));
+    assert!(infos.iter().any(|i| i.id == idle.as_raw() && i.state == SourceState::Initial));
+
+    assert_eq!(ctx.stop_all_sources().unwrap(), 1);
+    assert_eq!(playing.state(), SourceState::Stopped);
+    assert_eq!(ctx.stop_all_sources().unwrap(), 0);
+}
+
+#[test]
+fn reap_budget() {
+    use std::sync::Arc;
+    use std::time::Duration;
+    use alto::{Mono, PlayParams, ReapBudget, Source};
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+    let short = ctx.new_buffer(vec![Mono{center: 0i16}; 441], 44100).unwrap();
+    for _ in 0..3 {
+        ctx.play_once(&short, PlayParams::default()).unwrap();
+    }
+
+    // A stop fade whose source stops on its own is finalized by restoring the gain.
+    let mut src = ctx.new_static_source().unwrap();
+    src.set_buffer(Arc::new(ctx.new_buffer(vec![Mono{center: 0i16}; 441], 44100).unwrap())).unwrap();
+    src.set_gain(0.5).unwrap();
+    src.play();
+    src.stop_with_fade(Duration::from_secs(10)).unwrap();
+    ::std::thread::sleep(Duration::from_millis(100));
+
+    let stats = ctx.reap_within(ReapBudget{max_items: Some(2), max_time: None}).unwrap();
+    assert_eq!((stats.sources, stats.buffers, stats.fades, stats.complete), (2, 0, 0, false));
+    let stats = ctx.reap().unwrap();
+    assert_eq!((stats.sources, stats.buffers, stats.fades, stats.complete), (1, 1, 1, true));
+    assert_eq!(src.gain(), 0.5);
+    assert_eq!(ctx.reap().unwrap().sources, 0);
+}
+
+#[test]
+fn capture_drop() {
+    use std::ptr;
+    use alto::Mono;
+
+    let a = load_alto();
+    let capture = match a.open_capture::<Mono<i16>>(None, 44100, 1024) {
+        Ok(capture) => capture,
+        Err(..) => return,
+    };
+    a.clear_alc_error(None);
+    drop(capture);
+    a.get_error(ptr::null_mut()).unwrap();
+}
+
+#[test]
+fn volume_slider() {
+    use alto::{gain_from_slider, slider_from_gain};
+
+    assert_eq!(gain_from_slider(0.0), 0.0);
+    assert_eq!(gain_from_slider(1.0), 1.0);
+    assert_eq!(gain_from_slider(2.0), 1.0);
+    assert_eq!(gain_from_slider(-1.0), 0.0);
+    assert_eq!(gain_from_slider(::std::f32::NAN), 0.0);
+    assert!((gain_from_slider(0.5) - 0.031_622_78).abs() < 1e-6);
+    assert!((gain_from_slider(0.0001) - 0.001).abs() < 1e-5);
+
+    for &position in &[0.0, 0.1, 0.25, 0.5, 0.75, 1.0] {
+        assert!((slider_from_gain(gain_from_slider(position)) - position).abs() < 1e-5);
+    }
+    assert_eq!(slider_from_gain(0.0001), 0.0);
+    assert_eq!(slider_from_gain(4.0), 1.0);
+}
+
+#[test]
+fn source_update() {
+    use alto::Source;
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+    let mut src = ctx.new_static_source().unwrap();
+
+    src.update().position([1.0, 2.0, 3.0]).velocity([0.0, 0.0, -1.0]).gain(0.5).pitch(1.5).commit().unwrap();
+    assert_eq!(src.position::<[f32; 3]>(), [1.0, 2.0, 3.0]);
+    assert_eq!(src.velocity::<[f32; 3]>(), [0.0, 0.0, -1.0]);
+    assert_eq!(src.gain(), 0.5);
+    assert_eq!(src.pitch(), 1.5);
+
+    match src.update().position([4.0, 5.0, 6.0]).pitch(-1.0).commit() {
+        Err(AltoError::InvalidProperty{name: "AL_PITCH", cause}) => assert!(matches!(*cause, AltoError::InvalidValue)),
+        r => panic!("{:?}", r),
+    }
+    assert_eq!(src.position::<[f32; 3]>(), [1.0, 2.0, 3.0]);
+
+    // A muted source keeps the new gain for when it is unmuted.
+    src.set_muted(true).unwrap();
+    src.update().gain(0.25).commit().unwrap();
+    src.set_muted(false).unwrap();
+    assert_eq!(src.gain(), 0.25);
+
+    let defer = ctx.defer_updates();
+    src.update().gain(0.75).commit().unwrap();
+    assert!(ctx.is_suspended() == ctx.is_extension_present(alto::ext::Al::SoftDeferredUpdates));
+    drop(defer);
+
+    // An error raised by OpenAL names the property it was raised for and carries the AL error, unless the policy skips the check.
+    let api = a.raw_api();
+    let mut id = 0;
+    unsafe { api.alGenSources(1, &mut id); }
+    let mut gone = match unsafe { ctx.adopt_raw_source(id, alto::SourceKind::Static, false) }.unwrap() {
+        alto::AnySource::Static(src) => src,
+        alto::AnySource::Streaming(..) => panic!(),
+    };
+    unsafe { api.alDeleteSources(1, &mut id); }
+    match gone.update().position([1.0, 0.0, 0.0]).gain(0.5).commit() {
+        Err(AltoError::InvalidProperty{name: "AL_POSITION", cause}) => assert!(matches!(*cause, AltoError::InvalidName)),
+        r => panic!("{:?}", r),
+    }
+    ctx.set_error_checking(alto::ErrorChecking::Never);
+    gone.update().position([1.0, 0.0, 0.0]).commit().unwrap();
+}
+
+#[test]
+fn buffer_loop_points() {
+    use std::sync::Arc;
+    use alto::{Mono, PlayParams};
+    use alto::ext::Al;
+
+    let a = load_alto();
+    let dev = a.open(None).unwrap();
+    let ctx = dev.new_context(None).unwrap();
+    let buf = Arc::new(ctx.new_buffer(vec![Mono{center: 0i16}; 1000], 44100).unwrap());
+    if !ctx.is_extension_present(Al::SoftLoopPoints) {
+        assert!(matches!(buf.loop_points(), Err(AltoError::ExtensionNotPresent)));
+        return;
+    }
+
+    assert_eq!(buf.loop_points().unwrap(), (0, 1000));
+    buf.set_loop_points(100, 300).unwrap();
+    assert_eq!(buf.loop_points().unwrap(), (100, 300));
+    for &(start, end) in &[(-1, 300), (300, 300), (300, 100), (0, 1001)] {
+        match buf.set_loop_points(start, end) {
+            Err(AltoError::LoopPointsOutOfRange{start: s, end: e, len: 1000}) => assert_eq!((s, e), (start, end)),
+            r => panic!("{:?}", r),
+        }
+    }
+    assert_eq!(buf.loop_points().unwrap(), (100, 300));
+
+    // A sound started by `play_once` keeps the buffer attached until it is reaped.
+    let token = ctx.play_once(&buf, PlayParams::default()).unwrap();
+    match buf.set_loop_points(0, 1000) {
+        Err(AltoError::BufferInUse) => (),
+        r => panic!("{:?}", r),
+    }
+    ctx.stop_once(token).unwrap();
+    buf.set_loop_points(0, 1000).unwrap();
+
+    // The unchecked setter goes through the same checks, and a buffer shared with a source can still be changed.
+    match buf.set_soft_loop_points((0, 1001)) {
+        Err(AltoError::LoopPointsOutOfRange{..}) => (),
+        r => panic!("{:?}", r),
+    }
+    let mut src = ctx.new_static_source().unwrap();
+    src.set_buffer(buf.clone()).unwrap();
+    src.clear_buffer();
+    buf.set_loop_points(200, 400).unwrap();
+    assert_eq!(buf.loop_points().unwrap(), (200, 400));
+}